@@ -0,0 +1,79 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Message address helpers.
+
+use crate::{error::AbiError, token::TokenValue};
+
+use ever_block::{fail, MsgAddress, MsgAddressInt, Result};
+
+/// Converts `MsgAddress` into internal address. Fails for `addr_none` and external addresses.
+pub fn to_address_int(address: &MsgAddress) -> Result<MsgAddressInt> {
+    match address {
+        MsgAddress::AddrStd(std) => Ok(MsgAddressInt::AddrStd(std.clone())),
+        MsgAddress::AddrVar(var) => Ok(MsgAddressInt::AddrVar(var.clone())),
+        _ => fail!(AbiError::InvalidData {
+            msg: format!("Address {} is not an internal address", address)
+        }),
+    }
+}
+
+/// Converts internal address into `MsgAddress`
+pub fn from_address_int(address: &MsgAddressInt) -> MsgAddress {
+    match address {
+        MsgAddressInt::AddrStd(std) => MsgAddress::AddrStd(std.clone()),
+        MsgAddressInt::AddrVar(var) => MsgAddress::AddrVar(var.clone()),
+    }
+}
+
+/// Returns address without anycast info. Variable addresses which can be represented as std
+/// addresses (256 bit account id and 8 bit workchain) are converted into std form.
+pub fn normalize_address(address: &MsgAddress) -> Result<MsgAddress> {
+    match address {
+        MsgAddress::AddrStd(std) => {
+            MsgAddress::with_standart(None, std.workchain_id, std.address.clone())
+        }
+        MsgAddress::AddrVar(var) => {
+            if var.address.remaining_bits() == 256
+                && var.workchain_id >= i8::MIN as i32
+                && var.workchain_id <= i8::MAX as i32
+            {
+                MsgAddress::with_standart(None, var.workchain_id as i8, var.address.clone())
+            } else {
+                MsgAddress::with_variant(None, var.workchain_id, var.address.clone())
+            }
+        }
+        _ => Ok(address.clone()),
+    }
+}
+
+/// Compares addresses ignoring anycast info and std/var representation difference
+pub fn addresses_eq(left: &MsgAddress, right: &MsgAddress) -> Result<bool> {
+    Ok(normalize_address(left)? == normalize_address(right)?)
+}
+
+impl TokenValue {
+    /// Returns `TokenValue::Address` with normalized address. See `normalize_address`.
+    pub fn normalize_address(&self) -> Result<TokenValue> {
+        match self {
+            TokenValue::Address(address) => Ok(TokenValue::Address(normalize_address(address)?)),
+            _ => fail!(AbiError::InvalidData {
+                msg: format!("Token {} is not an address", self)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_address.rs"]
+mod tests;
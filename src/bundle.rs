@@ -0,0 +1,142 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI bundle: set of ABI documents keyed by contract code hash.
+//!
+//! Bundle is stored as JSON object
+//! ```json
+//! {
+//!     "version": 1,
+//!     "abis": {
+//!         "<hex-encoded code hash>": { <ABI JSON> }
+//!     }
+//! }
+//! ```
+//! ABI documents are stored as is, so saved bundle contains the same ABIs it was loaded from.
+
+use crate::{
+    contract::DecodedMessage,
+    error::AbiError,
+    resolver::{AbiKey, AbiResolver},
+    Contract,
+};
+
+use ever_block::{error, fail, Result, SliceData, UInt256};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// Version of bundle format
+pub const ABI_BUNDLE_VERSION: u64 = 1;
+
+/// Set of contract ABIs keyed by code hash
+#[derive(Clone, Debug, Default)]
+pub struct AbiBundle {
+    abis: BTreeMap<String, Value>,
+    contracts: HashMap<UInt256, Contract>,
+}
+
+impl AbiBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses bundle JSON
+    pub fn load(bundle: &str) -> Result<Self> {
+        let bundle: Value = serde_json::from_str(bundle)?;
+        match bundle["version"].as_u64() {
+            Some(ABI_BUNDLE_VERSION) => {}
+            _ => fail!(AbiError::InvalidVersion(format!(
+                "Unsupported ABI bundle version {}",
+                bundle["version"]
+            ))),
+        }
+        let abis = bundle["abis"].as_object().ok_or_else(|| {
+            error!(AbiError::InvalidData {
+                msg: "ABI bundle should contain `abis` object".to_owned()
+            })
+        })?;
+        let mut result = Self::new();
+        for (code_hash, abi) in abis {
+            let code_hash = UInt256::from_str(code_hash).map_err(|err| AbiError::InvalidData {
+                msg: format!("Invalid code hash {}: {}", code_hash, err),
+            })?;
+            result.insert_value(code_hash, abi.clone())?;
+        }
+        Ok(result)
+    }
+
+    /// Serializes bundle into JSON
+    pub fn save(&self) -> Result<String> {
+        let bundle = json!({
+            "version": ABI_BUNDLE_VERSION,
+            "abis": self.abis,
+        });
+        Ok(serde_json::to_string_pretty(&bundle)?)
+    }
+
+    /// Adds ABI JSON for contracts with provided code hash. Existing ABI is replaced.
+    pub fn insert(&mut self, code_hash: UInt256, abi: &str) -> Result<()> {
+        self.insert_value(code_hash, serde_json::from_str(abi)?)
+    }
+
+    fn insert_value(&mut self, code_hash: UInt256, abi: Value) -> Result<()> {
+        let contract = Contract::load(abi.to_string().as_bytes())?;
+        self.abis.insert(code_hash.as_hex_string(), abi);
+        self.contracts.insert(code_hash, contract);
+        Ok(())
+    }
+
+    pub fn get(&self, code_hash: &UInt256) -> Option<&Contract> {
+        self.contracts.get(code_hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.contracts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Decodes message body of contract with provided code hash. Body is decoded as function
+    /// call (internal or external) first and then as function answer or event.
+    pub fn decode_for_code_hash(
+        &self,
+        code_hash: &UInt256,
+        body: SliceData,
+    ) -> Result<DecodedMessage> {
+        let contract = self.get(code_hash).ok_or_else(|| {
+            error!(AbiError::InvalidData {
+                msg: format!("No ABI for code hash {}", code_hash.as_hex_string())
+            })
+        })?;
+        if let Ok((decoded, _)) = contract.decode_body_auto(body.clone()) {
+            return Ok(decoded);
+        }
+        contract.decode_output(body, false, false)
+    }
+}
+
+impl AbiResolver for AbiBundle {
+    fn resolve(&self, key: &AbiKey) -> Option<Contract> {
+        match key {
+            AbiKey::CodeHash(code_hash) => self.get(code_hash).cloned(),
+            AbiKey::Address(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_bundle.rs"]
+mod tests;
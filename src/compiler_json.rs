@@ -0,0 +1,210 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI JSON in the format emitted by TON Solidity compiler.
+//!
+//! Output follows compiler formatting rules, so ABI built programmatically can be compared
+//! byte by byte with compiler output:
+//! - sections are written in `ABI version`, `version`, `header`, `functions`, `data`, `events`,
+//!   `fields` order with tab indentation; `data` is written only before ABI 2.4 and `fields`
+//!   since ABI 2.1, fields `init` flag is written since ABI 2.4;
+//! - standard header parameters are written by name on one line;
+//! - every parameter is written on its own line as compact JSON with keys sorted;
+//! - function and event `id` is written only if it differs from the ID calculated from signature;
+//! - empty arrays are written with opening and closing brackets on separate lines.
+
+use crate::{
+    contract::{AbiVersion, Contract},
+    error::AbiError,
+    param::{HeaderParam, Param},
+    param_type::ParamType,
+};
+
+use serde_json::{Map, Value};
+use ever_block::{fail, Result};
+
+impl Contract {
+    /// Returns ABI JSON formatted as TON Solidity compiler output for `target_version` ABI
+    pub fn to_compiler_json(&self, target_version: &AbiVersion) -> Result<String> {
+        if target_version.major != 2 || self.version().major != 2 {
+            fail!(AbiError::NotSupported {
+                subject: "Compiler ABI JSON".to_owned(),
+                version: *target_version,
+            });
+        }
+        if !target_version.is_supported() {
+            fail!(AbiError::InvalidVersion(format!(
+                "Provided ABI version is not supported ({})",
+                target_version
+            )));
+        }
+        let params = self
+            .header()
+            .iter()
+            .chain(self.functions().values().flat_map(|function| {
+                function.input_params().iter().chain(function.output_params())
+            }))
+            .chain(self.events().values().flat_map(|event| event.inputs.iter()))
+            .chain(self.data().values().map(|item| &item.value))
+            .chain(self.fields());
+        for param in params {
+            if !param.kind.is_supported(target_version) {
+                fail!(AbiError::NotSupported {
+                    subject: format!("Parameter type {}", param.kind),
+                    version: *target_version,
+                });
+            }
+        }
+        if !self.fields().is_empty() && !target_version.at_least(2, 1) {
+            fail!(AbiError::NotSupported {
+                subject: "Storage fields".to_owned(),
+                version: *target_version,
+            });
+        }
+        if !self.data().is_empty() && target_version.at_least(2, 4) {
+            fail!(AbiError::NotSupported {
+                subject: "Data section".to_owned(),
+                version: *target_version,
+            });
+        }
+
+        let header: Vec<String> = self
+            .header()
+            .iter()
+            .map(|param| match HeaderParam::from_name(&param.name) {
+                Ok(header_param) if header_param.to_param() == *param => {
+                    format!("\"{}\"", param.name)
+                }
+                _ => param_json(param, None),
+            })
+            .collect();
+
+        let mut sections = vec![
+            format!("\t\"ABI version\": {}", target_version.major),
+            format!("\t\"version\": \"{}\"", target_version),
+            format!("\t\"header\": [{}]", header.join(", ")),
+        ];
+        let functions = self.functions().values().map(|function| {
+            let calculated_id = function.get_function_id() & 0x7FFFFFFF;
+            item_json(
+                &function.name,
+                (function.input_id != calculated_id).then_some(function.input_id),
+                function.input_params(),
+                function.output_params(),
+            )
+        });
+        sections.push(section_json("functions", functions));
+        if !target_version.at_least(2, 4) {
+            let data = self.data().values().map(|item| {
+                let mut value = param_value(&item.value, None);
+                value.insert("key".to_owned(), item.key.into());
+                format!("\t\t{}", Value::Object(value))
+            });
+            sections.push(section_json("data", data));
+        }
+        let events = self.events().values().map(|event| {
+            let calculated_id = event.get_function_id() & 0x7FFFFFFF;
+            let id = (event.id != calculated_id).then_some(event.id);
+            item_json(&event.name, id, &event.inputs, &[])
+        });
+        sections.push(section_json("events", events));
+        if target_version.at_least(2, 1) {
+            let with_init = Contract::init_fields_supported_in_version(target_version);
+            let fields = self.fields().iter().map(|param| {
+                let init = with_init.then(|| self.is_init_field(&param.name));
+                format!("\t\t{}", param_json(param, init))
+            });
+            sections.push(section_json("fields", fields));
+        }
+
+        Ok(format!("{{\n{}\n}}\n", sections.join(",\n")))
+    }
+}
+
+// function or event block
+fn item_json(name: &str, id: Option<u32>, inputs: &[Param], outputs: &[Param]) -> String {
+    let mut lines = vec![format!("\t\t\t\"name\": {}", Value::from(name))];
+    if let Some(id) = id {
+        lines.push(format!("\t\t\t\"id\": \"0x{:08X}\"", id));
+    }
+    for (name, params) in [("inputs", inputs), ("outputs", outputs)] {
+        let params = params.iter().map(|param| format!("\t\t\t\t{}", param_json(param, None)));
+        lines.push(array_json("\t\t\t", name, params));
+    }
+    format!("\t\t{{\n{}\n\t\t}}", lines.join(",\n"))
+}
+
+fn section_json(name: &str, items: impl Iterator<Item = String>) -> String {
+    array_json("\t", name, items)
+}
+
+fn array_json(indent: &str, name: &str, items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    if items.is_empty() {
+        format!("{}\"{}\": [\n{}]", indent, name, indent)
+    } else {
+        format!("{}\"{}\": [\n{}\n{}]", indent, name, items.join(",\n"), indent)
+    }
+}
+
+fn param_json(param: &Param, init: Option<bool>) -> String {
+    Value::Object(param_value(param, init)).to_string()
+}
+
+// keys are inserted in sorted order to keep it regardless of `serde_json` map implementation
+fn param_value(param: &Param, init: Option<bool>) -> Map<String, Value> {
+    let (type_name, components) = abi_type(&param.kind);
+    let mut value = Map::new();
+    if let Some(components) = components {
+        let components = components.iter().map(|param| Value::Object(param_value(param, None)));
+        value.insert("components".to_owned(), Value::Array(components.collect()));
+    }
+    if let Some(init) = init {
+        value.insert("init".to_owned(), init.into());
+    }
+    value.insert("name".to_owned(), param.name.clone().into());
+    value.insert("type".to_owned(), type_name.into());
+    value
+}
+
+// type name used in ABI JSON with tuple components moved out into `components`
+fn abi_type(kind: &ParamType) -> (String, Option<&Vec<Param>>) {
+    match kind {
+        ParamType::Tuple(params) => ("tuple".to_owned(), Some(params)),
+        ParamType::Array(item_type) => {
+            let (type_name, components) = abi_type(item_type);
+            (format!("{}[]", type_name), components)
+        }
+        ParamType::FixedArray(item_type, size) => {
+            let (type_name, components) = abi_type(item_type);
+            (format!("{}[{}]", type_name, size), components)
+        }
+        ParamType::Map(key_type, value_type) => {
+            let (type_name, components) = abi_type(value_type);
+            (format!("map({},{})", key_type.type_signature(), type_name), components)
+        }
+        ParamType::Optional(inner_type) => {
+            let (type_name, components) = abi_type(inner_type);
+            (format!("optional({})", type_name), components)
+        }
+        ParamType::Ref(inner_type) => {
+            let (type_name, components) = abi_type(inner_type);
+            (format!("ref({})", type_name), components)
+        }
+        kind => (kind.type_signature(), None),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_compiler_json.rs"]
+mod tests;
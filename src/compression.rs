@@ -0,0 +1,78 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Compressed message bodies.
+//!
+//! Compressed body is not a part of ABI specification and is intended for relays transferring
+//! bodies off-chain. Encoded body is serialized into BOC, compressed with user provided
+//! compressor and stored as `COMPRESSED_BODY_TAG` followed by `bytes` value with compressed data.
+//! Tag may coincide with a function ID, so both sides must agree on compressed bodies usage.
+
+use crate::{
+    contract::ABI_VERSION_2_4,
+    error::AbiError,
+    param_type::ParamType,
+    token::TokenValue,
+};
+
+use ever_block::{
+    fail, read_single_root_boc, write_boc, BuilderData, Cell, IBitstring, Result, SliceData,
+};
+
+/// Tag marking compressed body
+pub const COMPRESSED_BODY_TAG: u32 = 0xC0DEC0DE;
+
+/// Compression algorithm used for message bodies
+pub trait BodyCompressor {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>>;
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Compresses encoded message body
+pub fn compress_body(body: &Cell, compressor: &dyn BodyCompressor) -> Result<BuilderData> {
+    let data = compressor.compress(&write_boc(body)?)?;
+    let mut builder = BuilderData::new();
+    builder.append_u32(COMPRESSED_BODY_TAG)?;
+    builder.append_builder(&TokenValue::Bytes(data).pack_into_chain(&ABI_VERSION_2_4)?)?;
+    Ok(builder)
+}
+
+/// Checks if the body is compressed
+pub fn is_compressed_body(body: &SliceData) -> bool {
+    body.remaining_bits() == 32
+        && body.remaining_references() == 1
+        && body.clone().get_next_u32().ok() == Some(COMPRESSED_BODY_TAG)
+}
+
+/// Restores original body from compressed one. Body which is not compressed is returned as is.
+pub fn decompress_body(body: SliceData, compressor: &dyn BodyCompressor) -> Result<SliceData> {
+    if !is_compressed_body(&body) {
+        return Ok(body);
+    }
+    let mut cursor = body;
+    cursor.get_next_u32()?;
+    let (value, _) =
+        TokenValue::read_from(&ParamType::Bytes, cursor.into(), true, &ABI_VERSION_2_4, false)?;
+    let data = match value {
+        TokenValue::Bytes(data) => data,
+        _ => fail!(AbiError::InvalidData {
+            msg: "Compressed body must contain bytes".to_owned()
+        }),
+    };
+    let boc = compressor.decompress(&data)?;
+    SliceData::load_cell(read_single_root_boc(&boc)?)
+}
+
+#[cfg(test)]
+#[path = "tests/test_compression.rs"]
+mod tests;
@@ -14,22 +14,27 @@
 use crate::{
     error::AbiError,
     event::Event,
-    function::Function,
-    param::{Param, SerdeParam},
-    param_type::ParamType,
-    token::Token,
-    TokenValue,
+    function::{Function, FunctionId},
+    param::{HeaderParam, Param, SerdeParam},
+    param_type::{ParamType, MAX_TYPE_DEPTH},
+    token::{Cursor, Detokenizer, ReadOptions, Token},
+    TokenValue, Uint,
 
 };
+use chrono::prelude::Utc;
+use indexmap::IndexMap;
+use num_bigint::BigUint;
 use serde::de::Error as SerdeError;
 use serde_json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
 use std::io;
-use ever_block::{MsgAddressInt, Serializable};
+use std::str::FromStr;
+use std::sync::Arc;
+use ever_block::{MsgAddressInt, Serializable, StateInit};
 use ever_block::{
-    error, fail, BuilderData, HashmapE, Result, SliceData, ED25519_PUBLIC_KEY_LENGTH,
-    ED25519_SIGNATURE_LENGTH,
+    error, fail, sha256_digest, BuilderData, Ed25519PrivateKey, HashmapE, Result, SliceData,
+    ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
 };
 
 pub const MIN_SUPPORTED_VERSION: AbiVersion = ABI_VERSION_1_0;
@@ -42,37 +47,65 @@ pub const ABI_VERSION_2_2: AbiVersion = AbiVersion::from_parts(2, 2);
 pub const ABI_VERSION_2_3: AbiVersion = AbiVersion::from_parts(2, 3);
 pub const ABI_VERSION_2_4: AbiVersion = AbiVersion::from_parts(2, 4);
 
+/// Prefix of bounced message body
+pub const BOUNCE_PREFIX: u32 = 0xFFFFFFFF;
+
+/// Names of storage field containing contract owner public key
+pub const PUBKEY_FIELDS: [&str; 2] = ["_pubkey", "__pubkey"];
+/// Names of storage field containing replay protection timestamp
+pub const TIMESTAMP_FIELDS: [&str; 2] = ["_timestamp", "__timestamp"];
+
+/// Conventional system storage fields of contract
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SystemFields<'a> {
+    /// Contract owner public key
+    pub pubkey: Option<&'a Param>,
+    /// Timestamp of the last accepted external message
+    pub timestamp: Option<&'a Param>,
+}
+
 pub type PublicKeyData = [u8; ED25519_PUBLIC_KEY_LENGTH];
 pub type SignatureData = [u8; ED25519_SIGNATURE_LENGTH];
 
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct AbiVersion {
     pub major: u8,
     pub minor: u8,
 }
 
 impl AbiVersion {
+    /// Parses version string. Accepts `major`, `major.minor` and `major.minor.patch` forms;
+    /// missing minor version is 0 and patch version is ignored.
     pub fn parse(str_version: &str) -> Result<Self> {
         let parts: Vec<&str> = str_version.split(".").collect();
-        if parts.len() < 2 {
+        if parts.len() > 3 {
             fail!(AbiError::InvalidVersion(format!(
-                "version must consist of two parts divided by `.` ({})",
+                "version must consist of at most three parts divided by `.` ({})",
                 str_version
             )));
         }
 
-        let major = u8::from_str_radix(parts[0], 10).map_err(|err| {
-            error!(AbiError::InvalidVersion(format!(
-                "can not parse version string: {} ({})",
-                err, str_version
-            )))
-        })?;
-        let minor = u8::from_str_radix(parts[1], 10).map_err(|err| {
-            error!(AbiError::InvalidVersion(format!(
-                "can not parse version string: {} ({})",
-                err, str_version
-            )))
-        })?;
+        let parse_part = |part: &str| {
+            u8::from_str_radix(part, 10).map_err(|err| {
+                error!(AbiError::InvalidVersion(format!(
+                    "can not parse version string: {} ({})",
+                    err, str_version
+                )))
+            })
+        };
+        let major = parse_part(parts[0])?;
+        let minor = match parts.get(1) {
+            Some(minor) => parse_part(minor)?,
+            None => 0,
+        };
+        if let Some(patch) = parts.get(2) {
+            patch.parse::<u32>().map_err(|err| {
+                error!(AbiError::InvalidVersion(format!(
+                    "can not parse version string: {} ({})",
+                    err, str_version
+                )))
+            })?;
+        }
 
         Ok(Self { major, minor })
     }
@@ -81,6 +114,12 @@ impl AbiVersion {
         Self { major, minor }
     }
 
+    /// Checks if the version is `major.minor` or newer. Used for features and layout changes
+    /// introduced in particular version.
+    pub const fn at_least(&self, major: u8, minor: u8) -> bool {
+        self.major > major || (self.major == major && self.minor >= minor)
+    }
+
     pub fn is_supported(&self) -> bool {
         self >= &MIN_SUPPORTED_VERSION && self <= &MAX_SUPPORTED_VERSION
     }
@@ -101,6 +140,44 @@ impl From<u8> for AbiVersion {
     }
 }
 
+impl FromStr for AbiVersion {
+    type Err = ever_block::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// Version is serialized as `major.minor` string. Both strings and `ABI version` style integers
+/// are accepted on deserialization.
+impl serde::Serialize for AbiVersion {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AbiVersion {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum SerdeVersion {
+            Major(u8),
+            Version(String),
+        }
+
+        match SerdeVersion::deserialize(deserializer)? {
+            SerdeVersion::Major(major) => Ok(Self::from(major)),
+            SerdeVersion::Version(version) => Self::parse(&version).map_err(D::Error::custom),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 pub struct DataItem {
     pub key: u64,
@@ -168,6 +245,9 @@ pub(crate) struct SerdeFunction {
     #[serde(default)]
     #[serde(deserialize_with = "deserialize_opt_u32_from_string")]
     pub id: Option<u32>,
+    /// External calls contain signature slot. Non-standard extension for never signed calls.
+    #[serde(default = "bool_true")]
+    pub signed: bool,
 }
 
 /// Contract event specification.
@@ -187,6 +267,18 @@ fn bool_true() -> bool {
     true
 }
 
+/// Version field used when legacy `ABI version` and `version` fields of ABI JSON disagree
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VersionPrecedence {
+    /// Fail with `AbiError::AmbiguousVersion`
+    #[default]
+    Strict,
+    /// Use `version` field
+    Version,
+    /// Use legacy `ABI version` field
+    AbiVersion,
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 struct SerdeContract {
     /// ABI version up to 2.
@@ -212,47 +304,252 @@ struct SerdeContract {
     /// Contract storage fields.
     #[serde(default)]
     pub fields: Vec<SerdeParam>,
+    /// Defaults of missing header values.
+    #[serde(rename = "headerDefaults")]
+    #[serde(default)]
+    pub header_defaults: HeaderDefaults,
+}
+
+/// Handling of `pubkey` header value missing in external call
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PubkeyPolicy {
+    /// Encode absent public key
+    #[default]
+    DefaultNone,
+    /// Fail encoding
+    Required,
+}
+
+/// Contract level defaults of external call header values. Can be set in `headerDefaults`
+/// section of ABI JSON, e.g. `{"expireDelta": 60, "pubkey": "required"}`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderDefaults {
+    /// Seconds added to current time to get missing `expire` value. If not set, `expire` is
+    /// `u32::MAX` so replay protection relies on `time` only.
+    #[serde(default)]
+    pub expire_delta: Option<u32>,
+    /// Handling of missing `pubkey` value. `null` or `"none"` in JSON for absent public key
+    /// and `"required"` to fail encoding.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_pubkey_policy")]
+    pub pubkey: PubkeyPolicy,
+}
+
+impl HeaderDefaults {
+    // fills values of `params` missing in `header`, `now` is current time in milliseconds
+    pub(crate) fn complete_header(
+        &self,
+        params: &[Param],
+        header: &mut HashMap<String, TokenValue>,
+        now: u64,
+    ) -> Result<()> {
+        for param in params {
+            match param.kind {
+                ParamType::Expire if !header.contains_key(&param.name) => {
+                    if let Some(delta) = self.expire_delta {
+                        let now = (now / 1000) as u32;
+                        header.insert(
+                            param.name.clone(),
+                            TokenValue::Expire(now.saturating_add(delta)),
+                        );
+                    }
+                }
+                ParamType::PublicKey if self.pubkey == PubkeyPolicy::Required => {
+                    if !matches!(header.get(&param.name), Some(TokenValue::PublicKey(Some(_)))) {
+                        fail!(AbiError::InvalidInputData {
+                            msg: format!("Header value `{}` is required", param.name)
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn deserialize_pubkey_policy<'de, D>(d: D) -> std::result::Result<PubkeyPolicy, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match <Option<String> as serde::Deserialize>::deserialize(d)?.as_deref() {
+        None | Some("none") => Ok(PubkeyPolicy::DefaultNone),
+        Some("required") => Ok(PubkeyPolicy::Required),
+        Some(policy) => Err(D::Error::custom(format!("Unknown pubkey policy: {}", policy))),
+    }
+}
+
+/// Initial data dictionary decoded by `Contract::decode_data_with_unknown`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedData {
+    /// Values of data items declared in ABI
+    pub tokens: Vec<Token>,
+    /// Values stored under keys not declared in ABI. Public key is not included.
+    pub unknown: BTreeMap<u64, SliceData>,
+}
+
+/// Deploy message data produced by `Contract::encode_constructor`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeployMessage {
+    /// Constructor call body
+    pub body: BuilderData,
+    /// StateInit with initial contract data
+    pub state_init: StateInit,
+    /// Address of deployed contract
+    pub address: MsgAddressInt,
+}
+
+/// Function call restored from bounced message body
+#[derive(Clone, Debug, PartialEq)]
+pub struct BouncedMessage {
+    pub function_name: String,
+    /// Function parameters contained in bounced body
+    pub tokens: Vec<Token>,
+    /// Some function parameters were cut off from bounced body
+    pub truncated: bool,
+}
+
+/// ABI item matched while decoding message
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedItem {
+    Function(Arc<Function>),
+    Event(Arc<Event>),
+}
+
+/// Kind of message body decoding result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodedAs {
+    /// Function call decoded with `Contract::decode_input`
+    FunctionInput,
+    /// Function answer decoded with `Contract::decode_output`
+    FunctionOutput,
+    /// Event decoded with `Contract::decode_output`
+    Event,
+}
+
+/// Message body prefix read by `Contract::peek`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Peek {
+    /// Function or event ID
+    pub function_id: u32,
+    /// Body is an event emitted by contract
+    pub is_event: bool,
+    /// Body is an external call with signature
+    pub has_signature: bool,
+    /// `expire` header value of external call
+    pub expire: Option<u32>,
 }
 
 pub struct DecodedMessage {
     pub function_name: String,
     pub tokens: Vec<Token>,
+    /// Name of the contract (or merged ABI part) the function or event belongs to
+    pub source: Option<String>,
+    /// Function or event the message was decoded with
+    pub item: DecodedItem,
+    /// Which part of ABI matched the message body
+    pub decoded_as: DecodedAs,
+}
+
+impl DecodedMessage {
+    /// Returns decoded tokens as JSON string
+    pub fn to_json(&self) -> Result<String> {
+        Detokenizer::detokenize(&self.tokens)
+    }
+
+    /// Returns matched function if message was decoded as function call or answer
+    pub fn function(&self) -> Option<&Function> {
+        match &self.item {
+            DecodedItem::Function(function) => Some(function.as_ref()),
+            DecodedItem::Event(_) => None,
+        }
+    }
+
+    /// Returns matched event if message was decoded as event
+    pub fn event(&self) -> Option<&Event> {
+        match &self.item {
+            DecodedItem::Event(event) => Some(event.as_ref()),
+            DecodedItem::Function(_) => None,
+        }
+    }
+
+    /// Returns function name qualified with source contract name: `source::function_name`
+    pub fn qualified_name(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{}::{}", source, self.function_name),
+            None => self.function_name.clone(),
+        }
+    }
 }
 
 /// API building calls to contracts ABI.
+///
+/// Functions, events, data items and storage fields are shared between clones, so cloning
+/// a contract is cheap. Collections are copied on the first modification of a shared clone.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Contract {
     /// ABI version
     abi_version: AbiVersion,
     /// Contract functions header parameters
     header: Vec<Param>,
-    /// Contract functions.
-    functions: HashMap<String, Function>,
-    /// Contract events.
-    events: HashMap<String, Event>,
-    /// Contract initial data.
-    data: HashMap<String, DataItem>,
+    /// Contract functions in ABI declaration order.
+    functions: Arc<IndexMap<String, Arc<Function>>>,
+    /// Contract events in ABI declaration order.
+    events: Arc<IndexMap<String, Arc<Event>>>,
+    /// Contract initial data in ABI declaration order.
+    data: Arc<IndexMap<String, DataItem>>,
     /// Contract storage fields.
-    fields: Vec<Param>,
+    fields: Arc<Vec<Param>>,
     /// List of `fields` parameters with `init == true`
     init_fields: HashSet<String>,
+    /// Contract name used to identify decoding results
+    name: Option<String>,
+    /// Names of merged ABI parts functions are taken from
+    function_sources: HashMap<String, String>,
+    /// Names of merged ABI parts events are taken from
+    event_sources: HashMap<String, String>,
+    /// Defaults of missing header values
+    header_defaults: HeaderDefaults,
 }
 
 impl Contract {
     /// Loads contract from json.
     pub fn load<T: io::Read>(reader: T) -> Result<Self> {
+        Self::load_with_precedence(reader, VersionPrecedence::Strict)
+    }
+
+    /// Loads contract from json resolving conflict of `ABI version` and `version` fields
+    /// according to `precedence`
+    pub fn load_with_precedence<T: io::Read>(
+        reader: T,
+        precedence: VersionPrecedence,
+    ) -> Result<Self> {
         // A little trick similar to `Param` deserialization: first deserialize JSON into temporary
-        // struct `SerdeContract` containing necessary fields and then repack fields into HashMap
+        // struct `SerdeContract` containing necessary fields and then repack fields into maps
         let mut serde_contract: SerdeContract = serde_json::from_reader(reader)?;
 
-        let version = if let Some(str_version) = &serde_contract.version {
-            AbiVersion::parse(str_version)?
-        } else if let Some(version) = serde_contract.abi_version {
-            AbiVersion::from_parts(version, 0)
-        } else {
-            fail!(AbiError::InvalidVersion(
+        let version = match (&serde_contract.version, serde_contract.abi_version) {
+            (Some(str_version), Some(abi_version)) => {
+                let version = AbiVersion::parse(str_version)?;
+                if version.major == abi_version {
+                    version
+                } else {
+                    match precedence {
+                        VersionPrecedence::Strict => fail!(AbiError::AmbiguousVersion {
+                            abi_version,
+                            version,
+                        }),
+                        VersionPrecedence::Version => version,
+                        VersionPrecedence::AbiVersion => AbiVersion::from_parts(abi_version, 0),
+                    }
+                }
+            }
+            (Some(str_version), None) => AbiVersion::parse(str_version)?,
+            (None, Some(abi_version)) => AbiVersion::from_parts(abi_version, 0),
+            (None, None) => fail!(AbiError::InvalidVersion(
                 "No version in ABI JSON".to_owned()
-            ));
+            )),
         };
 
         if !version.is_supported() {
@@ -270,14 +567,11 @@ impl Contract {
                 .into());
             }
             if serde_contract.set_time {
-                serde_contract.header.push(Param {
-                    name: "time".into(),
-                    kind: ParamType::Time,
-                });
+                serde_contract.header.push(HeaderParam::Time.to_param());
             }
         }
 
-        if !serde_contract.fields.is_empty() && version < ABI_VERSION_2_1 {
+        if !serde_contract.fields.is_empty() && !version.at_least(2, 1) {
             fail!(AbiError::InvalidData {
                 msg: "Storage fields are supported since ABI v2.1".into()
             });
@@ -286,42 +580,56 @@ impl Contract {
         let mut result = Self {
             abi_version: version.clone(),
             header: serde_contract.header,
-            functions: HashMap::new(),
-            events: HashMap::new(),
-            data: HashMap::new(),
-            fields: Vec::new(),
+            functions: Default::default(),
+            events: Default::default(),
+            data: Default::default(),
+            fields: Default::default(),
             init_fields: HashSet::new(),
+            name: None,
+            function_sources: HashMap::new(),
+            event_sources: HashMap::new(),
+            header_defaults: serde_contract.header_defaults,
         };
 
         for function in serde_contract.functions {
+            if !function.signed && version.major == 1 {
+                fail!(AbiError::NotSupported {
+                    subject: "Function without signature".to_owned(),
+                    version,
+                });
+            }
             Self::check_params_support(&version, function.inputs.iter())?;
             Self::check_params_support(&version, function.outputs.iter())?;
-            result.functions.insert(
-                function.name.clone(),
-                Function::from_serde(version.clone(), function, result.header.clone()),
+            let function = Function::from_serde(
+                version.clone(),
+                function,
+                result.header.clone(),
+                result.header_defaults,
             );
+            result.functions_mut().insert(function.name.clone(), Arc::new(function));
         }
 
         for event in serde_contract.events {
             Self::check_params_support(&version, event.inputs.iter())?;
-            result.events.insert(
-                event.name.clone(),
-                Event::from_serde(version.clone(), event),
-            );
+            result
+                .events_mut()
+                .insert(event.name.clone(), Arc::new(Event::from_serde(version.clone(), event)));
         }
 
         Self::check_params_support(&version, serde_contract.data.iter().map(|val| &val.value))?;
         for data in serde_contract.data {
-            result.data.insert(data.value.name.clone(), data);
+            Self::check_data_item(&result.data, &data)?;
+            result.data_mut().insert(data.value.name.clone(), data);
         }
 
         for field in serde_contract.fields {
             if field.init {
                 result.init_fields.insert(field.name.clone());
             }
-            result
-                .fields
-                .push(Param::from_serde(field).map_err(|err| AbiError::InvalidData { msg: err })?);
+            let field =
+                Param::from_serde(field).map_err(|err| AbiError::InvalidData { msg: err })?;
+            field.kind.validate(MAX_TYPE_DEPTH)?;
+            Arc::make_mut(&mut result.fields).push(field);
         }
 
         Ok(result)
@@ -332,6 +640,7 @@ impl Contract {
         T: std::iter::Iterator<Item = &'a Param>,
     {
         for param in params {
+            param.kind.validate(MAX_TYPE_DEPTH)?;
             if !param.kind.is_supported(abi_version) {
                 return Err(AbiError::NotSupported {
                     subject: format!("Parameter type {}", param.kind),
@@ -343,9 +652,190 @@ impl Contract {
         Ok(())
     }
 
+    /// Merges several ABI fragments into one contract. All parts should have the same version,
+    /// header and header defaults. Functions, events and data items with the same name are
+    /// allowed only if they are identical; different items with the same name or ID are reported
+    /// as collision. Storage fields describe the whole contract so they should be either
+    /// identical or present in only one part.
+    pub fn merge(parts: &[Contract]) -> Result<Contract> {
+        let first = parts.first().ok_or_else(|| AbiError::InvalidData {
+            msg: "No ABI parts to merge".into(),
+        })?;
+        let mut result = first.clone();
+        result.name = None;
+        result.record_sources(first);
+
+        for part in &parts[1..] {
+            if part.abi_version != result.abi_version {
+                fail!(AbiError::InvalidData {
+                    msg: format!(
+                        "Can not merge ABI parts with different versions ({} and {})",
+                        result.abi_version, part.abi_version
+                    )
+                });
+            }
+            if part.header != result.header {
+                fail!(AbiError::InvalidData {
+                    msg: "Can not merge ABI parts with different headers".into()
+                });
+            }
+            if part.header_defaults != result.header_defaults {
+                fail!(AbiError::InvalidData {
+                    msg: "Can not merge ABI parts with different header defaults".into()
+                });
+            }
+
+            for (name, function) in part.functions.iter() {
+                if let Some(existing) = result.functions.get(name) {
+                    if existing != function {
+                        fail!(AbiError::InvalidData {
+                            msg: format!("Function `{}` collision while merging ABI parts", name)
+                        });
+                    }
+                    continue;
+                }
+                if let Some(existing) = result.functions.values().find(|existing| {
+                    existing.input_id == function.input_id
+                        || existing.output_id == function.output_id
+                }) {
+                    fail!(AbiError::InvalidData {
+                        msg: format!(
+                            "Functions `{}` and `{}` have the same ID",
+                            existing.name, name
+                        )
+                    });
+                }
+                result.functions_mut().insert(name.clone(), function.clone());
+                if let Some(source) = part.function_source(name) {
+                    result.function_sources.insert(name.clone(), source.to_owned());
+                }
+            }
+
+            for (name, event) in part.events.iter() {
+                if let Some(existing) = result.events.get(name) {
+                    if existing != event {
+                        fail!(AbiError::InvalidData {
+                            msg: format!("Event `{}` collision while merging ABI parts", name)
+                        });
+                    }
+                    continue;
+                }
+                if let Some(existing) =
+                    result.events.values().find(|existing| existing.id == event.id)
+                {
+                    fail!(AbiError::InvalidData {
+                        msg: format!(
+                            "Events `{}` and `{}` have the same ID",
+                            existing.name, name
+                        )
+                    });
+                }
+                result.events_mut().insert(name.clone(), event.clone());
+                if let Some(source) = part.event_source(name) {
+                    result.event_sources.insert(name.clone(), source.to_owned());
+                }
+            }
+
+            for (name, item) in part.data.iter() {
+                if let Some(existing) = result.data.get(name) {
+                    if existing != item {
+                        fail!(AbiError::InvalidData {
+                            msg: format!("Data item `{}` collision while merging ABI parts", name)
+                        });
+                    }
+                    continue;
+                }
+                Self::check_data_item(&result.data, item)?;
+                result.data_mut().insert(name.clone(), item.clone());
+            }
+
+            if result.fields.is_empty() {
+                result.fields = part.fields.clone();
+                result.init_fields = part.init_fields.clone();
+            } else if !part.fields.is_empty()
+                && (part.fields != result.fields || part.init_fields != result.init_fields)
+            {
+                fail!(AbiError::InvalidData {
+                    msg: "Can not merge ABI parts with different storage fields".into()
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn record_sources(&mut self, part: &Contract) {
+        for name in part.functions.keys() {
+            if let Some(source) = part.function_source(name) {
+                self.function_sources.insert(name.clone(), source.to_owned());
+            }
+        }
+        for name in part.events.keys() {
+            if let Some(source) = part.event_source(name) {
+                self.event_sources.insert(name.clone(), source.to_owned());
+            }
+        }
+    }
+
+    /// Sets contract name which is reported as `source` in decoding results
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets defaults applied by `complete_header` to missing header values
+    pub fn with_header_defaults(mut self, header_defaults: HeaderDefaults) -> Self {
+        self.header_defaults = header_defaults;
+        for function in self.functions_mut().values_mut() {
+            Arc::make_mut(function).header_defaults = header_defaults;
+        }
+        self
+    }
+
+    /// Returns defaults of missing header values
+    pub fn header_defaults(&self) -> &HeaderDefaults {
+        &self.header_defaults
+    }
+
+    /// Fills external call header values missing in `header` according to contract header
+    /// defaults. Values without configured defaults are left to function encoding.
+    pub fn complete_header(&self, header: &mut HashMap<String, TokenValue>) -> Result<()> {
+        self.complete_header_at(header, Utc::now().timestamp_millis() as u64)
+    }
+
+    /// Same as `complete_header` with current time `now` in milliseconds
+    pub fn complete_header_at(
+        &self,
+        header: &mut HashMap<String, TokenValue>,
+        now: u64,
+    ) -> Result<()> {
+        self.header_defaults.complete_header(&self.header, header, now)
+    }
+
+    /// Returns contract name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns name of the contract (or merged ABI part) function belongs to
+    pub fn function_source(&self, name: &str) -> Option<&str> {
+        self.function_sources
+            .get(name)
+            .map(|source| source.as_str())
+            .or(self.name())
+    }
+
+    /// Returns name of the contract (or merged ABI part) event belongs to
+    pub fn event_source(&self, name: &str) -> Option<&str> {
+        self.event_sources
+            .get(name)
+            .map(|source| source.as_str())
+            .or(self.name())
+    }
+
     /// Returns `Function` struct with provided function name.
     pub fn function(&self, name: &str) -> Result<&Function> {
-        self.functions.get(name).ok_or_else(|| {
+        self.functions.get(name).map(Arc::as_ref).ok_or_else(|| {
             AbiError::InvalidName {
                 name: name.to_owned(),
             }
@@ -355,7 +845,11 @@ impl Contract {
 
     /// Returns `Function` struct with provided function id.
     pub fn function_by_id(&self, id: u32, input: bool) -> Result<&Function> {
-        for (_, func) in &self.functions {
+        self.shared_function_by_id(id, input).map(Arc::as_ref)
+    }
+
+    fn shared_function_by_id(&self, id: u32, input: bool) -> Result<&Arc<Function>> {
+        for func in self.functions.values() {
             let func_id = if input {
                 func.get_input_id()
             } else {
@@ -369,9 +863,14 @@ impl Contract {
         Err(AbiError::InvalidFunctionId { id }.into())
     }
 
+    /// Returns `Function` struct with provided key
+    pub fn function_by_key(&self, id: FunctionId) -> Result<&Function> {
+        self.function_by_id(id.0, true)
+    }
+
     /// Returns `Event` struct with provided function name.
     pub fn event(&self, name: &str) -> Result<&Event> {
-        self.events.get(name).ok_or_else(|| {
+        self.events.get(name).map(Arc::as_ref).ok_or_else(|| {
             AbiError::InvalidName {
                 name: name.to_owned(),
             }
@@ -381,7 +880,11 @@ impl Contract {
 
     /// Returns `Event` struct with provided function id.
     pub fn event_by_id(&self, id: u32) -> Result<&Event> {
-        for (_, event) in &self.events {
+        self.shared_event_by_id(id).map(Arc::as_ref)
+    }
+
+    fn shared_event_by_id(&self, id: u32) -> Result<&Arc<Event>> {
+        for event in self.events.values() {
             if event.get_id() == id {
                 return Ok(event);
             }
@@ -390,8 +893,8 @@ impl Contract {
         Err(AbiError::InvalidFunctionId { id }.into())
     }
 
-    /// Returns functions collection
-    pub fn functions(&self) -> &HashMap<String, Function> {
+    /// Returns functions collection in ABI declaration order
+    pub fn functions(&self) -> &IndexMap<String, Arc<Function>> {
         &self.functions
     }
 
@@ -400,13 +903,13 @@ impl Contract {
         &self.header
     }
 
-    /// Returns events collection
-    pub fn events(&self) -> &HashMap<String, Event> {
+    /// Returns events collection in ABI declaration order
+    pub fn events(&self) -> &IndexMap<String, Arc<Event>> {
         &self.events
     }
 
-    /// Returns data collection
-    pub fn data(&self) -> &HashMap<String, DataItem> {
+    /// Returns data collection in ABI declaration order
+    pub fn data(&self) -> &IndexMap<String, DataItem> {
         &self.data
     }
 
@@ -415,40 +918,206 @@ impl Contract {
         &self.fields
     }
 
+    /// Checks if storage field should be set on deploy
+    pub fn is_init_field(&self, name: &str) -> bool {
+        self.init_fields.contains(name)
+    }
+
+    /// Returns mutable functions collection. Collection shared with other clones of the contract
+    /// is copied before modification.
+    pub fn functions_mut(&mut self) -> &mut IndexMap<String, Arc<Function>> {
+        Arc::make_mut(&mut self.functions)
+    }
+
+    /// Returns mutable events collection. Collection shared with other clones of the contract
+    /// is copied before modification.
+    pub fn events_mut(&mut self) -> &mut IndexMap<String, Arc<Event>> {
+        Arc::make_mut(&mut self.events)
+    }
+
+    /// Returns mutable data collection. Collection shared with other clones of the contract
+    /// is copied before modification.
+    pub fn data_mut(&mut self) -> &mut IndexMap<String, DataItem> {
+        Arc::make_mut(&mut self.data)
+    }
+
     /// Returns version
     pub fn version(&self) -> &AbiVersion {
         &self.abi_version
     }
 
-    /// Decodes contract answer and returns name of the function called
+    /// Computes ABI fingerprint: SHA256 hash of normalized ABI description. Fingerprint doesn't
+    /// depend on JSON formatting and on the order of functions, events and data items.
+    pub fn fingerprint(&self) -> Vec<u8> {
+        sha256_digest(self.normalized_description().as_bytes()).to_vec()
+    }
+
+    fn normalized_description(&self) -> String {
+        let params_description = |params: &[Param]| {
+            params
+                .iter()
+                .map(Self::normalized_param)
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+
+        let mut functions: Vec<String> = self
+            .functions
+            .values()
+            .map(|function| {
+                format!(
+                    "{}({})({}){:08x}:{:08x}{}",
+                    function.name,
+                    params_description(&function.inputs),
+                    params_description(&function.outputs),
+                    function.input_id,
+                    function.output_id,
+                    if function.signed { "" } else { " unsigned" }
+                )
+            })
+            .collect();
+        functions.sort();
+
+        let mut events: Vec<String> = self
+            .events
+            .values()
+            .map(|event| {
+                format!("{}({}){:08x}", event.name, params_description(&event.inputs), event.id)
+            })
+            .collect();
+        events.sort();
+
+        let mut data: Vec<(u64, String)> = self
+            .data
+            .values()
+            .map(|item| (item.key, Self::normalized_param(&item.value)))
+            .collect();
+        data.sort();
+        let data: Vec<String> = data
+            .into_iter()
+            .map(|(key, param)| format!("{}={}", key, param))
+            .collect();
+
+        // storage fields order is significant
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let init = if self.init_fields.contains(&field.name) { "init " } else { "" };
+                format!("{}{}", init, Self::normalized_param(field))
+            })
+            .collect();
+
+        let pubkey = match self.header_defaults.pubkey {
+            PubkeyPolicy::DefaultNone => "none",
+            PubkeyPolicy::Required => "required",
+        };
+        let expire_delta = self.header_defaults.expire_delta.map(|delta| delta.to_string());
+        format!(
+            "version:{};header:{};headerDefaults:expireDelta={},pubkey={};functions:{};events:{};\
+                data:{};fields:{}",
+            self.abi_version,
+            params_description(&self.header),
+            expire_delta.as_deref().unwrap_or("none"),
+            pubkey,
+            functions.join(";"),
+            events.join(";"),
+            data.join(","),
+            fields.join(","),
+        )
+    }
+
+    fn normalized_param(param: &Param) -> String {
+        format!("{}:{}", param.name, Self::normalized_type(&param.kind))
+    }
+
+    fn normalized_type(kind: &ParamType) -> String {
+        match kind {
+            ParamType::Tuple(params) => format!(
+                "({})",
+                params
+                    .iter()
+                    .map(Self::normalized_param)
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            ParamType::Array(inner) => format!("{}[]", Self::normalized_type(inner)),
+            ParamType::FixedArray(inner, size) => {
+                format!("{}[{}]", Self::normalized_type(inner), size)
+            }
+            ParamType::Map(key, value) => format!(
+                "map({},{})",
+                Self::normalized_type(key),
+                Self::normalized_type(value)
+            ),
+            ParamType::Optional(inner) => format!("optional({})", Self::normalized_type(inner)),
+            ParamType::Ref(inner) => format!("ref({})", Self::normalized_type(inner)),
+            _ => kind.type_signature(),
+        }
+    }
+
+    /// Decodes contract answer and returns name of the function called. Body ID is looked up
+    /// among function outputs first and then among events (see `Contract::lint` for
+    /// colliding IDs). Fails with
+    /// `AbiError::InvalidFunctionId` if ID matches nothing and with
+    /// `AbiError::MatchedItemDecodeFailed` if matched item can not decode the body.
     pub fn decode_output(
         &self,
         data: SliceData,
         internal: bool,
         allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        self.decode_output_with_options(data, internal, allow_partial, ReadOptions::default())
+    }
+
+    pub(crate) fn decode_output_with_options(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<DecodedMessage> {
         let original_data = data.clone();
 
         let func_id = Function::decode_output_id(data)?;
 
-        if let Ok(func) = self.function_by_id(func_id, false) {
-            let tokens = func.decode_output(original_data, internal, allow_partial)?;
+        if let Ok(func) = self.shared_function_by_id(func_id, false) {
+            let tokens = func
+                .decode_output_with_options(original_data, internal, allow_partial, options)
+                .map_err(|err| Self::matched_item_error(&func.name, err))?;
 
             Ok(DecodedMessage {
                 function_name: func.name.clone(),
                 tokens: tokens,
+                source: self.function_source(&func.name).map(str::to_owned),
+                item: DecodedItem::Function(Arc::clone(func)),
+                decoded_as: DecodedAs::FunctionOutput,
             })
         } else {
-            let event = self.event_by_id(func_id)?;
-            let tokens = event.decode_input(original_data, allow_partial)?;
+            let event = self.shared_event_by_id(func_id)?;
+            let tokens = event
+                .decode_input_with_options(original_data, allow_partial, options)
+                .map_err(|err| Self::matched_item_error(&event.name, err))?;
 
             Ok(DecodedMessage {
                 function_name: event.name.clone(),
                 tokens: tokens,
+                source: self.event_source(&event.name).map(str::to_owned),
+                item: DecodedItem::Event(Arc::clone(event)),
+                decoded_as: DecodedAs::Event,
             })
         }
     }
 
+    // wraps error of decoding body whose ID matched function or event
+    fn matched_item_error(name: &str, err: ever_block::Error) -> ever_block::Error {
+        AbiError::MatchedItemDecodeFailed {
+            name: name.to_owned(),
+            err: err.to_string(),
+        }
+        .into()
+    }
+
     /// Decodes contract answer and returns name of the function called
     pub fn decode_input(
         &self,
@@ -456,24 +1125,241 @@ impl Contract {
         internal: bool,
         allow_partial: bool,
     ) -> Result<DecodedMessage> {
-        let original_data = data.clone();
-
-        let func_id = Function::decode_input_id(&self.abi_version, data, &self.header, internal)?;
+        self.decode_input_with_options(data, internal, allow_partial, ReadOptions::default())
+    }
 
-        let func = self.function_by_id(func_id, true)?;
+    pub(crate) fn decode_input_with_options(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+        options: ReadOptions,
+    ) -> Result<DecodedMessage> {
+        let func = self.called_function(data.clone(), internal)?;
 
-        let tokens = func.decode_input(original_data, internal, allow_partial)?;
+        let tokens = func.decode_input_with_options(data, internal, allow_partial, options)?;
 
         Ok(DecodedMessage {
             function_name: func.name.clone(),
             tokens,
+            source: self.function_source(&func.name).map(str::to_owned),
+            item: DecodedItem::Function(Arc::clone(func)),
+            decoded_as: DecodedAs::FunctionInput,
+        })
+    }
+
+    // finds function called by the body: external call body starts with signature slot
+    // unless the function is never signed
+    fn called_function(&self, data: SliceData, internal: bool) -> Result<&Arc<Function>> {
+        let signed =
+            Function::decode_input_id(&self.abi_version, data.clone(), &self.header, internal)
+                .and_then(|id| self.shared_function_by_id(id, true));
+        if internal || self.functions.values().all(|function| function.signed) {
+            return signed;
+        }
+        if matches!(&signed, Ok(function) if function.signed) {
+            return signed;
+        }
+        let unsigned = Function::decode_header_with_signature(
+            &self.abi_version,
+            data,
+            &self.header,
+            false,
+            false,
+        )
+        .ok()
+        .and_then(|(_, id, _)| self.shared_function_by_id(id, true).ok())
+        .filter(|function| !function.signed);
+        match (unsigned, signed) {
+            (Some(function), _) => Ok(function),
+            (None, Err(err)) => Err(err),
+            (None, Ok(function)) => fail!(AbiError::InvalidData {
+                msg: format!("Function `{}` calls are never signed", function.name)
+            }),
+        }
+    }
+
+    /// Encodes unsigned external call of the function for local execution, e.g. running getter
+    /// on downloaded account state. `expire` is set to maximum value, `pubkey` is empty and
+    /// other header parameters have default values. Header defaults policy is not applied.
+    pub fn encode_getter_call(&self, name: &str, input: &[Token]) -> Result<BuilderData> {
+        let function = Function {
+            header_defaults: HeaderDefaults::default(),
+            ..self.function(name)?.clone()
+        };
+        let header = self
+            .header
+            .iter()
+            .map(|param| {
+                let value = TokenValue::get_default_value_for_header(&param.kind)
+                    .unwrap_or_else(|_| TokenValue::default_value(&param.kind));
+                (param.name.clone(), value)
+            })
+            .collect();
+        function.encode_input(&header, input, false, None, None)
+    }
+
+    /// Decodes answer of the function called with `encode_getter_call`
+    pub fn decode_getter_answer(&self, name: &str, data: SliceData) -> Result<Vec<Token>> {
+        self.function(name)?.decode_output(data, false, false)
+    }
+
+    /// Reads only message body prefix: function ID, signature presence and `expire` header
+    /// without decoding function parameters. Body is checked to be an event or function answer
+    /// first, then external function call and then internal one.
+    pub fn peek(&self, data: SliceData) -> Result<Peek> {
+        let id = data.clone().get_next_u32()?;
+        // function answer takes precedence over event with the same ID as in `decode_output`
+        let is_event = self.function_by_id(id, false).is_err() && self.event_by_id(id).is_ok();
+        if is_event || self.function_by_id(id, false).is_ok() {
+            return Ok(Peek {
+                function_id: id,
+                is_event,
+                has_signature: false,
+                expire: None,
+            });
+        }
+
+        if let Ok(function) = self.called_function(data.clone(), false) {
+            let header = function.read_call_header(data)?;
+            return Ok(Peek {
+                function_id: header.function_id,
+                is_event: false,
+                has_signature: header.signature.is_some(),
+                expire: header.expire(),
+            });
+        }
+
+        self.function_by_id(id, true)?;
+        Ok(Peek {
+            function_id: id,
+            is_event: false,
+            has_signature: false,
+            expire: None,
+        })
+    }
+
+    /// Decodes body of bounced internal function call. Bounced body contains `BOUNCE_PREFIX`
+    /// followed by the beginning of original body without references, so only parameters
+    /// fitting into it are decoded.
+    pub fn decode_bounced(&self, mut data: SliceData) -> Result<BouncedMessage> {
+        if data.get_next_u32()? != BOUNCE_PREFIX {
+            fail!(AbiError::InvalidData {
+                msg: "Message body is not bounced".to_owned()
+            });
+        }
+        let function = self.function_by_id(data.get_next_u32()?, true)?;
+
+        let mut tokens = vec![];
+        let mut cursor = Cursor::from(data);
+        cursor.used_bits = 32;
+        for param in function.input_params() {
+            match TokenValue::read_from(&param.kind, cursor.clone(), false, &self.abi_version, true)
+            {
+                Ok((value, new_cursor)) => {
+                    tokens.push(Token::new(&param.name, value));
+                    cursor = new_cursor;
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok(BouncedMessage {
+            function_name: function.name.clone(),
+            truncated: tokens.len() < function.input_params().len(),
+            tokens,
         })
     }
 
+    /// Decodes function call body trying internal message layout first and then external one
+    /// with header and signature. Returns decoded message and `true` if internal layout matched.
+    pub fn decode_body_auto(&self, data: SliceData) -> Result<(DecodedMessage, bool)> {
+        if let Ok(decoded) = self.decode_input(data.clone(), true, false) {
+            return Ok((decoded, true));
+        }
+        let decoded = self.decode_input(data, false, false)?;
+        Ok((decoded, false))
+    }
+
+    /// Decodes contract answer with every provided contract and returns all successful results.
+    /// Unlike picking the first matching contract, caller can detect ambiguous messages and
+    /// disambiguate them using `DecodedMessage::source`.
+    pub fn decode_output_all(
+        contracts: &[Contract],
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Vec<DecodedMessage> {
+        contracts
+            .iter()
+            .filter_map(|contract| {
+                contract
+                    .decode_output(data.clone(), internal, allow_partial)
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Decodes function call with every provided contract and returns all successful results.
+    /// See `decode_output_all`.
+    pub fn decode_input_all(
+        contracts: &[Contract],
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Vec<DecodedMessage> {
+        contracts
+            .iter()
+            .filter_map(|contract| {
+                contract
+                    .decode_input(data.clone(), internal, allow_partial)
+                    .ok()
+            })
+            .collect()
+    }
+
     pub const DATA_MAP_KEYLEN: usize = 64;
 
+    /// Initial data dictionary key of contract public key
+    pub const PUBKEY_DATA_KEY: u64 = 0;
+
+    // checks that data item doesn't use public key or already used key and name
+    fn check_data_item(data: &IndexMap<String, DataItem>, item: &DataItem) -> Result<()> {
+        if item.key == Self::PUBKEY_DATA_KEY {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Data item `{}` uses key {} reserved for public key",
+                    item.value.name,
+                    Self::PUBKEY_DATA_KEY
+                )
+            });
+        }
+        if data.contains_key(&item.value.name) {
+            fail!(AbiError::InvalidData {
+                msg: format!("Data item `{}` is defined twice", item.value.name)
+            });
+        }
+        if let Some(existing) = data.values().find(|existing| existing.key == item.key) {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Data items `{}` and `{}` have the same key {}",
+                    existing.value.name, item.value.name, item.key
+                )
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns initial data dictionary keys with names of data items stored under them
+    pub fn data_keys(&self) -> BTreeMap<u64, &str> {
+        self.data
+            .values()
+            .map(|item| (item.key, item.value.name.as_str()))
+            .collect()
+    }
+
     pub fn data_map_supported_in_version(abi_version: &AbiVersion) -> bool {
-        abi_version < &ABI_VERSION_2_4
+        !abi_version.at_least(2, 4)
     }
 
     pub fn data_map_supported(&self) -> bool {
@@ -492,7 +1378,7 @@ impl Contract {
     }
 
     pub fn init_fields_supported_in_version(abi_version: &AbiVersion) -> bool {
-        abi_version >= &ABI_VERSION_2_4
+        abi_version.at_least(2, 4)
     }
 
     pub fn init_fields_supported(&self) -> bool {
@@ -536,7 +1422,7 @@ impl Contract {
         let map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
 
         let mut tokens = vec![];
-        for (_, item) in &self.data {
+        for item in self.data.values() {
             let key = SliceData::load_builder(item.key.write_to_new_cell()?)?;
             if let Some(value) = map.get(key)? {
                 tokens.append(&mut TokenValue::decode_params(
@@ -551,14 +1437,152 @@ impl Contract {
         Ok(tokens)
     }
 
+    /// Same as `decode_data` but also returns raw values stored under keys not declared in ABI
+    pub fn decode_data_with_unknown(
+        &self,
+        data: SliceData,
+        allow_partial: bool,
+    ) -> Result<DecodedData> {
+        let tokens = self.decode_data(data.clone(), allow_partial)?;
+
+        let known = self.data_keys();
+        let mut unknown = BTreeMap::new();
+        let map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
+        map.iterate_slices(|mut key, value| {
+            let key = key.get_next_u64()?;
+            if key != Self::PUBKEY_DATA_KEY && !known.contains_key(&key) {
+                unknown.insert(key, value);
+            }
+            Ok(true)
+        })?;
+
+        Ok(DecodedData { tokens, unknown })
+    }
+
     // Gets public key from contract data
     pub fn get_pubkey(data: &SliceData) -> Result<Option<PublicKeyData>> {
         let map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
-        Ok(map.get(SliceData::load_builder(0u64.write_to_new_cell()?)?)?
+        Ok(map.get(SliceData::load_builder(Self::PUBKEY_DATA_KEY.write_to_new_cell()?)?)?
             .map(|slice| slice.get_bytestring(0).as_slice().try_into())
             .transpose()?)
     }
 
+    /// Returns storage field containing contract owner public key
+    pub fn pubkey_field(&self) -> Option<&Param> {
+        self.fields
+            .iter()
+            .find(|param| PUBKEY_FIELDS.contains(&param.name.as_str()))
+    }
+
+    /// Returns storage field containing replay protection timestamp
+    pub fn timestamp_field(&self) -> Option<&Param> {
+        self.fields
+            .iter()
+            .find(|param| TIMESTAMP_FIELDS.contains(&param.name.as_str()))
+    }
+
+    /// Returns conventional system storage fields present in contract ABI
+    pub fn system_fields(&self) -> SystemFields {
+        SystemFields {
+            pubkey: self.pubkey_field(),
+            timestamp: self.timestamp_field(),
+        }
+    }
+
+    /// Sets public key storage field value. Since ABI 2.4 counterpart of `insert_pubkey`.
+    pub fn set_storage_pubkey(
+        &self,
+        data: SliceData,
+        pubkey: &PublicKeyData,
+    ) -> Result<SliceData> {
+        let field = self.pubkey_field().ok_or_else(|| AbiError::InvalidData {
+            msg: "Contract has no public key storage field".to_owned(),
+        })?;
+        let value = Self::pubkey_to_value(&field.kind, pubkey)?;
+        self.update_storage_field(data, &field.name, value)
+    }
+
+    /// Sets timestamp storage field value
+    pub fn set_storage_timestamp(&self, data: SliceData, timestamp: u64) -> Result<SliceData> {
+        let field = self.timestamp_field().ok_or_else(|| AbiError::InvalidData {
+            msg: "Contract has no timestamp storage field".to_owned(),
+        })?;
+        let value = match field.kind {
+            ParamType::Uint(size) if size >= 64 => {
+                TokenValue::Uint(Uint::new(timestamp as u128, size))
+            }
+            _ => fail!(AbiError::InvalidData {
+                msg: format!("Timestamp can not be stored as {}", field.kind)
+            }),
+        };
+        self.update_storage_field(data, &field.name, value)
+    }
+
+    fn update_storage_field(
+        &self,
+        data: SliceData,
+        name: &str,
+        value: TokenValue,
+    ) -> Result<SliceData> {
+        let mut tokens = self.decode_storage_fields(data, false)?;
+        if let Some(token) = tokens.iter_mut().find(|token| token.name == name) {
+            token.value = value;
+        }
+        SliceData::load_builder(TokenValue::pack_values_into_chain(
+            &tokens,
+            vec![],
+            &self.abi_version,
+        )?)
+    }
+
+    /// Gets public key from contract data of any layout: from data dictionary before ABI 2.4 and
+    /// from public key storage field since 2.4
+    pub fn pubkey_from_data(&self, data: &SliceData) -> Result<Option<PublicKeyData>> {
+        if self.data_map_supported() {
+            return Self::get_pubkey(data);
+        }
+        let field = match self.pubkey_field() {
+            Some(field) => field,
+            None => return Ok(None),
+        };
+        let fields = self.decode_storage_fields(data.clone(), true)?;
+        Ok(fields
+            .iter()
+            .find(|token| token.name == field.name)
+            .and_then(|token| Self::pubkey_from_value(&token.value)))
+    }
+
+    fn pubkey_to_value(kind: &ParamType, pubkey: &PublicKeyData) -> Result<TokenValue> {
+        match kind {
+            ParamType::Uint(256) => Ok(TokenValue::Uint(Uint {
+                number: BigUint::from_bytes_be(pubkey),
+                size: 256,
+            })),
+            ParamType::FixedBytes(ED25519_PUBLIC_KEY_LENGTH) => {
+                Ok(TokenValue::FixedBytes(pubkey.to_vec()))
+            }
+            ParamType::PublicKey => Ok(TokenValue::PublicKey(Some(*pubkey))),
+            _ => fail!(AbiError::InvalidData {
+                msg: format!("Public key can not be stored as {}", kind)
+            }),
+        }
+    }
+
+    fn pubkey_from_value(value: &TokenValue) -> Option<PublicKeyData> {
+        match value {
+            TokenValue::Uint(uint) => {
+                let bytes = uint.number.to_bytes_be();
+                let mut pubkey = PublicKeyData::default();
+                let offset = pubkey.len().checked_sub(bytes.len())?;
+                pubkey[offset..].copy_from_slice(&bytes);
+                Some(pubkey)
+            }
+            TokenValue::FixedBytes(bytes) => bytes.as_slice().try_into().ok(),
+            TokenValue::PublicKey(pubkey) => *pubkey,
+            _ => None,
+        }
+    }
+
     /// Sets public key into contract data
     pub fn insert_pubkey(data: SliceData, pubkey: &PublicKeyData) -> Result<SliceData> {
         let pubkey_vec = pubkey.to_vec();
@@ -566,7 +1590,8 @@ impl Contract {
         let value = BuilderData::with_raw(pubkey_vec, pubkey_len)?;
 
         let mut map = HashmapE::with_hashmap(Self::DATA_MAP_KEYLEN, data.reference_opt(0));
-        map.set_builder(SliceData::load_builder(0u64.write_to_new_cell()?)?, &value)?;
+        let key = SliceData::load_builder(Self::PUBKEY_DATA_KEY.write_to_new_cell()?)?;
+        map.set_builder(key, &value)?;
         SliceData::load_cell(map.serialize()?)
     }
 
@@ -588,7 +1613,7 @@ impl Contract {
         self.check_init_fields_support()?;
         
         let mut tokens = vec![];
-        for param in &self.fields {
+        for param in self.fields.iter() {
             let token = init_fields
                 .remove_entry(&param.name)
                 .map(|(name, value)| Token { name, value });
@@ -619,6 +1644,61 @@ impl Contract {
         TokenValue::pack_values_into_chain(&tokens, vec![], &self.abi_version)
     }
 
+    /// Encodes constructor call and initial contract data. `init` contains values of data items
+    /// for ABI versions before 2.4 and values of `init` storage fields since 2.4. Public key of
+    /// `sign_key` is stored into data dictionary or into `_pubkey` init field if it is not
+    /// supplied. Contract data of `state_init` is replaced with resulting data.
+    pub fn encode_constructor(
+        &self,
+        state_init: &StateInit,
+        workchain_id: i8,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        mut init: HashMap<String, TokenValue>,
+        sign_key: Option<&Ed25519PrivateKey>,
+    ) -> Result<DeployMessage> {
+        let function = self.function("constructor")?;
+        let pubkey = sign_key.map(|sign_key| sign_key.verifying_key());
+
+        let data = if self.data_map_supported() {
+            let data = match &state_init.data {
+                Some(data) => SliceData::load_cell(data.clone())?,
+                None => SliceData::new_empty(),
+            };
+            let tokens: Vec<Token> = init
+                .into_iter()
+                .map(|(name, value)| Token { name, value })
+                .collect();
+            let mut data = self.update_data(data, &tokens)?;
+            if let Some(pubkey) = &pubkey {
+                data = Self::insert_pubkey(data, pubkey)?;
+            }
+            data.into_cell()
+        } else {
+            if let (Some(pubkey), Some(field)) = (&pubkey, self.pubkey_field()) {
+                if self.init_fields.contains(&field.name) && !init.contains_key(&field.name) {
+                    init.insert(field.name.clone(), Self::pubkey_to_value(&field.kind, pubkey)?);
+                }
+            }
+            self.encode_storage_fields(init)?.into_cell()?
+        };
+
+        let mut state_init = state_init.clone();
+        state_init.data = Some(data);
+        let address = MsgAddressInt::with_standart(
+            None,
+            workchain_id,
+            state_init.serialize()?.repr_hash().into(),
+        )?;
+        let body = function.encode_input(header, input, false, sign_key, Some(address.clone()))?;
+
+        Ok(DeployMessage {
+            body,
+            state_init,
+            address,
+        })
+    }
+
     /// Decode account storage fields
     pub fn decode_storage_fields(
         &self,
@@ -0,0 +1,179 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI coverage of tests.
+//!
+//! `CoverageContract` wraps contract ABI and records functions and events exercised by encoding
+//! and decoding calls made through it. Report lists ABI items and parameter types which were
+//! never exercised, so contract teams can check completeness of their tests.
+
+use crate::{
+    contract::{Contract, DecodedAs, DecodedMessage},
+    param::Param,
+    token::{Token, TokenValue},
+};
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use ever_block::{BuilderData, Ed25519PrivateKey, MsgAddressInt, Result, SliceData};
+
+#[derive(Debug, Default)]
+struct Exercised {
+    functions: BTreeSet<String>,
+    events: BTreeSet<String>,
+}
+
+/// ABI items not exercised through `CoverageContract`. Lists are sorted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoverageReport {
+    pub untested_functions: Vec<String>,
+    pub untested_events: Vec<String>,
+    /// Signatures of parameter types used only by untested functions and events
+    pub untested_types: Vec<String>,
+}
+
+impl CoverageReport {
+    /// Checks if every function and event was exercised
+    pub fn is_complete(&self) -> bool {
+        self.untested_functions.is_empty() && self.untested_events.is_empty()
+    }
+}
+
+/// Contract ABI recording exercised functions and events. Can be shared between test threads.
+#[derive(Debug)]
+pub struct CoverageContract {
+    contract: Contract,
+    exercised: Mutex<Exercised>,
+}
+
+impl CoverageContract {
+    pub fn new(contract: Contract) -> Self {
+        Self {
+            contract,
+            exercised: Mutex::new(Exercised::default()),
+        }
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Marks function as exercised, e.g. if it is called bypassing this wrapper
+    pub fn record_function(&self, name: &str) {
+        self.exercised.lock().unwrap().functions.insert(name.to_owned());
+    }
+
+    /// Marks event as exercised
+    pub fn record_event(&self, name: &str) {
+        self.exercised.lock().unwrap().events.insert(name.to_owned());
+    }
+
+    /// Encodes function call and records the function. See `Function::encode_input`.
+    pub fn encode_input(
+        &self,
+        function: &str,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        let body = self
+            .contract
+            .function(function)?
+            .encode_input(header, input, internal, sign_key, address)?;
+        self.record_function(function);
+        Ok(body)
+    }
+
+    /// Decodes function call and records the function. See `Contract::decode_input`.
+    pub fn decode_input(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        let decoded = self.contract.decode_input(data, internal, allow_partial)?;
+        self.record(&decoded);
+        Ok(decoded)
+    }
+
+    /// Decodes function answer or event and records it. See `Contract::decode_output`.
+    pub fn decode_output(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        let decoded = self.contract.decode_output(data, internal, allow_partial)?;
+        self.record(&decoded);
+        Ok(decoded)
+    }
+
+    fn record(&self, decoded: &DecodedMessage) {
+        match decoded.decoded_as {
+            DecodedAs::FunctionInput | DecodedAs::FunctionOutput => {
+                self.record_function(&decoded.function_name)
+            }
+            DecodedAs::Event => self.record_event(&decoded.function_name),
+        }
+    }
+
+    /// Returns ABI items not exercised so far
+    pub fn report(&self) -> CoverageReport {
+        let exercised = self.exercised.lock().unwrap();
+        let mut report = CoverageReport::default();
+        let mut tested_types = BTreeSet::new();
+        let mut all_types = BTreeSet::new();
+
+        for (name, function) in self.contract.functions() {
+            let params = function.input_params().iter().chain(function.output_params());
+            let tested = exercised.functions.contains(name);
+            Self::collect_types(params, tested, &mut all_types, &mut tested_types);
+            if !tested {
+                report.untested_functions.push(name.clone());
+            }
+        }
+        for (name, event) in self.contract.events() {
+            let tested = exercised.events.contains(name);
+            Self::collect_types(&event.input_params(), tested, &mut all_types, &mut tested_types);
+            if !tested {
+                report.untested_events.push(name.clone());
+            }
+        }
+
+        report.untested_functions.sort();
+        report.untested_events.sort();
+        report.untested_types = all_types.difference(&tested_types).cloned().collect();
+        report
+    }
+
+    fn collect_types<'a>(
+        params: impl IntoIterator<Item = &'a Param>,
+        tested: bool,
+        all_types: &mut BTreeSet<String>,
+        tested_types: &mut BTreeSet<String>,
+    ) {
+        for param in params {
+            let signature = param.kind.type_signature();
+            if tested {
+                tested_types.insert(signature.clone());
+            }
+            all_types.insert(signature);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_coverage.rs"]
+mod tests;
@@ -0,0 +1,284 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Project-specific parameter types.
+//!
+//! Custom type is referenced in ABI JSON by its name (e.g. `"type": "decimal"`) and is encoded
+//! as its base ABI type. Custom type callbacks convert JSON representation of values and may
+//! check or transform token values before serialization and after deserialization.
+//! Custom types can be used for function, event and storage field parameters and for tuple
+//! components, but not as array items, map values or optional values.
+
+use crate::{
+    contract::Contract,
+    error::AbiError,
+    param::Param,
+    param_type::{read_type, ParamType},
+    token::{Detokenizer, Token, TokenValue, Tokenizer},
+};
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use ever_block::{fail, Result};
+
+/// Custom parameter type behaviour. All callbacks have identity default implementation.
+pub trait CustomType: Send + Sync {
+    /// Type values are encoded as. Tuples are not supported as base types.
+    fn base_type(&self) -> ParamType;
+
+    /// Converts JSON value of the custom type into JSON value of the base type
+    fn tokenize(&self, value: &Value) -> Result<Value> {
+        Ok(value.clone())
+    }
+
+    /// Converts JSON value of the base type into JSON value of the custom type
+    fn detokenize(&self, value: Value) -> Result<Value> {
+        Ok(value)
+    }
+
+    /// Checks or transforms value before serialization
+    fn serialize(&self, value: TokenValue) -> Result<TokenValue> {
+        Ok(value)
+    }
+
+    /// Checks or transforms value after deserialization
+    fn deserialize(&self, value: TokenValue) -> Result<TokenValue> {
+        Ok(value)
+    }
+}
+
+/// Registry of custom types available in ABI
+#[derive(Clone, Default)]
+pub struct CustomTypeRegistry {
+    types: HashMap<String, Arc<dyn CustomType>>,
+}
+
+impl CustomTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers custom type. Fails if the name is a standard ABI type or is already registered.
+    pub fn register(&mut self, name: &str, custom_type: Arc<dyn CustomType>) -> Result<()> {
+        if read_type(name).is_ok() || self.types.contains_key(name) {
+            fail!(AbiError::InvalidName {
+                name: format!("type `{}` is already defined", name)
+            });
+        }
+        if let ParamType::Tuple(_) = custom_type.base_type() {
+            fail!(AbiError::InvalidName {
+                name: format!("tuple can not be base type of `{}`", name)
+            });
+        }
+        self.types.insert(name.to_owned(), custom_type);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn CustomType>> {
+        self.types.get(name)
+    }
+
+    /// Loads contract ABI with custom types replaced by their base types
+    pub fn load_contract(&self, abi: &str) -> Result<CustomContract> {
+        let mut abi: Value = serde_json::from_str(abi)?;
+        let mut custom = CustomParamsMap::new();
+        for section in ["functions", "events"] {
+            if let Some(items) = abi.get_mut(section).and_then(Value::as_array_mut) {
+                for item in items {
+                    let name = item["name"].as_str().unwrap_or_default().to_owned();
+                    for kind in ["inputs", "outputs"] {
+                        let params = self.resolve_params(item.get_mut(kind), &[])?;
+                        custom.insert((format!("{}.{}", section, kind), name.clone()), params);
+                    }
+                }
+            }
+        }
+        let params = self.resolve_params(abi.get_mut("fields"), &[])?;
+        custom.insert(("fields".to_owned(), String::new()), params);
+
+        Ok(CustomContract {
+            contract: Contract::load(abi.to_string().as_bytes())?,
+            registry: self.clone(),
+            custom,
+        })
+    }
+
+    // replaces custom types in params JSON array and returns paths of replaced params
+    fn resolve_params(
+        &self,
+        params: Option<&mut Value>,
+        prefix: &[String],
+    ) -> Result<CustomParams> {
+        let mut result = vec![];
+        let params = match params.and_then(Value::as_array_mut) {
+            Some(params) => params,
+            None => return Ok(result),
+        };
+        for param in params {
+            let mut path = prefix.to_vec();
+            path.push(param["name"].as_str().unwrap_or_default().to_owned());
+            let type_name = param["type"].as_str().unwrap_or_default().to_owned();
+            if let Some(custom_type) = self.types.get(&type_name) {
+                param["type"] = custom_type.base_type().type_signature().into();
+                result.push((path, type_name));
+            } else {
+                result.append(&mut self.resolve_params(param.get_mut("components"), &path)?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+// paths of custom type params and names of their types
+type CustomParams = Vec<(Vec<String>, String)>;
+// custom params of ABI items by section and item name
+type CustomParamsMap = HashMap<(String, String), CustomParams>;
+
+/// Contract ABI loaded with custom types
+#[derive(Clone)]
+pub struct CustomContract {
+    contract: Contract,
+    registry: CustomTypeRegistry,
+    custom: CustomParamsMap,
+}
+
+impl CustomContract {
+    /// Returns contract ABI with custom types replaced by their base types
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Parses function input JSON
+    pub fn tokenize_input(&self, function: &str, values: &Value) -> Result<Vec<Token>> {
+        let params = self.contract.function(function)?.input_params();
+        self.tokenize(params, self.custom_params("functions.inputs", function), values)
+    }
+
+    /// Parses function output JSON
+    pub fn tokenize_output(&self, function: &str, values: &Value) -> Result<Vec<Token>> {
+        let params = self.contract.function(function)?.output_params();
+        self.tokenize(params, self.custom_params("functions.outputs", function), values)
+    }
+
+    /// Parses storage fields JSON
+    pub fn tokenize_fields(&self, values: &Value) -> Result<Vec<Token>> {
+        self.tokenize(self.contract.fields(), self.custom_params("fields", ""), values)
+    }
+
+    /// Converts decoded function input into JSON
+    pub fn detokenize_input(&self, function: &str, tokens: &[Token]) -> Result<Value> {
+        self.contract.function(function)?;
+        self.detokenize(self.custom_params("functions.inputs", function), tokens)
+    }
+
+    /// Converts decoded function output into JSON
+    pub fn detokenize_output(&self, function: &str, tokens: &[Token]) -> Result<Value> {
+        self.contract.function(function)?;
+        self.detokenize(self.custom_params("functions.outputs", function), tokens)
+    }
+
+    /// Converts decoded event into JSON
+    pub fn detokenize_event(&self, event: &str, tokens: &[Token]) -> Result<Value> {
+        self.contract.event(event)?;
+        self.detokenize(self.custom_params("events.inputs", event), tokens)
+    }
+
+    /// Converts decoded storage fields into JSON
+    pub fn detokenize_fields(&self, tokens: &[Token]) -> Result<Value> {
+        self.detokenize(self.custom_params("fields", ""), tokens)
+    }
+
+    fn custom_params(&self, section: &str, name: &str) -> &[(Vec<String>, String)] {
+        self.custom
+            .get(&(section.to_owned(), name.to_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn custom_type(&self, name: &str) -> Result<&Arc<dyn CustomType>> {
+        self.registry.get(name).ok_or_else(|| {
+            AbiError::InvalidName {
+                name: name.to_owned(),
+            }
+            .into()
+        })
+    }
+
+    fn tokenize(
+        &self,
+        params: &[Param],
+        custom: &[(Vec<String>, String)],
+        values: &Value,
+    ) -> Result<Vec<Token>> {
+        let mut values = values.clone();
+        for (path, type_name) in custom {
+            if let Some(value) = values.pointer_mut(&json_pointer(path)) {
+                *value = self.custom_type(type_name)?.tokenize(value)?;
+            }
+        }
+        let mut tokens = Tokenizer::tokenize_all_params(params, &values)?;
+        for (path, type_name) in custom {
+            if let Some(value) = token_at_mut(&mut tokens, path) {
+                *value = self.custom_type(type_name)?.serialize(value.clone())?;
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn detokenize(&self, custom: &[(Vec<String>, String)], tokens: &[Token]) -> Result<Value> {
+        let mut tokens = tokens.to_vec();
+        for (path, type_name) in custom {
+            if let Some(value) = token_at_mut(&mut tokens, path) {
+                *value = self.custom_type(type_name)?.deserialize(value.clone())?;
+            }
+        }
+        let mut values = Detokenizer::detokenize_to_json_value(&tokens)?;
+        for (path, type_name) in custom {
+            if let Some(value) = values.pointer_mut(&json_pointer(path)) {
+                *value = self.custom_type(type_name)?.detokenize(value.take())?;
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn json_pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|name| format!("/{}", name.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn token_at_mut<'a>(tokens: &'a mut [Token], path: &[String]) -> Option<&'a mut TokenValue> {
+    let (name, rest) = path.split_first()?;
+    let token = tokens.iter_mut().find(|token| &token.name == name)?;
+    let value = unwrap_ref(&mut token.value);
+    if rest.is_empty() {
+        return Some(value);
+    }
+    match value {
+        TokenValue::Tuple(tokens) => token_at_mut(tokens, rest),
+        _ => None,
+    }
+}
+
+fn unwrap_ref(value: &mut TokenValue) -> &mut TokenValue {
+    match value {
+        TokenValue::Ref(inner) => unwrap_ref(inner),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_custom_types.rs"]
+mod tests;
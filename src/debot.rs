@@ -0,0 +1,110 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! DeBot interfaces.
+//!
+//! DeBot calls interface by sending internal message to the address in DeBot workchain with
+//! interface ID as account ID. Interface ID is the SHA256 hash of interface name.
+
+use crate::{
+    contract::{Contract, DecodedMessage},
+    error::AbiError,
+    token::Token,
+};
+
+use ever_block::{sha256_digest, AccountId, BuilderData, MsgAddressInt, Result, SliceData};
+
+/// Workchain of DeBot interface addresses
+pub const DEBOT_WC: i8 = -31;
+
+/// Returns hex-encoded ID of DeBot interface with provided name
+pub fn interface_id(name: &str) -> String {
+    hex::encode(sha256_digest(name.as_bytes()))
+}
+
+/// DeBot interface described by its ABI
+#[derive(Clone, Debug)]
+pub struct DebotInterface {
+    name: String,
+    id: String,
+    abi: Contract,
+}
+
+impl DebotInterface {
+    /// Creates interface with ID computed from its name
+    pub fn new(name: &str, abi: Contract) -> Self {
+        Self::with_id(name, &interface_id(name), abi)
+    }
+
+    /// Creates interface with fixed ID
+    pub fn with_id(name: &str, id: &str, abi: Contract) -> Self {
+        Self {
+            name: name.to_owned(),
+            id: id.to_lowercase(),
+            abi,
+        }
+    }
+
+    /// Loads interface ABI from JSON. ID is computed from interface name.
+    pub fn load(name: &str, abi: &str) -> Result<Self> {
+        Ok(Self::new(name, Contract::load(abi.as_bytes())?))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns hex-encoded interface ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn abi(&self) -> &Contract {
+        &self.abi
+    }
+
+    /// Returns address DeBot sends interface calls to
+    pub fn address(&self) -> Result<MsgAddressInt> {
+        let id: [u8; 32] = hex::decode(&self.id)
+            .ok()
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| AbiError::InvalidData {
+                msg: format!("Interface ID {} is not a 32 bytes hex string", self.id),
+            })?;
+        MsgAddressInt::with_standart(None, DEBOT_WC, AccountId::from(id))
+    }
+
+    /// Checks if the address is the address of this interface
+    pub fn is_my_address(&self, address: &MsgAddressInt) -> bool {
+        match self.address() {
+            Ok(my_address) => &my_address == address,
+            Err(_) => false,
+        }
+    }
+
+    /// Encodes interface function call body
+    pub fn encode_call(&self, function: &str, input: &[Token]) -> Result<BuilderData> {
+        self.abi
+            .function(function)?
+            .encode_input(&Default::default(), input, true, None, None)
+    }
+
+    /// Decodes interface function call body
+    pub fn decode_call(&self, body: SliceData) -> Result<DecodedMessage> {
+        self.abi.decode_input(body, true, false)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_debot.rs"]
+mod tests;
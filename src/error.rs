@@ -30,6 +30,9 @@ pub enum AbiError {
     #[error("Invalid function id: {:X}", .id)]
     InvalidFunctionId { id: u32 },
 
+    #[error("Body matched `{}` but can not be decoded: {}", .name, .err)]
+    MatchedItemDecodeFailed { name: String, err: String },
+
     #[error("Deserialization error {}: {}", .msg, .cursor)]
     DeserializationError {
         msg: &'static str,
@@ -48,29 +51,33 @@ pub enum AbiError {
     #[error("Token types do not match expected function parameter types")]
     WrongParameterType,
 
+    /// `path` is JSON pointer of the value relative to the tokenized value, e.g. `/t2/1/b`
     #[error(
-        "Wrong data format in `{}` parameter:\n{}\n{} expected",
-        .name, .val, .expected
+        "Wrong data format in `{}` parameter{}:\n{}\n{} expected",
+        .name, at_path(.path), .val, .expected
     )]
     WrongDataFormat {
         val: serde_json::Value,
         name: String,
+        path: String,
         expected: String,
     },
 
     #[error(
-        "Invalid parameter `{}` length, expected {}:\n{}",
-        .name, .expected, .val
+        "Invalid parameter `{}`{} length, expected {}:\n{}",
+        .name, at_path(.path), .expected, .val
     )]
     InvalidParameterLength {
         name: String,
+        path: String,
         val: serde_json::Value,
         expected: String,
     },
 
-    #[error("Invalid parameter `{}` value:\n{}\n{}", .name, .val, .err)]
+    #[error("Invalid parameter `{}`{} value:\n{}\n{}", .name, at_path(.path), .val, .err)]
     InvalidParameterValue {
         name: String,
+        path: String,
         val: serde_json::Value,
         err: String,
     },
@@ -84,6 +91,12 @@ pub enum AbiError {
     #[error("Invalid version: {}", .0)]
     InvalidVersion(String),
 
+    #[error(
+        "Ambiguous ABI version: `ABI version` is {}, but `version` is {}",
+        .abi_version, .version
+    )]
+    AmbiguousVersion { abi_version: u8, version: AbiVersion },
+
     #[error("Wrong function ID: {:x}", .id)]
     WrongId { id: u32 },
 
@@ -103,6 +116,93 @@ pub enum AbiError {
     )]
     AddressRequired,
 
-    #[error("Wrong data layout")]
-    WrongDataLayout
+    /// Value is not placed according to deterministic layout rules. Cell usage and value sizes
+    /// are maximum sizes for ABI 2.2 and later and actual sizes for earlier versions.
+    #[error(
+        "Wrong data layout{}: {} (ABI {}, cell uses {} bits and {} refs, value takes {} bits \
+            and {} refs)",
+        layout_param(.param), .rule, .version, .used_bits, .used_refs, .value_bits, .value_refs
+    )]
+    WrongDataLayout {
+        /// Parameter path with tuple components separated by `.`, empty if unknown
+        param: String,
+        rule: &'static str,
+        version: AbiVersion,
+        used_bits: usize,
+        used_refs: usize,
+        value_bits: usize,
+        value_refs: usize,
+    },
+
+    #[error("Parameter type nesting is deeper than {} levels", .max_depth)]
+    TypeTooDeep { max_depth: usize },
+
+    /// Encoded body decodes to other values. `path` is JSON pointer of the first differing
+    /// value, empty if parameter lists differ.
+    #[error(
+        "Encoded body does not decode to the same values{}: encoded {}, decoded {}",
+        at_path(.path), .encoded, .decoded
+    )]
+    RoundtripMismatch {
+        path: String,
+        encoded: String,
+        decoded: String,
+    },
+
+    #[error("No value for parameter `{}`", .name)]
+    MissingToken { name: String },
+
+    #[error("Value {} is not {}", .value, .expected)]
+    UnexpectedValueType {
+        expected: &'static str,
+        value: String,
+    },
+
+    /// Golden corpus cases which failed, each entry is case name followed by the failure reason
+    #[error("Golden cases failed: {}", .failures.join("; "))]
+    GoldenCasesFailed { failures: Vec<String> },
+}
+
+fn at_path(path: &str) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!(" at `{}`", path)
+    }
+}
+
+fn layout_param(param: &str) -> String {
+    if param.is_empty() {
+        String::new()
+    } else {
+        format!(" of `{}` parameter", param)
+    }
+}
+
+impl AbiError {
+    /// Prepends JSON pointer `prefix` to the path of value format errors
+    pub(crate) fn prepend_path(mut err: ever_block::Error, prefix: &str) -> ever_block::Error {
+        match err.downcast_mut::<AbiError>() {
+            Some(AbiError::WrongDataFormat { path, .. })
+            | Some(AbiError::InvalidParameterLength { path, .. })
+            | Some(AbiError::InvalidParameterValue { path, .. }) => path.insert_str(0, prefix),
+            _ => {}
+        }
+        err
+    }
+
+    /// Prepends parameter name to the parameter path of layout errors
+    pub(crate) fn prepend_layout_param(
+        mut err: ever_block::Error,
+        name: &str,
+    ) -> ever_block::Error {
+        if let Some(AbiError::WrongDataLayout { param, .. }) = err.downcast_mut::<AbiError>() {
+            if param.is_empty() {
+                *param = name.to_owned();
+            } else {
+                *param = format!("{}.{}", name, param);
+            }
+        }
+        err
+    }
 }
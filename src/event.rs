@@ -11,13 +11,17 @@
 * limitations under the License.
 */
 
-use crate::contract::{AbiVersion, SerdeEvent};
+use crate::contract::{AbiVersion, DecodedMessage, SerdeEvent};
 use crate::error::AbiError;
+use crate::token::ReadOptions;
 use crate::{Function, Param, Token, TokenValue};
-use ever_block::{Result, SliceData};
+use ever_block::{fail, BuilderData, Result, Serializable, SliceData};
+
+/// Closure extracting event parameter value from decoded event tokens
+pub type TopicExtractor = Box<dyn Fn(&[Token]) -> Option<TokenValue> + Send + Sync>;
 
 /// Contract event specification.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Event {
     /// ABI version
     pub abi_version: AbiVersion,
@@ -80,15 +84,60 @@ impl Event {
         self.id
     }
 
+    /// Encodes event emitting message body. Can be used by emulators to produce events.
+    pub fn encode(&self, input: &[Token]) -> Result<BuilderData> {
+        if !Token::types_check(input, &self.inputs) {
+            fail!(AbiError::WrongParameterType);
+        }
+        let cells = vec![self.get_id().write_to_new_cell()?.into()];
+        TokenValue::pack_values_into_chain(input, cells, &self.abi_version)
+    }
+
+    /// Returns event ID and closures extracting every event parameter from decoded tokens
+    pub fn topics(&self) -> (u32, Vec<(String, TopicExtractor)>) {
+        let extractors = self
+            .inputs
+            .iter()
+            .map(|param| {
+                let name = param.name.clone();
+                let extractor: TopicExtractor = Box::new(move |tokens: &[Token]| {
+                    tokens
+                        .iter()
+                        .find(|token| token.name == name)
+                        .map(|token| token.value.clone())
+                });
+                (param.name.clone(), extractor)
+            })
+            .collect();
+        (self.get_id(), extractors)
+    }
+
     /// Parses the ABI function call to list of tokens.
-    pub fn decode_input(&self, mut data: SliceData, allow_partial: bool) -> Result<Vec<Token>> {
+    pub fn decode_input(&self, data: SliceData, allow_partial: bool) -> Result<Vec<Token>> {
+        self.decode_input_with_options(data, allow_partial, ReadOptions::default())
+    }
+
+    pub(crate) fn decode_input_with_options(
+        &self,
+        mut data: SliceData,
+        allow_partial: bool,
+        options: ReadOptions,
+    ) -> Result<Vec<Token>> {
         let id = data.get_next_u32()?;
 
         if id != self.get_id() {
             Err(AbiError::WrongId { id })?
         }
 
-        TokenValue::decode_params(&self.input_params(), data, &self.abi_version, allow_partial)
+        TokenValue::decode_params_with_options(
+            &self.input_params(),
+            data.into(),
+            &self.abi_version,
+            allow_partial,
+            options,
+            true,
+        )
+        .map(|(tokens, _)| tokens)
     }
 
     /// Decodes function id from contract answer
@@ -102,3 +151,42 @@ impl Event {
         Ok(self.get_id() == decoded_id)
     }
 }
+
+/// Matcher of decoded events by name and parameter values
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventFilter {
+    name: String,
+    conditions: Vec<(String, TokenValue)>,
+}
+
+impl EventFilter {
+    /// Creates filter matching all events with provided name
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            conditions: vec![],
+        }
+    }
+
+    /// Adds condition: event parameter `param` should be equal to `value`
+    pub fn where_eq(mut self, param: &str, value: TokenValue) -> Self {
+        self.conditions.push((param.to_owned(), value));
+        self
+    }
+
+    /// Checks if decoded message is an event satisfying the filter
+    pub fn matches(&self, message: &DecodedMessage) -> bool {
+        message.event().is_some()
+            && message.function_name == self.name
+            && self.matches_tokens(&message.tokens)
+    }
+
+    /// Checks if decoded event parameters satisfy filter conditions. Event name is not checked.
+    pub fn matches_tokens(&self, tokens: &[Token]) -> bool {
+        self.conditions.iter().all(|(param, value)| {
+            tokens
+                .iter()
+                .any(|token| &token.name == param && &token.value == value)
+        })
+    }
+}
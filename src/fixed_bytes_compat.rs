@@ -0,0 +1,200 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Decoding of `fixedbytes<N>` values encoded with pre-2.4 layout.
+//!
+//! ABI 2.4 stores `fixedbytes<N>` values inline, while older encoders put them into a separate
+//! cell as `bytes`. Compat decoding tries 2.4 layout first and falls back to the legacy one, so
+//! bodies produced by not yet updated encoders can be decoded during migration. The fallback is
+//! reported in results to let callers find such encoders.
+
+use crate::{
+    contract::AbiVersion,
+    error::AbiError,
+    function::Function,
+    param::Param,
+    param_type::ParamType,
+    token::{Cursor, Token, TokenValue},
+};
+
+use ever_block::{fail, Result, SliceData};
+
+/// Values decoded with fallback to legacy `fixedbytes<N>` layout
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompatDecoded {
+    pub tokens: Vec<Token>,
+    /// Values were decoded with pre-2.4 layout: `fixedbytes<N>` stored as `bytes` in reference
+    pub legacy_fixed_bytes: bool,
+}
+
+impl TokenValue {
+    /// Decodes provided params falling back to pre-2.4 `fixedbytes<N>` layout if 2.4 layout
+    /// does not match the data
+    pub fn decode_params_fixed_bytes_compat(
+        params: &[Param],
+        data: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> Result<CompatDecoded> {
+        decode_params_compat(params, data.into(), abi_version, allow_partial)
+    }
+}
+
+impl Function {
+    /// Parses the ABI function call falling back to pre-2.4 `fixedbytes<N>` layout
+    pub fn decode_input_fixed_bytes_compat(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<CompatDecoded> {
+        let (_, id, cursor) = Self::decode_header_with_signature(
+            &self.abi_version,
+            data,
+            &self.header,
+            internal,
+            self.signed,
+        )?;
+        if id != self.get_input_id() {
+            fail!(AbiError::WrongId { id })
+        }
+        decode_params_compat(self.input_params(), cursor, &self.abi_version, allow_partial)
+    }
+
+    /// Parses the ABI function output falling back to pre-2.4 `fixedbytes<N>` layout
+    pub fn decode_output_fixed_bytes_compat(
+        &self,
+        mut data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<CompatDecoded> {
+        let id = data.get_next_u32()?;
+        if !internal && id != self.get_output_id() {
+            fail!(AbiError::WrongId { id })
+        }
+        decode_params_compat(self.output_params(), data.into(), &self.abi_version, allow_partial)
+    }
+}
+
+fn decode_params_compat(
+    params: &[Param],
+    cursor: Cursor,
+    abi_version: &AbiVersion,
+    allow_partial: bool,
+) -> Result<CompatDecoded> {
+    let decode = |params: &[Param], cursor: Cursor| {
+        TokenValue::decode_params_with_cursor(params, cursor, abi_version, allow_partial, true)
+    };
+    let err = match decode(params, cursor.clone()) {
+        Ok((tokens, _)) => {
+            return Ok(CompatDecoded {
+                tokens,
+                legacy_fixed_bytes: false,
+            })
+        }
+        Err(err) => err,
+    };
+    let legacy_params: Vec<Param> = params
+        .iter()
+        .map(|param| Param::new(&param.name, legacy_type(&param.kind)))
+        .collect();
+    if !abi_version.at_least(2, 4) || legacy_params.as_slice() == params {
+        return Err(err);
+    }
+    let (tokens, _) = decode(&legacy_params, cursor).map_err(|_| err)?;
+    Ok(CompatDecoded {
+        tokens: restore_tokens(params, tokens)?,
+        legacy_fixed_bytes: true,
+    })
+}
+
+fn restore_tokens(params: &[Param], tokens: Vec<Token>) -> Result<Vec<Token>> {
+    params
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| {
+            Ok(Token::new(&token.name, restore_value(&param.kind, token.value)?))
+        })
+        .collect()
+}
+// type with `fixedbytes<N>` values replaced by `bytes` having the same layout as pre-2.4
+// `fixedbytes<N>`
+fn legacy_type(param_type: &ParamType) -> ParamType {
+    match param_type {
+        ParamType::FixedBytes(_) => ParamType::Bytes,
+        ParamType::Tuple(params) => ParamType::Tuple(
+            params
+                .iter()
+                .map(|param| Param::new(&param.name, legacy_type(&param.kind)))
+                .collect(),
+        ),
+        ParamType::Array(item) => ParamType::Array(Box::new(legacy_type(item))),
+        ParamType::FixedArray(item, size) => {
+            ParamType::FixedArray(Box::new(legacy_type(item)), *size)
+        }
+        ParamType::Map(key, value) => ParamType::Map(key.clone(), Box::new(legacy_type(value))),
+        ParamType::Optional(inner) => ParamType::Optional(Box::new(legacy_type(inner))),
+        ParamType::Ref(inner) => ParamType::Ref(Box::new(legacy_type(inner))),
+        param_type => param_type.clone(),
+    }
+}
+
+// converts value decoded with `legacy_type` back to the value of original type
+fn restore_value(param_type: &ParamType, value: TokenValue) -> Result<TokenValue> {
+    let restore_items = |item_type: &ParamType, items: Vec<TokenValue>| {
+        items
+            .into_iter()
+            .map(|item| restore_value(item_type, item))
+            .collect::<Result<Vec<_>>>()
+    };
+    Ok(match (param_type, value) {
+        (ParamType::FixedBytes(size), TokenValue::Bytes(data)) => {
+            if data.len() != *size {
+                fail!(AbiError::InvalidData {
+                    msg: "Size of fixed bytes does not correspond to expected size".to_owned()
+                })
+            }
+            TokenValue::FixedBytes(data)
+        }
+        (ParamType::Tuple(params), TokenValue::Tuple(tokens)) => {
+            TokenValue::Tuple(restore_tokens(params, tokens)?)
+        }
+        (ParamType::Array(item), TokenValue::Array(_, items)) => {
+            TokenValue::Array(item.as_ref().clone(), restore_items(item, items)?)
+        }
+        (ParamType::FixedArray(item, _), TokenValue::FixedArray(_, items)) => {
+            TokenValue::FixedArray(item.as_ref().clone(), restore_items(item, items)?)
+        }
+        (ParamType::Map(key, value), TokenValue::Map(_, _, map)) => TokenValue::Map(
+            key.as_ref().clone(),
+            value.as_ref().clone(),
+            map.into_iter()
+                .map(|(key, item)| Ok((key, restore_value(value, item)?)))
+                .collect::<Result<_>>()?,
+        ),
+        (ParamType::Optional(inner), TokenValue::Optional(_, value)) => TokenValue::Optional(
+            inner.as_ref().clone(),
+            value
+                .map(|value| restore_value(inner, *value).map(Box::new))
+                .transpose()?,
+        ),
+        (ParamType::Ref(inner), TokenValue::Ref(value)) => {
+            TokenValue::Ref(Box::new(restore_value(inner, *value)?))
+        }
+        (_, value) => value,
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/test_fixed_bytes_compat.rs"]
+mod tests;
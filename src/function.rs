@@ -14,13 +14,18 @@
 //! Contract function call builder.
 
 use crate::{
-    contract::{AbiVersion, SerdeFunction, ABI_VERSION_1_0, ABI_VERSION_2_3},
+    contract::{AbiVersion, HeaderDefaults, SerdeFunction, ABI_VERSION_1_0},
     error::AbiError,
-    param::Param,
-    token::{SerializedValue, Token, TokenValue, Cursor},
+    param::{HeaderParam, Param},
+    token::{
+        AmountFormat, ChainReason, Cursor, Detokenizer, EncodeReport, ReadOptions,
+        SerializedValue, Token, TokenValue,
+    },
     ParamType, PublicKeyData, SignatureData,
 };
 
+use chrono::prelude::Utc;
+use num_bigint::BigInt;
 use std::collections::HashMap;
 use ever_block::{MsgAddressInt, Serializable};
 use ever_block::{
@@ -28,8 +33,187 @@ use ever_block::{
     SliceData, ED25519_SIGNATURE_LENGTH, MAX_DATA_BYTES,
 };
 
-/// Contract function specification.
+/// Number of fractional digits of coin amounts in `Function::signing_summary`
+pub const SIGNING_SUMMARY_DECIMALS: u32 = 9;
+
+/// Function call header decoded without `Function` instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderInfo {
+    /// Message signature. Always `None` for internal messages
+    pub signature: Option<Vec<u8>>,
+    /// Header parameters values
+    pub tokens: Vec<Token>,
+    /// Function ID
+    pub function_id: u32,
+}
+
+impl HeaderInfo {
+    /// Returns `time` header value if present
+    pub fn time(&self) -> Option<u64> {
+        self.tokens.iter().find_map(|token| match token.value {
+            TokenValue::Time(time) => Some(time),
+            _ => None,
+        })
+    }
+
+    /// Returns `expire` header value if present
+    pub fn expire(&self) -> Option<u32> {
+        self.tokens.iter().find_map(|token| match token.value {
+            TokenValue::Expire(expire) => Some(expire),
+            _ => None,
+        })
+    }
+
+    /// Returns `pubkey` header value if present
+    pub fn pubkey(&self) -> Option<PublicKeyData> {
+        self.tokens.iter().find_map(|token| match token.value {
+            TokenValue::PublicKey(pubkey) => pubkey,
+            _ => None,
+        })
+    }
+}
+
+/// Value of external call header parameter. Values of standard parameters are bound to their
+/// names and types, so they can not be mixed up. Custom parameter values are checked against
+/// declared header parameters on encoding.
 #[derive(Debug, Clone, PartialEq)]
+pub enum HeaderValue {
+    Time(u64),
+    Expire(u32),
+    PublicKey(Option<PublicKeyData>),
+    /// Value of custom header parameter declared in ABI
+    Custom { name: String, value: TokenValue },
+}
+
+impl HeaderValue {
+    /// Creates custom header parameter value. Fails if `name` is a standard header parameter name.
+    pub fn custom(name: &str, value: TokenValue) -> Result<Self> {
+        if HeaderParam::from_name(name).is_ok() {
+            fail!(AbiError::InvalidName {
+                name: format!("`{}` is a standard header parameter", name)
+            });
+        }
+        Ok(HeaderValue::Custom {
+            name: name.to_owned(),
+            value,
+        })
+    }
+
+    /// Returns name of header parameter the value is set for
+    pub fn name(&self) -> &str {
+        match self {
+            HeaderValue::Time(_) => HeaderParam::Time.name(),
+            HeaderValue::Expire(_) => HeaderParam::Expire.name(),
+            HeaderValue::PublicKey(_) => HeaderParam::PublicKey.name(),
+            HeaderValue::Custom { name, .. } => name,
+        }
+    }
+
+    /// Collects header values into the map accepted by encoding functions. Fails if a parameter
+    /// value is set twice.
+    pub fn to_header_map(values: &[HeaderValue]) -> Result<HashMap<String, TokenValue>> {
+        let mut header = HashMap::new();
+        for value in values {
+            if header.insert(value.name().to_owned(), value.clone().into()).is_some() {
+                fail!(AbiError::InvalidData {
+                    msg: format!("Header parameter `{}` value is set twice", value.name())
+                });
+            }
+        }
+        Ok(header)
+    }
+}
+
+impl From<HeaderValue> for TokenValue {
+    fn from(value: HeaderValue) -> Self {
+        match value {
+            HeaderValue::Time(time) => TokenValue::Time(time),
+            HeaderValue::Expire(expire) => TokenValue::Expire(expire),
+            HeaderValue::PublicKey(pubkey) => TokenValue::PublicKey(pubkey),
+            HeaderValue::Custom { value, .. } => value,
+        }
+    }
+}
+
+/// Additional data wrapping standard function call: `prefix` is placed right before function ID
+/// and `suffix` after function parameters. Both are placed into cells chain according to ABI
+/// layout rules same as function parameters.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadEnvelope {
+    pub prefix: Option<SerializedValue>,
+    pub suffix: Option<SerializedValue>,
+}
+
+impl PayloadEnvelope {
+    fn check(&self) -> Result<()> {
+        for value in self.prefix.iter().chain(self.suffix.iter()) {
+            if value.data.bits_used() > value.max_bits
+                || value.data.references_used() > value.max_refs
+            {
+                fail!(AbiError::InvalidData {
+                    msg: "Envelope data exceeds its layout size".to_owned()
+                });
+            }
+            // one reference should remain free for chaining
+            if value.max_bits > BuilderData::bits_capacity()
+                || value.max_refs >= BuilderData::references_capacity()
+            {
+                fail!(AbiError::InvalidData {
+                    msg: format!(
+                        "Envelope data ({} bits, {} refs) does not fit into a chain cell",
+                        value.max_bits, value.max_refs
+                    )
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Location of placeholder signature in external call body encoded by
+/// `Function::encode_input_with_placeholder`. Allows signing already serialized body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePatch {
+    /// Name of the signer which should provide the signature
+    pub signer: String,
+    /// Offset of the signature in the root cell data
+    pub offset_bits: usize,
+    /// Signature length in bytes
+    pub len: usize,
+    /// Hash to be signed
+    pub hash: Vec<u8>,
+}
+
+impl SignaturePatch {
+    /// Replaces placeholder in the encoded body with `signature`
+    pub fn apply(&self, body: BuilderData, signature: &SignatureData) -> Result<BuilderData> {
+        if signature.len() != self.len {
+            fail!(AbiError::InvalidInputData {
+                msg: format!("Signature must be {} bytes length", self.len)
+            });
+        }
+        let mut slice = SliceData::load_builder(body)?;
+        let mut builder = BuilderData::new();
+        builder.append_bytestring(&slice.get_next_slice(self.offset_bits)?)?;
+        slice.move_by(self.len * 8)?;
+        builder.append_raw(signature, self.len * 8)?;
+        builder.append_builder(&slice.as_builder())?;
+        Ok(builder)
+    }
+}
+
+/// Lightweight key of contract function for routing tables: function input ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FunctionId(pub u32);
+
+impl From<&Function> for FunctionId {
+    fn from(function: &Function) -> Self {
+        function.id()
+    }
+}
+
+/// Contract function specification.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Function {
     /// ABI version
     pub abi_version: AbiVersion,
@@ -45,6 +229,10 @@ pub struct Function {
     pub input_id: u32,
     /// Function ID for outbound messages
     pub output_id: u32,
+    /// Defaults of external call header values missing in encoded header
+    pub header_defaults: HeaderDefaults,
+    /// External calls contain signature slot. Calls of never signed functions start with header.
+    pub signed: bool,
 }
 
 impl Function {
@@ -53,6 +241,7 @@ impl Function {
         abi_version: AbiVersion,
         serde_function: SerdeFunction,
         header: Vec<Param>,
+        header_defaults: HeaderDefaults,
     ) -> Self {
         let mut function = Function {
             abi_version,
@@ -62,6 +251,8 @@ impl Function {
             outputs: serde_function.outputs,
             input_id: 0,
             output_id: 0,
+            header_defaults,
+            signed: serde_function.signed,
         };
         if let Some(id) = serde_function.id {
             function.input_id = id;
@@ -99,6 +290,26 @@ impl Function {
         self.outputs.len() != 0
     }
 
+    /// Returns JSON object with sample values for all input parameters. Can be used as
+    /// a parameters template for UI forms and test fixtures.
+    pub fn sample_input_json(&self) -> Result<serde_json::Value> {
+        let tokens: Vec<Token> = self
+            .inputs
+            .iter()
+            .map(|param| Token::new(&param.name, TokenValue::sample_value(&param.kind)))
+            .collect();
+        Detokenizer::detokenize_to_json_value(&tokens)
+    }
+
+    /// Returns JSON Schema of the function input parameters object. Can be used to validate
+    /// user input before encoding function call.
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let mut schema = ParamType::object_schema(&self.inputs);
+        schema["$schema"] = "http://json-schema.org/draft-07/schema#".into();
+        schema["title"] = self.name.clone().into();
+        schema
+    }
+
     /// Retruns ABI function signature
     pub fn get_function_signature(&self) -> String {
         let mut input_types = vec![];
@@ -153,6 +364,11 @@ impl Function {
         Self::calc_function_id(&signature)
     }
 
+    /// Returns lightweight key of the function
+    pub fn id(&self) -> FunctionId {
+        FunctionId(self.input_id)
+    }
+
     /// Returns ID for call message
     pub fn get_input_id(&self) -> u32 {
         self.input_id
@@ -165,16 +381,34 @@ impl Function {
 
     /// Parses the ABI function output to list of tokens.
     pub fn decode_output(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<Vec<Token>> {
+        self.decode_output_with_options(data, internal, allow_partial, ReadOptions::default())
+    }
+
+    pub(crate) fn decode_output_with_options(
         &self,
         mut data: SliceData,
         internal: bool,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<Vec<Token>> {
         let id = data.get_next_u32()?;
         if !internal && id != self.get_output_id() {
             Err(AbiError::WrongId { id })?
         }
-        TokenValue::decode_params(self.output_params(), data, &self.abi_version, allow_partial)
+        TokenValue::decode_params_with_options(
+            self.output_params(),
+            data.into(),
+            &self.abi_version,
+            allow_partial,
+            options,
+            true,
+        )
+        .map(|(tokens, _)| tokens)
     }
 
     /// Parses the ABI function call to list of tokens.
@@ -184,22 +418,68 @@ impl Function {
         internal: bool,
         allow_partial: bool,
     ) -> Result<Vec<Token>> {
-        let (_, id, cursor) = Self::decode_header(&self.abi_version, data, &self.header, internal)?;
+        self.decode_input_with_options(data, internal, allow_partial, ReadOptions::default())
+    }
+
+    pub(crate) fn decode_input_with_options(
+        &self,
+        data: SliceData,
+        internal: bool,
+        allow_partial: bool,
+        options: ReadOptions,
+    ) -> Result<Vec<Token>> {
+        let (_, id, cursor) = Self::decode_header_with_signature(
+            &self.abi_version,
+            data,
+            &self.header,
+            internal,
+            self.signed,
+        )?;
 
         if id != self.get_input_id() {
             Err(AbiError::WrongId { id })?
         }
 
-        TokenValue::decode_params_with_cursor(
+        TokenValue::decode_params_with_options(
             self.input_params(),
             cursor,
             &self.abi_version,
             allow_partial,
+            options,
             true,
         )
         .map(|(tokens, _)| tokens)
     }
 
+    /// Parses the ABI function call to list of tokens. Data following function parameters
+    /// (e.g. envelope suffix) is not treated as error and returned as remainder slice.
+    pub fn decode_input_with_remainder(
+        &self,
+        data: SliceData,
+        internal: bool,
+    ) -> Result<(Vec<Token>, SliceData)> {
+        let (_, id, cursor) = Self::decode_header_with_signature(
+            &self.abi_version,
+            data,
+            &self.header,
+            internal,
+            self.signed,
+        )?;
+
+        if id != self.get_input_id() {
+            Err(AbiError::WrongId { id })?
+        }
+
+        TokenValue::decode_params_with_cursor(
+            self.input_params(),
+            cursor,
+            &self.abi_version,
+            true,
+            false,
+        )
+        .map(|(tokens, cursor)| (tokens, cursor.slice))
+    }
+
     /// Decodes function id from contract answer
     pub fn decode_input_id(
         abi_version: &AbiVersion,
@@ -225,25 +505,319 @@ impl Function {
         sign_key: Option<&Ed25519PrivateKey>,
         address: Option<MsgAddressInt>,
     ) -> Result<BuilderData> {
-        let (mut builder, hash) =
-            self.create_unsigned_call(header, input, internal, sign_key.is_some(), address)?;
+        self.encode_input_impl(header, input, internal, sign_key, address, None, None)
+    }
 
-        if !internal {
-            builder = match sign_key {
-                Some(key) => {
-                    let signature = key.sign(&hash);
-                    Self::fill_sign(
-                        &self.abi_version,
-                        Some(&signature),
-                        Some(&key.verifying_key()),
-                        builder,
-                    )?
-                }
-                None => Self::fill_sign(&self.abi_version, None, None, builder)?,
+    /// Encodes provided function parameters same as `encode_input` with typed header values
+    pub fn encode_input_with_header_values(
+        &self,
+        header: &[HeaderValue],
+        input: &[Token],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData> {
+        if let Some(value) = header
+            .iter()
+            .find(|value| !self.header.iter().any(|param| param.name == value.name()))
+        {
+            fail!(AbiError::InvalidName {
+                name: format!("header parameter `{}` is not declared", value.name())
+            });
+        }
+        let header = HeaderValue::to_header_map(header)?;
+        self.encode_input_impl(&header, input, internal, sign_key, address, None, None)
+    }
+
+    /// Encodes provided function parameters same as `encode_input` wrapping them with
+    /// `envelope` prefix and suffix data
+    pub fn encode_input_with_envelope(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+        envelope: &PayloadEnvelope,
+    ) -> Result<BuilderData> {
+        envelope.check()?;
+        self.encode_input_impl(header, input, internal, sign_key, address, Some(envelope), None)
+    }
+
+    /// Encodes provided function parameters same as `encode_input` and returns report about
+    /// values placed into separate cells and about message body cells chaining
+    pub fn encode_input_with_report(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<(BuilderData, EncodeReport)> {
+        let mut breaks = vec![];
+        let builder =
+            self.encode_input_impl(
+                header,
+                input,
+                internal,
+                sign_key,
+                address,
+                None,
+                Some(&mut breaks),
+            )?;
+
+        let mut labels = self.header_labels(internal);
+        for token in input {
+            EncodeReport::collect_labels(&token.name, &token.value, &mut labels);
+        }
+
+        Ok((builder, EncodeReport::new(input, &labels, &breaks, &self.abi_version)))
+    }
+
+    fn encode_input_impl(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+        envelope: Option<&PayloadEnvelope>,
+        breaks: Option<&mut Vec<(usize, ChainReason)>>,
+    ) -> Result<BuilderData> {
+        let (builder, hash) = self.create_unsigned_call_impl(
+            header,
+            input,
+            internal,
+            sign_key.is_some(),
+            address,
+            envelope,
+            breaks,
+        )?;
+        self.sign_unsigned_call(builder, &hash, internal, sign_key)
+    }
+
+    fn sign_unsigned_call(
+        &self,
+        builder: BuilderData,
+        hash: &[u8],
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+    ) -> Result<BuilderData> {
+        if internal || !self.signed {
+            return Ok(builder);
+        }
+        match sign_key {
+            Some(key) => {
+                let signature = key.sign(hash);
+                Self::fill_sign(
+                    &self.abi_version,
+                    Some(&signature),
+                    Some(&key.verifying_key()),
+                    builder,
+                )
             }
+            None => Self::fill_sign(&self.abi_version, None, None, builder),
         }
+    }
 
-        Ok(builder)
+    /// Encodes calls of the function with every provided input. External calls get strictly
+    /// increasing `time` header values required by replay protection: call with index `i` has
+    /// `time` equal to provided (or current) time plus `i` milliseconds. Other header values are
+    /// the same for all calls. Leading parameters equal in all inputs are serialized once.
+    pub fn encode_batch(
+        &self,
+        inputs: &[Vec<Token>],
+        shared_header: &HashMap<String, TokenValue>,
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<MsgAddressInt>,
+    ) -> Result<Vec<BuilderData>> {
+        for input in inputs {
+            if !Token::types_check(input, self.input_params()) {
+                fail!(AbiError::WrongParameterType);
+            }
+        }
+        let first = match inputs.first() {
+            Some(first) => first,
+            None => return Ok(vec![]),
+        };
+        let shared = (0..first.len())
+            .take_while(|&i| inputs.iter().all(|input| input[i] == first[i]))
+            .count();
+
+        let time_params: Vec<&Param> = match internal {
+            true => vec![],
+            false => self.header.iter().filter(|param| param.kind == ParamType::Time).collect(),
+        };
+        let mut header = shared_header.clone();
+        let base_times: Vec<u64> = time_params
+            .iter()
+            .map(|param| match shared_header.get(&param.name) {
+                Some(TokenValue::Time(time)) => *time,
+                _ => Utc::now().timestamp_millis() as u64,
+            })
+            .collect();
+
+        let mut shared_cells = vec![];
+        for token in &first[..shared] {
+            shared_cells.append(&mut token.value.write_to_cells(&self.abi_version)?);
+        }
+
+        let mut result = Vec::with_capacity(inputs.len());
+        for (index, input) in inputs.iter().enumerate() {
+            for (param, time) in time_params.iter().zip(&base_times) {
+                header.insert(param.name.clone(), TokenValue::Time(time + index as u64));
+            }
+            let header_cells = self.encode_header(&header, internal)?;
+            let mut input_cells = shared_cells.clone();
+            for token in &input[shared..] {
+                input_cells.append(&mut token.value.write_to_cells(&self.abi_version)?);
+            }
+            let (builder, hash) = self.pack_unsigned_call(
+                header_cells,
+                input_cells,
+                internal,
+                sign_key.is_some(),
+                address.clone(),
+                None,
+            )?;
+            result.push(self.sign_unsigned_call(builder, &hash, internal, sign_key)?);
+        }
+        Ok(result)
+    }
+
+    /// Encodes provided function parameters into `BuilderData` containing ABI contract call.
+    /// External call is signed by `signer` closure receiving hash to be signed, so secret key
+    /// is not needed. `public_key` is put along with signature in ABI v1.
+    pub fn encode_input_with_signer<F>(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        public_key: Option<&PublicKeyData>,
+        signer: F,
+        address: Option<MsgAddressInt>,
+    ) -> Result<BuilderData>
+    where
+        F: FnOnce(&[u8]) -> Result<SignatureData>,
+    {
+        let (builder, hash) =
+            self.create_unsigned_call_impl(header, input, internal, !internal, address, None, None)?;
+
+        if internal {
+            return Ok(builder);
+        }
+
+        let signature = signer(&hash)?;
+        Self::fill_sign(&self.abi_version, Some(&signature), public_key, builder)
+    }
+
+    /// Encodes provided function parameters into external call body with zero placeholder
+    /// signature. Placeholder is replaced by `SignaturePatch::apply` after `signer` signs
+    /// the returned hash. Signature in ABI v1 is stored in a separate cell, so it is not supported.
+    pub fn encode_input_with_placeholder(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        signer: &str,
+        address: Option<MsgAddressInt>,
+    ) -> Result<(BuilderData, SignaturePatch)> {
+        if self.abi_version.major == 1 {
+            fail!(AbiError::NotSupported {
+                subject: "Signature placeholder".to_owned(),
+                version: self.abi_version,
+            });
+        }
+        let (builder, hash) =
+            self.create_unsigned_call_impl(header, input, false, true, address, None, None)?;
+        let placeholder = [0u8; ED25519_SIGNATURE_LENGTH];
+        let builder = Self::fill_sign(&self.abi_version, Some(&placeholder), None, builder)?;
+
+        let patch = SignaturePatch {
+            signer: signer.to_owned(),
+            // signature follows the bit marking signed body
+            offset_bits: 1,
+            len: ED25519_SIGNATURE_LENGTH,
+            hash,
+        };
+        Ok((builder, patch))
+    }
+
+    /// Returns ordered `(label, value)` pairs describing function call for display on hardware
+    /// wallet screens. Nested values are labeled with paths (`msg.dest`, `items[0]`), coin
+    /// amounts (`varuint16`, `varint16`, `gram`) are shown with `SIGNING_SUMMARY_DECIMALS`
+    /// fractional digits, cells and slices are shown as their hashes and byte strings longer
+    /// than 32 bytes as their SHA256 hashes.
+    pub fn signing_summary(&self, tokens: &[Token]) -> Result<Vec<(String, String)>> {
+        if !Token::types_check(tokens, self.input_params()) {
+            fail!(AbiError::WrongParameterType);
+        }
+        let mut summary = vec![];
+        for token in tokens {
+            Self::summarize_value(&token.name, &token.value, &mut summary)?;
+        }
+        Ok(summary)
+    }
+
+    fn summarize_value(
+        label: &str,
+        value: &TokenValue,
+        summary: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        let amount = AmountFormat {
+            decimals: SIGNING_SUMMARY_DECIMALS,
+            names: vec![],
+        };
+        let text = match value {
+            TokenValue::Tuple(tokens) => {
+                for token in tokens {
+                    let label = format!("{}.{}", label, token.name);
+                    Self::summarize_value(&label, &token.value, summary)?;
+                }
+                return Ok(());
+            }
+            TokenValue::Array(_, items) | TokenValue::FixedArray(_, items) if !items.is_empty() => {
+                for (i, item) in items.iter().enumerate() {
+                    Self::summarize_value(&format!("{}[{}]", label, i), item, summary)?;
+                }
+                return Ok(());
+            }
+            TokenValue::Map(_, _, map) if !map.is_empty() => {
+                for (key, item) in map {
+                    Self::summarize_value(&format!("{}[{}]", label, key), item, summary)?;
+                }
+                return Ok(());
+            }
+            TokenValue::Array(..) | TokenValue::FixedArray(..) | TokenValue::Map(..) => {
+                "empty".to_owned()
+            }
+            TokenValue::Optional(_, Some(item)) | TokenValue::Ref(item) => {
+                return Self::summarize_value(label, item, summary);
+            }
+            TokenValue::Optional(_, None) => "none".to_owned(),
+            TokenValue::VarUint(16, number) => amount.format(&BigInt::from(number.clone())),
+            TokenValue::VarInt(16, number) => amount.format(number),
+            TokenValue::Token(grams) => {
+                let number = grams.to_string().parse::<BigInt>().map_err(|err| {
+                    AbiError::InvalidData {
+                        msg: format!("Invalid grams value: {}", err),
+                    }
+                })?;
+                amount.format(&number)
+            }
+            TokenValue::Cell(cell) => format!("hash:{}", hex::encode(cell.repr_hash().into_vec())),
+            TokenValue::Slice(slice) => format!(
+                "hash:{}",
+                hex::encode(slice.clone().into_cell().repr_hash().into_vec())
+            ),
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) if bytes.len() > 32 => {
+                format!("sha256:{}", hex::encode(sha256_digest(bytes)))
+            }
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => hex::encode(bytes),
+            _ => value.to_string(),
+        };
+        summary.push((label.to_owned(), text));
+        Ok(())
     }
 
     /// Encodes provided function return values into `BuilderData`
@@ -255,13 +829,19 @@ impl Function {
     }
 
     /// Encodes function header with provided header parameters
-    fn encode_header(
+    pub(crate) fn encode_header(
         &self,
         header_tokens: &HashMap<String, TokenValue>,
         internal: bool,
     ) -> Result<Vec<SerializedValue>> {
         let mut vec = vec![];
         if !internal {
+            let mut header_tokens = header_tokens.clone();
+            self.header_defaults.complete_header(
+                &self.header,
+                &mut header_tokens,
+                Utc::now().timestamp_millis() as u64,
+            )?;
             for param in &self.header {
                 if let Some(token) = header_tokens.get(&param.name) {
                     if !token.type_check(&param.kind) {
@@ -284,12 +864,41 @@ impl Function {
         Ok(vec)
     }
 
+    /// Returns names of serialized values produced by `encode_header` with signature placeholder
+    fn header_labels(&self, internal: bool) -> Vec<String> {
+        let mut labels = vec![];
+        if !internal {
+            if self.signed {
+                labels.push("signature".to_owned());
+            }
+            if self.abi_version.major == 1 {
+                labels.push("function id".to_owned());
+            }
+            labels.extend(self.header.iter().map(|param| param.name.clone()));
+        }
+        if internal || self.abi_version.major != 1 {
+            labels.push("function id".to_owned());
+        }
+        labels
+    }
+
     /// Encodes function header with provided header parameters
     pub fn decode_header(
         abi_version: &AbiVersion,
         cursor: SliceData,
         header: &Vec<Param>,
         internal: bool,
+    ) -> Result<(Vec<Token>, u32, Cursor)> {
+        Self::decode_header_with_signature(abi_version, cursor, header, internal, true)
+    }
+
+    /// Same as `decode_header`. External call signature slot is expected only if `signed` is set.
+    pub(crate) fn decode_header_with_signature(
+        abi_version: &AbiVersion,
+        cursor: SliceData,
+        header: &[Param],
+        internal: bool,
+        signed: bool,
     ) -> Result<(Vec<Token>, u32, Cursor)> {
         let mut tokens = vec![];
         let mut id = 0;
@@ -299,16 +908,16 @@ impl Function {
             cursor.used_bits += 32;
         }
         if !internal {
-            // skip signature
-            if abi_version == &ABI_VERSION_1_0 {
+            // skip signature, never signed call has no signature slot
+            if signed && abi_version == &ABI_VERSION_1_0 {
                 cursor.slice.checked_drain_reference()?;
                 cursor.used_refs += 1;
-            } else {
+            } else if signed {
                 if cursor.slice.get_next_bit()? {
                     cursor.slice.get_next_bytes(ED25519_SIGNATURE_LENGTH)?;
                 }
-                cursor.used_bits += if abi_version >= &ABI_VERSION_2_3 {
-                    TokenValue::max_bit_size(&ParamType::Address, abi_version)
+                cursor.used_bits += if abi_version.at_least(2, 3) {
+                    ParamType::Address.max_bit_size(abi_version)
                 } else {
                     1 + ED25519_SIGNATURE_LENGTH * 8
                 };
@@ -323,6 +932,59 @@ impl Function {
         Ok((tokens, id, cursor))
     }
 
+    /// Reads signature, header and function ID of the function call without decoding function
+    /// parameters. Returns decoded header and slice with function parameters. Header parameters
+    /// set is needed since it is not encoded in the message.
+    pub fn strip_header(
+        abi_version: &AbiVersion,
+        slice: SliceData,
+        header: &[Param],
+        internal: bool,
+    ) -> Result<(HeaderInfo, SliceData)> {
+        let mut signature = None;
+        if !internal {
+            let mut cursor = slice.clone();
+            if abi_version == &ABI_VERSION_1_0 {
+                cursor.get_next_u32()?;
+                let mut sign_cell = SliceData::load_cell(cursor.checked_drain_reference()?)?;
+                if sign_cell.remaining_bits() >= ED25519_SIGNATURE_LENGTH * 8 {
+                    signature = Some(sign_cell.get_next_bytes(ED25519_SIGNATURE_LENGTH)?);
+                }
+            } else if cursor.get_next_bit()? {
+                signature = Some(cursor.get_next_bytes(ED25519_SIGNATURE_LENGTH)?);
+            }
+        }
+        let (tokens, function_id, cursor) =
+            Self::decode_header(abi_version, slice, &header.to_vec(), internal)?;
+        Ok((
+            HeaderInfo {
+                signature,
+                tokens,
+                function_id,
+            },
+            cursor.slice,
+        ))
+    }
+
+    // reads header of external call of this function, never signed calls have no signature slot
+    pub(crate) fn read_call_header(&self, slice: SliceData) -> Result<HeaderInfo> {
+        if self.signed {
+            return Ok(Self::strip_header(&self.abi_version, slice, &self.header, false)?.0);
+        }
+        let (tokens, function_id, _) = Self::decode_header_with_signature(
+            &self.abi_version,
+            slice,
+            &self.header,
+            false,
+            false,
+        )?;
+        Ok(HeaderInfo {
+            signature: None,
+            tokens,
+            function_id,
+        })
+    }
+
     pub fn get_signature_data(
         abi_version: &AbiVersion,
         mut cursor: SliceData,
@@ -342,16 +1004,41 @@ impl Function {
             }
         };
 
-        let hash = if abi_version >= &ABI_VERSION_2_3 {
+        let hash = Self::signing_hash(abi_version, cursor, address)?;
+
+        Ok((signature, hash))
+    }
+
+    /// Recomputes hash to be signed for already encoded function call body. Body should contain
+    /// signature slot: either empty, filled with placeholder or with some signature which is
+    /// ignored. Allows several parties to sign the same body without original parameters.
+    pub fn body_hash_for_signing(
+        mut body: SliceData,
+        abi_version: &AbiVersion,
+        address: Option<MsgAddressInt>,
+    ) -> Result<Vec<u8>> {
+        if abi_version == &ABI_VERSION_1_0 {
+            body.checked_drain_reference()?;
+        } else if body.get_next_bit()? {
+            body.get_next_bytes(ED25519_SIGNATURE_LENGTH)?;
+        }
+
+        Self::signing_hash(abi_version, body, address)
+    }
+
+    pub(crate) fn signing_hash(
+        abi_version: &AbiVersion,
+        unsigned_body: SliceData,
+        address: Option<MsgAddressInt>,
+    ) -> Result<Vec<u8>> {
+        if abi_version.at_least(2, 3) {
             let address = address.ok_or(AbiError::AddressRequired)?;
             let mut address_builder = address.write_to_new_cell()?;
-            address_builder.append_builder(&cursor.as_builder())?;
-            address_builder.into_cell()?.repr_hash().into_vec()
+            address_builder.append_builder(&unsigned_body.as_builder())?;
+            Ok(address_builder.into_cell()?.repr_hash().into_vec())
         } else {
-            cursor.into_cell().repr_hash().into_vec()
-        };
-
-        Ok((signature, hash))
+            Ok(unsigned_body.into_cell().repr_hash().into_vec())
+        }
     }
 
     /// Encodes provided function parameters into `BuilderData` containing ABI contract call.
@@ -363,6 +1050,19 @@ impl Function {
         internal: bool,
         reserve_sign: bool,
         address: Option<MsgAddressInt>,
+    ) -> Result<(BuilderData, Vec<u8>)> {
+        self.create_unsigned_call_impl(header, input, internal, reserve_sign, address, None, None)
+    }
+
+    fn create_unsigned_call_impl(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        internal: bool,
+        reserve_sign: bool,
+        address: Option<MsgAddressInt>,
+        envelope: Option<&PayloadEnvelope>,
+        breaks: Option<&mut Vec<(usize, ChainReason)>>,
     ) -> Result<(BuilderData, Vec<u8>)> {
         let params = self.input_params();
 
@@ -373,9 +1073,41 @@ impl Function {
         // prepare standard message
         let mut cells = self.encode_header(header, internal)?;
 
+        if let Some(prefix) = envelope.and_then(|envelope| envelope.prefix.as_ref()) {
+            // function ID is the first value in ABI v1 and the last one since ABI v2
+            let id_index = if self.abi_version.major == 1 { 0 } else { cells.len() - 1 };
+            cells.insert(id_index, prefix.clone());
+        }
+
+        let mut input_cells = vec![];
+        for token in input {
+            input_cells.append(&mut token.value.write_to_cells(&self.abi_version)?);
+        }
+        if let Some(suffix) = envelope.and_then(|envelope| envelope.suffix.as_ref()) {
+            input_cells.push(suffix.clone());
+        }
+
+        self.pack_unsigned_call(cells, input_cells, internal, reserve_sign, address, breaks)
+    }
+
+    // packs serialized header and input into unsigned call body and computes its hash for signing
+    fn pack_unsigned_call(
+        &self,
+        mut cells: Vec<SerializedValue>,
+        mut input_cells: Vec<SerializedValue>,
+        internal: bool,
+        reserve_sign: bool,
+        address: Option<MsgAddressInt>,
+        breaks: Option<&mut Vec<(usize, ChainReason)>>,
+    ) -> Result<(BuilderData, Vec<u8>)> {
         let mut remove_ref = false;
         let mut remove_bits = 0;
-        if !internal {
+        if !internal && !self.signed && reserve_sign {
+            fail!(AbiError::InvalidInputData {
+                msg: format!("Function `{}` calls are never signed", self.name)
+            });
+        }
+        if !internal && self.signed {
             let mut sign_builder = BuilderData::new();
             if self.abi_version.major == 1 {
                 // reserve reference for sign
@@ -384,12 +1116,12 @@ impl Function {
             } else {
                 // reserve in-cell data
                 if reserve_sign {
-                    if self.abi_version >= ABI_VERSION_2_3 {
+                    if self.abi_version.at_least(2, 3) {
                         sign_builder.append_raw(
                             &[0u8; MAX_DATA_BYTES],
-                            TokenValue::max_bit_size(&ParamType::Address, &self.abi_version),
+                            ParamType::Address.max_bit_size(&self.abi_version),
                         )?;
-                        remove_bits = TokenValue::max_bit_size(&ParamType::Address, &self.abi_version);
+                        remove_bits = ParamType::Address.max_bit_size(&self.abi_version);
                     } else {
                         sign_builder.append_bit_one()?;
                         sign_builder.append_raw(
@@ -407,8 +1139,8 @@ impl Function {
                 0,
                 SerializedValue {
                     data: sign_builder,
-                    max_bits: if self.abi_version >= ABI_VERSION_2_3 {
-                        TokenValue::max_bit_size(&ParamType::Address, &self.abi_version)
+                    max_bits: if self.abi_version.at_least(2, 3) {
+                        ParamType::Address.max_bit_size(&self.abi_version)
                     } else {
                         1 + ED25519_SIGNATURE_LENGTH * 8
                     },
@@ -418,9 +1150,11 @@ impl Function {
         }
 
         // encoding itself
-        let mut builder = TokenValue::pack_values_into_chain(input, cells, &self.abi_version)?;
+        cells.append(&mut input_cells);
+        let mut builder =
+            TokenValue::pack_values_into_chain_with_breaks(&[], cells, &self.abi_version, breaks)?;
 
-        if !internal {
+        if !internal && self.signed {
             // delete reserved sign before hash
             let mut slice = SliceData::load_builder(builder)?;
             if remove_ref {
@@ -432,7 +1166,7 @@ impl Function {
             builder = slice.as_builder();
         }
 
-        let hash = if self.abi_version >= ABI_VERSION_2_3 && reserve_sign {
+        let hash = if self.abi_version.at_least(2, 3) && reserve_sign {
             let address = address.ok_or(AbiError::AddressRequired)?;
             let mut address_builder = address.write_to_new_cell()?;
             address_builder.append_builder(&builder)?;
@@ -0,0 +1,151 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Golden test corpus runner.
+//!
+//! Corpus is a directory with `corpus.json` file containing corpus layout version
+//! (`{"version": 1}`) and a subdirectory per test case. Case directory contains:
+//! * `abi.json` - contract ABI;
+//! * `params.json` - function call description: `function` name, `input` parameters JSON,
+//!   optional `header` values JSON, `internal` flag and `time` in milliseconds used as
+//!   `EncodeOptions::fixed_time`;
+//! * `expected.boc` - expected encoded body serialized into BOC.
+//!
+//! Every case is encoded and compared with the expected body, then the expected body is decoded
+//! back and compared with the input parameters. Projects can ship their own corpora and check
+//! them with `run_corpus` (requires `golden` feature).
+
+use crate::{
+    contract::Contract,
+    error::AbiError,
+    json_abi::{EncodeOptions, JsonAbiSession},
+    token::Tokenizer,
+};
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ever_block::{fail, read_single_root_boc, write_boc, Result, SliceData};
+
+/// Corpus layout version supported by the runner
+pub const CORPUS_VERSION: u32 = 1;
+
+#[derive(serde::Deserialize)]
+struct CorpusInfo {
+    version: u32,
+}
+
+#[derive(serde::Deserialize)]
+struct CaseParams {
+    function: String,
+    #[serde(default)]
+    header: Option<Value>,
+    input: Value,
+    #[serde(default)]
+    internal: bool,
+    #[serde(default)]
+    time: Option<u64>,
+}
+
+/// Runs all cases of the corpus in `dir` in the order of their names and returns the number of
+/// cases run. If any case doesn't round-trip, fails with `AbiError::GoldenCasesFailed` listing
+/// all failed cases.
+pub fn run_corpus<P: AsRef<Path>>(dir: P) -> Result<usize> {
+    let cases = corpus_cases(dir.as_ref())?;
+    let mut failures = vec![];
+    for case in &cases {
+        if let Err(err) = run_case(case) {
+            let name = case.file_name().unwrap_or_default().to_string_lossy();
+            failures.push(format!("{}: {}", name, err));
+        }
+    }
+    if !failures.is_empty() {
+        fail!(AbiError::GoldenCasesFailed { failures });
+    }
+    Ok(cases.len())
+}
+
+fn corpus_cases(dir: &Path) -> Result<Vec<PathBuf>> {
+    let info: CorpusInfo = serde_json::from_slice(&fs::read(dir.join("corpus.json"))?)?;
+    if info.version != CORPUS_VERSION {
+        fail!(AbiError::InvalidData {
+            msg: format!(
+                "Corpus version {} is not supported, expected {}",
+                info.version, CORPUS_VERSION
+            )
+        });
+    }
+    let mut cases = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            cases.push(path);
+        }
+    }
+    cases.sort();
+    Ok(cases)
+}
+
+// encodes case and decodes expected body back
+fn run_case(dir: &Path) -> Result<()> {
+    let abi = fs::read_to_string(dir.join("abi.json"))?;
+    let params: CaseParams = serde_json::from_slice(&fs::read(dir.join("params.json"))?)?;
+    let expected = fs::read(dir.join("expected.boc"))?;
+
+    let session = JsonAbiSession::new(&abi)?;
+    let header = params.header.as_ref().map(Value::to_string);
+    let mut options = EncodeOptions::new().internal(params.internal);
+    if let Some(header) = &header {
+        options = options.with_header(header);
+    }
+    if let Some(time) = params.time {
+        options = options.fixed_time(time);
+    }
+    let body = session
+        .encode_function_call_ext(&params.function, &params.input.to_string(), &options)?
+        .into_cell()?;
+    let body = write_boc(&body)?;
+    if body != expected {
+        fail!(AbiError::InvalidData {
+            msg: format!(
+                "encoded body {} differs from expected {}",
+                hex::encode(&body),
+                hex::encode(&expected)
+            )
+        });
+    }
+
+    let expected = SliceData::load_cell(read_single_root_boc(&expected)?)?;
+    check_decoding(session.contract(), &params, expected)
+}
+
+fn check_decoding(contract: &Contract, params: &CaseParams, body: SliceData) -> Result<()> {
+    let function = contract.function(&params.function)?;
+    let decoded = contract.decode_input(body, params.internal, false)?;
+    if decoded.function_name != function.name {
+        fail!(AbiError::InvalidData {
+            msg: format!("body is decoded as `{}` call", decoded.function_name)
+        });
+    }
+    let input = Tokenizer::tokenize_all_params(function.input_params(), &params.input)?;
+    if decoded.tokens != input {
+        fail!(AbiError::InvalidData {
+            msg: format!("decoded parameters {} differ from input", decoded.to_json()?)
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/test_golden.rs"]
+mod tests;
@@ -11,6 +11,8 @@
 * limitations under the License.
 */
 
+use crate::param_type::ParamType;
+
 use num_bigint::{BigInt, BigUint};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -42,3 +44,64 @@ impl Uint {
         }
     }
 }
+
+/// Range of values representable by an integer type, bounds are inclusive
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntBounds {
+    pub min: BigInt,
+    pub max: BigInt,
+}
+
+impl IntBounds {
+    /// Bounds of `uint<size>`
+    pub fn uint(size: usize) -> Self {
+        Self {
+            min: BigInt::from(0),
+            max: (BigInt::from(1) << size) - 1,
+        }
+    }
+
+    /// Bounds of `int<size>`. `int0` can hold only zero.
+    pub fn int(size: usize) -> Self {
+        if size == 0 {
+            return Self::uint(0);
+        }
+        let half = BigInt::from(1) << (size - 1);
+        Self {
+            min: -half.clone(),
+            max: half - 1,
+        }
+    }
+
+    /// Bounds of `varuint<size>`. Value takes up to `size - 1` bytes.
+    pub fn varuint(size: usize) -> Self {
+        Self::uint(size.saturating_sub(1) * 8)
+    }
+
+    /// Bounds of `varint<size>`. Value takes up to `size - 1` bytes.
+    pub fn varint(size: usize) -> Self {
+        Self::int(size.saturating_sub(1) * 8)
+    }
+
+    /// Returns bounds of integer-like type or `None` for other types
+    pub fn for_type(param_type: &ParamType) -> Option<Self> {
+        match param_type {
+            ParamType::Uint(size) => Some(Self::uint(*size)),
+            ParamType::Int(size) => Some(Self::int(*size)),
+            ParamType::VarUint(size) => Some(Self::varuint(*size)),
+            ParamType::VarInt(size) => Some(Self::varint(*size)),
+            ParamType::Token => Some(Self::uint(120)),
+            ParamType::Time => Some(Self::uint(64)),
+            ParamType::Expire => Some(Self::uint(32)),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, number: &BigInt) -> bool {
+        &self.min <= number && number <= &self.max
+    }
+
+    pub fn contains_uint(&self, number: &BigUint) -> bool {
+        self.contains(&BigInt::from(number.clone()))
+    }
+}
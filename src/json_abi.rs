@@ -12,16 +12,629 @@
 */
 
 use crate::{
-    contract::Contract,
+    contract::{Contract, DecodedItem},
     error::AbiError,
-    token::{Detokenizer, TokenValue, Tokenizer},
+    function::Function,
+    param_type::ParamType,
+    token::{Detokenizer, ReadOptions, Token, TokenValue, Tokenizer, Utf8Handling},
     PublicKeyData, SignatureData,
 };
 
 use serde_json::Value;
 use std::{collections::HashMap, str::FromStr};
 use ever_block::MsgAddressInt;
-use ever_block::{BuilderData, Ed25519PrivateKey, Result, SliceData};
+use ever_block::{fail, write_boc, BuilderData, Ed25519PrivateKey, Result, SliceData};
+
+pub struct DecodedMessage {
+    pub function_name: String,
+    /// ID of decoded function call, function answer or event
+    pub function_id: u32,
+    pub params: String,
+    /// Header values JSON of external function call, e.g. `{"expire":...,"pubkey":...}`.
+    /// Filled only if requested by `DecodeOptions::decode_header`.
+    pub header: Option<String>,
+}
+
+/// Options of function call encoding
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EncodeOptions<'a> {
+    /// Header values JSON
+    pub header: Option<&'a str>,
+    /// Encode internal message body
+    pub internal: bool,
+    /// Key to sign external message body with
+    pub sign_key: Option<&'a Ed25519PrivateKey>,
+    /// Destination address included into signed data since ABI 2.3
+    pub address: Option<&'a str>,
+    /// Time in milliseconds used instead of the current time for header defaults
+    pub fixed_time: Option<u64>,
+    /// Decode encoded body back and fail with `AbiError::RoundtripMismatch` if decoded
+    /// parameters differ from the encoded ones
+    pub verify_roundtrip: bool,
+}
+
+impl<'a> EncodeOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_header(mut self, header: &'a str) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn internal(mut self, internal: bool) -> Self {
+        self.internal = internal;
+        self
+    }
+
+    pub fn with_sign_key(mut self, sign_key: &'a Ed25519PrivateKey) -> Self {
+        self.sign_key = Some(sign_key);
+        self
+    }
+
+    pub fn with_address(mut self, address: &'a str) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Uses `time` (in milliseconds) as `time` header default and as the base of `expire`
+    /// header default, so encoded body doesn't depend on the wall clock
+    pub fn fixed_time(mut self, time: u64) -> Self {
+        self.fixed_time = Some(time);
+        self
+    }
+
+    pub fn verify_roundtrip(mut self, verify_roundtrip: bool) -> Self {
+        self.verify_roundtrip = verify_roundtrip;
+        self
+    }
+}
+
+/// Options of message body decoding
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DecodeOptions {
+    /// Decode internal message body
+    pub internal: bool,
+    /// Allow body to contain unread data after the last parameter
+    pub allow_partial: bool,
+    /// Return header values of external function call along with parameters
+    pub decode_header: bool,
+    /// ID function answer is expected to have. Answers with other ID are rejected by
+    /// `decode_function_response_ext`.
+    pub expected_id: Option<u32>,
+    /// Do not verify that parameters are placed into cells as ABI 2.2+ deterministic layout
+    /// requires. Allows to inspect bodies produced by non-conforming encoders.
+    pub skip_layout_check: bool,
+    /// Handling of `string` values which are not valid UTF-8
+    pub utf8: Utf8Handling,
+}
+
+impl DecodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn internal(mut self, internal: bool) -> Self {
+        self.internal = internal;
+        self
+    }
+
+    pub fn allow_partial(mut self, allow_partial: bool) -> Self {
+        self.allow_partial = allow_partial;
+        self
+    }
+
+    pub fn decode_header(mut self, decode_header: bool) -> Self {
+        self.decode_header = decode_header;
+        self
+    }
+
+    pub fn with_expected_id(mut self, expected_id: u32) -> Self {
+        self.expected_id = Some(expected_id);
+        self
+    }
+
+    pub fn skip_layout_check(mut self, skip_layout_check: bool) -> Self {
+        self.skip_layout_check = skip_layout_check;
+        self
+    }
+
+    pub fn utf8(mut self, utf8: Utf8Handling) -> Self {
+        self.utf8 = utf8;
+        self
+    }
+
+    fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            utf8: self.utf8,
+            check_layout: !self.skip_layout_check,
+        }
+    }
+}
+
+/// Set of JSON ABI operations over the contract ABI parsed once. Free functions of this module
+/// parse ABI on every call, so session should be preferred for repeated calls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonAbiSession {
+    contract: Contract,
+}
+
+impl JsonAbiSession {
+    /// Parses and validates ABI JSON
+    pub fn new(abi: &str) -> Result<Self> {
+        Ok(Self {
+            contract: Contract::load(abi.as_bytes())?,
+        })
+    }
+
+    /// Creates session for already loaded contract
+    pub fn from_contract(contract: Contract) -> Self {
+        Self { contract }
+    }
+
+    /// Returns contract ABI
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    fn tokenize_header(
+        &self,
+        function: &Function,
+        header: Option<&str>,
+        public_key: Option<&PublicKeyData>,
+        internal: bool,
+        fixed_time: Option<u64>,
+    ) -> Result<HashMap<String, TokenValue>> {
+        let mut header_tokens = if let Some(header) = header {
+            let v: Value =
+                serde_json::from_str(header).map_err(|err| AbiError::SerdeError { err })?;
+            Tokenizer::tokenize_optional_params(function.header_params(), &v)?
+        } else {
+            HashMap::new()
+        };
+        // add public key into header
+        if public_key.is_some() && header_tokens.get("pubkey").is_none() {
+            header_tokens.insert("pubkey".to_owned(), TokenValue::PublicKey(public_key.copied()));
+        }
+        if !internal {
+            if let Some(time) = fixed_time {
+                for param in function.header_params() {
+                    if param.kind == ParamType::Time && !header_tokens.contains_key(&param.name) {
+                        header_tokens.insert(param.name.clone(), TokenValue::Time(time));
+                    }
+                }
+                self.contract.complete_header_at(&mut header_tokens, time)?;
+            } else {
+                self.contract.complete_header(&mut header_tokens)?;
+            }
+        }
+        Ok(header_tokens)
+    }
+
+    fn tokenize_input(function: &Function, parameters: &str) -> Result<Vec<Token>> {
+        let v: Value =
+            serde_json::from_str(&parameters).map_err(|err| AbiError::SerdeError { err })?;
+        Tokenizer::tokenize_all_params(function.input_params(), &v)
+    }
+
+    fn item_id(item: &DecodedItem, input: bool) -> u32 {
+        match item {
+            DecodedItem::Function(function) if input => function.get_input_id(),
+            DecodedItem::Function(function) => function.get_output_id(),
+            DecodedItem::Event(event) => event.get_id(),
+        }
+    }
+
+    fn parse_address(address: Option<&str>) -> Result<Option<MsgAddressInt>> {
+        address
+            .map(|string| MsgAddressInt::from_str(&string))
+            .transpose()
+    }
+
+    /// Encodes `parameters` for given `function` into `BuilderData` which can be used as message
+    /// body for calling contract
+    pub fn encode_function_call(
+        &self,
+        function: &str,
+        header: Option<&str>,
+        parameters: &str,
+        internal: bool,
+        sign_key: Option<&Ed25519PrivateKey>,
+        address: Option<&str>,
+    ) -> Result<BuilderData> {
+        let options = EncodeOptions {
+            header,
+            internal,
+            sign_key,
+            address,
+            fixed_time: None,
+            verify_roundtrip: false,
+        };
+        self.encode_function_call_ext(function, parameters, &options)
+    }
+
+    /// Same as `encode_function_call` with encoding options passed as a struct
+    pub fn encode_function_call_ext(
+        &self,
+        function: &str,
+        parameters: &str,
+        options: &EncodeOptions,
+    ) -> Result<BuilderData> {
+        let function = self.contract.function(&function)?;
+
+        let public_key = options.sign_key.map(|sign_key| sign_key.verifying_key());
+        let header_tokens = self.tokenize_header(
+            function,
+            options.header,
+            public_key.as_ref(),
+            options.internal,
+            options.fixed_time,
+        )?;
+        let input_tokens = Self::tokenize_input(function, parameters)?;
+        let address = Self::parse_address(options.address)?;
+
+        let body = function.encode_input(
+            &header_tokens,
+            &input_tokens,
+            options.internal,
+            options.sign_key,
+            address,
+        )?;
+
+        if options.verify_roundtrip {
+            let decoded = function.decode_input(
+                SliceData::load_builder(body.clone())?,
+                options.internal,
+                false,
+            )?;
+            if let Some((path, encoded, decoded)) = Self::find_mismatch(&input_tokens, &decoded) {
+                fail!(AbiError::RoundtripMismatch { path, encoded, decoded });
+            }
+        }
+
+        Ok(body)
+    }
+
+    // returns JSON pointer and values of the first token which differs after decoding
+    fn find_mismatch(encoded: &[Token], decoded: &[Token]) -> Option<(String, String, String)> {
+        if encoded.len() != decoded.len() {
+            return Some((
+                String::new(),
+                format!("{} values", encoded.len()),
+                format!("{} values", decoded.len()),
+            ));
+        }
+        encoded.iter().zip(decoded).find_map(|(encoded, decoded)| {
+            if encoded.name != decoded.name {
+                return Some((String::new(), encoded.name.clone(), decoded.name.clone()));
+            }
+            Self::find_value_mismatch(&encoded.value, &decoded.value).map(
+                |(path, encoded_value, decoded_value)| {
+                    (format!("/{}{}", encoded.name, path), encoded_value, decoded_value)
+                },
+            )
+        })
+    }
+
+    fn find_value_mismatch(
+        encoded: &TokenValue,
+        decoded: &TokenValue,
+    ) -> Option<(String, String, String)> {
+        let find_item_mismatch =
+            |key: &dyn std::fmt::Display, encoded: &TokenValue, decoded: &TokenValue| {
+                Self::find_value_mismatch(encoded, decoded)
+                    .map(|(path, encoded, decoded)| (format!("/{}{}", key, path), encoded, decoded))
+            };
+        match (encoded, decoded) {
+            (TokenValue::Tuple(encoded), TokenValue::Tuple(decoded)) => {
+                Self::find_mismatch(encoded, decoded)
+            }
+            (TokenValue::Array(_, encoded), TokenValue::Array(_, decoded))
+            | (TokenValue::FixedArray(_, encoded), TokenValue::FixedArray(_, decoded))
+                if encoded.len() == decoded.len() =>
+            {
+                encoded
+                    .iter()
+                    .zip(decoded)
+                    .enumerate()
+                    .find_map(|(index, (encoded, decoded))| {
+                        find_item_mismatch(&index, encoded, decoded)
+                    })
+            }
+            (TokenValue::Map(key_type, _, encoded), TokenValue::Map(_, _, decoded))
+                if encoded.len() == decoded.len() =>
+            {
+                // keys are compared as values since encoded keys may be written in any accepted
+                // form, e.g. hex integers
+                let key_value = |key: &str| {
+                    Tokenizer::tokenize_parameter(key_type, &key.into(), "map key").ok()
+                };
+                let decoded: Vec<_> = decoded
+                    .iter()
+                    .map(|(key, value)| (value, key_value(key.as_str())))
+                    .collect();
+                encoded.iter().find_map(|(key, encoded)| {
+                    let key_token = key_value(key.as_str());
+                    match decoded.iter().find(|(_, decoded_key)| decoded_key == &key_token) {
+                        Some(&(decoded, _)) => find_item_mismatch(key, encoded, decoded),
+                        None => Some((format!("/{}", key), encoded.to_string(), "none".to_owned())),
+                    }
+                })
+            }
+            (TokenValue::Optional(_, Some(encoded)), TokenValue::Optional(_, Some(decoded)))
+            | (TokenValue::Ref(encoded), TokenValue::Ref(decoded)) => {
+                Self::find_value_mismatch(encoded, decoded)
+            }
+            _ if encoded == decoded => None,
+            _ => Some((String::new(), encoded.to_string(), decoded.to_string())),
+        }
+    }
+
+    /// Encodes `parameters` for given `function` into `BuilderData` which can be used as message
+    /// body for calling contract. External message body is signed by `signer` closure receiving
+    /// hash to be signed, so secret key is not passed to this crate.
+    pub fn encode_function_call_with_signer<F>(
+        &self,
+        function: &str,
+        header: Option<&str>,
+        parameters: &str,
+        internal: bool,
+        public_key: Option<&PublicKeyData>,
+        signer: F,
+        address: Option<&str>,
+    ) -> Result<BuilderData>
+    where
+        F: FnOnce(&[u8]) -> Result<SignatureData>,
+    {
+        let function = self.contract.function(&function)?;
+
+        let header_tokens = self.tokenize_header(function, header, public_key, internal, None)?;
+        let input_tokens = Self::tokenize_input(function, parameters)?;
+        let address = Self::parse_address(address)?;
+
+        function.encode_input_with_signer(
+            &header_tokens,
+            &input_tokens,
+            internal,
+            public_key,
+            signer,
+            address,
+        )
+    }
+
+    /// Encodes `parameters` for given `function` into `BuilderData` which can be used as message
+    /// body for calling contract. Message body is prepared for signing. Sign should be the added
+    /// by `add_sign_to_function_call` function
+    pub fn prepare_function_call_for_sign(
+        &self,
+        function: &str,
+        header: Option<&str>,
+        parameters: &str,
+        address: Option<&str>,
+    ) -> Result<(BuilderData, Vec<u8>)> {
+        let function = self.contract.function(function)?;
+
+        let header_tokens = self.tokenize_header(function, header, None, false, None)?;
+        let input_tokens = Self::tokenize_input(function, parameters)?;
+        let address = Self::parse_address(address)?;
+
+        function.create_unsigned_call(&header_tokens, &input_tokens, false, true, address)
+    }
+
+    /// Returns hash to be signed for the call of `function` with given `parameters`. It is the
+    /// same hash `prepare_function_call_for_sign` returns along with unsigned body. Header values
+    /// which default to the current time should be passed explicitly so the hash matches the
+    /// body built elsewhere.
+    pub fn compute_signing_hash(
+        &self,
+        function: &str,
+        header: Option<&str>,
+        parameters: &str,
+        address: Option<&str>,
+    ) -> Result<Vec<u8>> {
+        self.prepare_function_call_for_sign(function, header, parameters, address)
+            .map(|(_, hash)| hash)
+    }
+
+    /// Add sign to messsage body returned by `prepare_function_call_for_sign` function
+    pub fn add_sign_to_function_call(
+        &self,
+        signature: &SignatureData,
+        public_key: Option<&PublicKeyData>,
+        function_call: SliceData,
+    ) -> Result<BuilderData> {
+        self.contract
+            .add_sign_to_encoded_input(signature, public_key, function_call)
+    }
+
+    /// Decodes output parameters returned by contract function call
+    pub fn decode_function_response(
+        &self,
+        function: &str,
+        response: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<String> {
+        self.decode_function_response_ext(
+            function,
+            response,
+            &DecodeOptions::new().internal(internal).allow_partial(allow_partial),
+        )
+    }
+
+    /// Same as `decode_function_response` with decoding options passed as a struct
+    pub fn decode_function_response_ext(
+        &self,
+        function: &str,
+        response: SliceData,
+        options: &DecodeOptions,
+    ) -> Result<String> {
+        if let Some(expected_id) = options.expected_id {
+            let id = Function::decode_output_id(response.clone())?;
+            if id != expected_id {
+                fail!(AbiError::WrongId { id });
+            }
+        }
+        let function = self.contract.function(&function)?;
+
+        let tokens = function.decode_output_with_options(
+            response,
+            options.internal,
+            options.allow_partial,
+            options.read_options(),
+        )?;
+
+        Detokenizer::detokenize(&tokens)
+    }
+
+    /// Decodes output parameters returned by some function call. Returns parametes and function
+    /// name
+    pub fn decode_unknown_function_response(
+        &self,
+        response: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        self.decode_unknown_function_response_ext(
+            response,
+            &DecodeOptions::new().internal(internal).allow_partial(allow_partial),
+        )
+    }
+
+    /// Same as `decode_unknown_function_response` with decoding options passed as a struct
+    pub fn decode_unknown_function_response_ext(
+        &self,
+        response: SliceData,
+        options: &DecodeOptions,
+    ) -> Result<DecodedMessage> {
+        let result = self.contract.decode_output_with_options(
+            response,
+            options.internal,
+            options.allow_partial,
+            options.read_options(),
+        )?;
+
+        let output = result.to_json()?;
+
+        Ok(DecodedMessage {
+            function_id: Self::item_id(&result.item, false),
+            function_name: result.function_name,
+            params: output,
+            header: None,
+        })
+    }
+
+    /// Decodes output parameters returned by some function call. Returns parametes and function
+    /// name
+    pub fn decode_unknown_function_call(
+        &self,
+        response: SliceData,
+        internal: bool,
+        allow_partial: bool,
+    ) -> Result<DecodedMessage> {
+        self.decode_unknown_function_call_ext(
+            response,
+            &DecodeOptions::new().internal(internal).allow_partial(allow_partial),
+        )
+    }
+
+    /// Same as `decode_unknown_function_call` with decoding options passed as a struct
+    pub fn decode_unknown_function_call_ext(
+        &self,
+        response: SliceData,
+        options: &DecodeOptions,
+    ) -> Result<DecodedMessage> {
+        let result = self.contract.decode_input_with_options(
+            response.clone(),
+            options.internal,
+            options.allow_partial,
+            options.read_options(),
+        )?;
+        let header = match result.function() {
+            Some(function) if options.decode_header && !options.internal => {
+                Some(Detokenizer::detokenize(&function.read_call_header(response)?.tokens)?)
+            }
+            _ => None,
+        };
+        Ok(DecodedMessage {
+            function_id: Self::item_id(&result.item, true),
+            params: result.to_json()?,
+            function_name: result.function_name,
+            header,
+        })
+    }
+
+    /// Changes initial values for public contract variables
+    pub fn update_contract_data(&self, parameters: &str, data: SliceData) -> Result<SliceData> {
+        let data_json: serde_json::Value = serde_json::from_str(parameters)?;
+
+        let params: Vec<_> = self
+            .contract
+            .data()
+            .values()
+            .map(|item| item.value.clone())
+            .collect();
+
+        let tokens = Tokenizer::tokenize_all_params(&params[..], &data_json)?;
+
+        self.contract.update_data(data, &tokens)
+    }
+
+    /// Decode initial values of public contract variables
+    pub fn decode_contract_data(&self, data: SliceData, allow_partial: bool) -> Result<String> {
+        Detokenizer::detokenize(&self.contract.decode_data(data, allow_partial)?)
+    }
+
+    /// Decode initial values of public contract variables along with values stored under keys
+    /// not declared in ABI. Result is `{"data": {...}, "unknown": {"<key>": "<hex BOC>"}}`.
+    pub fn decode_contract_data_with_unknown(
+        &self,
+        data: SliceData,
+        allow_partial: bool,
+    ) -> Result<String> {
+        let decoded = self.contract.decode_data_with_unknown(data, allow_partial)?;
+        let mut unknown = serde_json::Map::new();
+        for (key, value) in decoded.unknown {
+            unknown.insert(key.to_string(), hex::encode(write_boc(&value.into_cell())?).into());
+        }
+        let result = serde_json::json!({
+            "data": Detokenizer::detokenize_to_json_value(&decoded.tokens)?,
+            "unknown": unknown,
+        });
+        Ok(result.to_string())
+    }
+
+    /// Decode account storage fields
+    pub fn decode_storage_fields(&self, data: SliceData, allow_partial: bool) -> Result<String> {
+        let decoded = self.contract.decode_storage_fields(data, allow_partial)?;
+
+        Detokenizer::detokenize(&decoded)
+    }
+
+    /// Get signature and signed hash from function call data
+    pub fn get_signature_data(
+        &self,
+        cursor: SliceData,
+        address: Option<&str>,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let address = Self::parse_address(address)?;
+        self.contract.get_signature_data(cursor, address)
+    }
+
+    /// Encodes contract storage fields with provided initial values
+    pub fn encode_storage_fields(&self, init_fields: Option<&str>) -> Result<BuilderData> {
+        let init_fields = if let Some(init_fields) = init_fields {
+            let v: Value =
+                serde_json::from_str(&init_fields).map_err(|err| AbiError::SerdeError { err })?;
+            Tokenizer::tokenize_optional_params(&self.contract.fields(), &v)?
+        } else {
+            HashMap::new()
+        };
+
+        self.contract.encode_storage_fields(init_fields)
+    }
+}
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
 /// which can be used as message body for calling contract
@@ -34,32 +647,40 @@ pub fn encode_function_call(
     sign_key: Option<&Ed25519PrivateKey>,
     address: Option<&str>,
 ) -> Result<BuilderData> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let function = contract.function(&function)?;
-
-    let mut header_tokens = if let Some(header) = header {
-        let v: Value = serde_json::from_str(header).map_err(|err| AbiError::SerdeError { err })?;
-        Tokenizer::tokenize_optional_params(function.header_params(), &v)?
-    } else {
-        HashMap::new()
-    };
-    // add public key into header
-    if sign_key.is_some() && header_tokens.get("pubkey").is_none() {
-        header_tokens.insert(
-            "pubkey".to_owned(),
-            TokenValue::PublicKey(sign_key.as_ref().map(|sign_key| sign_key.verifying_key())),
-        );
-    }
-
-    let v: Value = serde_json::from_str(&parameters).map_err(|err| AbiError::SerdeError { err })?;
-    let input_tokens = Tokenizer::tokenize_all_params(function.input_params(), &v)?;
+    JsonAbiSession::new(abi)?.encode_function_call(
+        function, header, parameters, internal, sign_key, address,
+    )
+}
 
-    let address = address
-        .map(|string| MsgAddressInt::from_str(&string))
-        .transpose()?;
+/// Same as `encode_function_call` with encoding options passed as a struct
+pub fn encode_function_call_ext(
+    abi: &str,
+    function: &str,
+    parameters: &str,
+    options: &EncodeOptions,
+) -> Result<BuilderData> {
+    JsonAbiSession::new(abi)?.encode_function_call_ext(function, parameters, options)
+}
 
-    function.encode_input(&header_tokens, &input_tokens, internal, sign_key, address)
+/// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
+/// which can be used as message body for calling contract. External message body is signed by
+/// `signer` closure receiving hash to be signed, so secret key is not passed to this crate.
+pub fn encode_function_call_with_signer<F>(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    internal: bool,
+    public_key: Option<&PublicKeyData>,
+    signer: F,
+    address: Option<&str>,
+) -> Result<BuilderData>
+where
+    F: FnOnce(&[u8]) -> Result<SignatureData>,
+{
+    JsonAbiSession::new(abi)?.encode_function_call_with_signer(
+        function, header, parameters, internal, public_key, signer, address,
+    )
 }
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
@@ -72,25 +693,19 @@ pub fn prepare_function_call_for_sign(
     parameters: &str,
     address: Option<&str>,
 ) -> Result<(BuilderData, Vec<u8>)> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let function = contract.function(function)?;
-
-    let header_tokens = if let Some(header) = header {
-        let v: Value = serde_json::from_str(header).map_err(|err| AbiError::SerdeError { err })?;
-        Tokenizer::tokenize_optional_params(function.header_params(), &v)?
-    } else {
-        HashMap::new()
-    };
-
-    let v: Value = serde_json::from_str(&parameters).map_err(|err| AbiError::SerdeError { err })?;
-    let input_tokens = Tokenizer::tokenize_all_params(function.input_params(), &v)?;
-
-    let address = address
-        .map(|string| MsgAddressInt::from_str(&string))
-        .transpose()?;
+    JsonAbiSession::new(abi)?.prepare_function_call_for_sign(function, header, parameters, address)
+}
 
-    function.create_unsigned_call(&header_tokens, &input_tokens, false, true, address)
+/// Returns hash to be signed for the call of `function` of contract described by `abi`.
+/// Header values which default to the current time should be passed explicitly.
+pub fn compute_signing_hash(
+    abi: &str,
+    function: &str,
+    header: Option<&str>,
+    parameters: &str,
+    address: Option<&str>,
+) -> Result<Vec<u8>> {
+    JsonAbiSession::new(abi)?.compute_signing_hash(function, header, parameters, address)
 }
 
 /// Add sign to messsage body returned by `prepare_function_call_for_sign` function
@@ -100,8 +715,7 @@ pub fn add_sign_to_function_call(
     public_key: Option<&PublicKeyData>,
     function_call: SliceData,
 ) -> Result<BuilderData> {
-    let contract = Contract::load(abi.as_bytes())?;
-    contract.add_sign_to_encoded_input(signature, public_key, function_call)
+    JsonAbiSession::new(abi)?.add_sign_to_function_call(signature, public_key, function_call)
 }
 
 /// Decodes output parameters returned by contract function call
@@ -112,18 +726,17 @@ pub fn decode_function_response(
     internal: bool,
     allow_partial: bool,
 ) -> Result<String> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let function = contract.function(&function)?;
-
-    let tokens = function.decode_output(response, internal, allow_partial)?;
-
-    Detokenizer::detokenize(&tokens)
+    JsonAbiSession::new(abi)?.decode_function_response(function, response, internal, allow_partial)
 }
 
-pub struct DecodedMessage {
-    pub function_name: String,
-    pub params: String,
+/// Same as `decode_function_response` with decoding options passed as a struct
+pub fn decode_function_response_ext(
+    abi: &str,
+    function: &str,
+    response: SliceData,
+    options: &DecodeOptions,
+) -> Result<String> {
+    JsonAbiSession::new(abi)?.decode_function_response_ext(function, response, options)
 }
 
 /// Decodes output parameters returned by some function call. Returns parametes and function name
@@ -133,16 +746,16 @@ pub fn decode_unknown_function_response(
     internal: bool,
     allow_partial: bool,
 ) -> Result<DecodedMessage> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let result = contract.decode_output(response, internal, allow_partial)?;
-
-    let output = Detokenizer::detokenize(&result.tokens)?;
+    JsonAbiSession::new(abi)?.decode_unknown_function_response(response, internal, allow_partial)
+}
 
-    Ok(DecodedMessage {
-        function_name: result.function_name,
-        params: output,
-    })
+/// Same as `decode_unknown_function_response` with decoding options passed as a struct
+pub fn decode_unknown_function_response_ext(
+    abi: &str,
+    response: SliceData,
+    options: &DecodeOptions,
+) -> Result<DecodedMessage> {
+    JsonAbiSession::new(abi)?.decode_unknown_function_response_ext(response, options)
 }
 
 /// Decodes output parameters returned by some function call. Returns parametes and function name
@@ -152,49 +765,41 @@ pub fn decode_unknown_function_call(
     internal: bool,
     allow_partial: bool,
 ) -> Result<DecodedMessage> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let result = contract.decode_input(response, internal, allow_partial)?;
-
-    let input = Detokenizer::detokenize(&result.tokens)?;
+    JsonAbiSession::new(abi)?.decode_unknown_function_call(response, internal, allow_partial)
+}
 
-    Ok(DecodedMessage {
-        function_name: result.function_name,
-        params: input,
-    })
+/// Same as `decode_unknown_function_call` with decoding options passed as a struct
+pub fn decode_unknown_function_call_ext(
+    abi: &str,
+    response: SliceData,
+    options: &DecodeOptions,
+) -> Result<DecodedMessage> {
+    JsonAbiSession::new(abi)?.decode_unknown_function_call_ext(response, options)
 }
 
 /// Changes initial values for public contract variables
 pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> Result<SliceData> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let data_json: serde_json::Value = serde_json::from_str(parameters)?;
-
-    let params: Vec<_> = contract
-        .data()
-        .values()
-        .map(|item| item.value.clone())
-        .collect();
-
-    let tokens = Tokenizer::tokenize_all_params(&params[..], &data_json)?;
-
-    contract.update_data(data, &tokens)
+    JsonAbiSession::new(abi)?.update_contract_data(parameters, data)
 }
 
 /// Decode initial values of public contract variables
 pub fn decode_contract_data(abi: &str, data: SliceData, allow_partial: bool) -> Result<String> {
-    let contract = Contract::load(abi.as_bytes())?;
+    JsonAbiSession::new(abi)?.decode_contract_data(data, allow_partial)
+}
 
-    Detokenizer::detokenize(&contract.decode_data(data, allow_partial)?)
+/// Decode initial values of public contract variables along with values stored under keys
+/// not declared in ABI
+pub fn decode_contract_data_with_unknown(
+    abi: &str,
+    data: SliceData,
+    allow_partial: bool,
+) -> Result<String> {
+    JsonAbiSession::new(abi)?.decode_contract_data_with_unknown(data, allow_partial)
 }
 
 /// Decode account storage fields
 pub fn decode_storage_fields(abi: &str, data: SliceData, allow_partial: bool) -> Result<String> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let decoded = contract.decode_storage_fields(data, allow_partial)?;
-
-    Detokenizer::detokenize(&decoded)
+    JsonAbiSession::new(abi)?.decode_storage_fields(data, allow_partial)
 }
 
 /// Get signature and signed hash from function call data
@@ -203,27 +808,13 @@ pub fn get_signature_data(
     cursor: SliceData,
     address: Option<&str>,
 ) -> Result<(Vec<u8>, Vec<u8>)> {
-    let contract = Contract::load(abi.as_bytes())?;
-    let address = address
-        .map(|string| MsgAddressInt::from_str(string))
-        .transpose()?;
-    contract.get_signature_data(cursor, address)
+    JsonAbiSession::new(abi)?.get_signature_data(cursor, address)
 }
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
 /// which can be used as message body for calling contract
 pub fn encode_storage_fields(abi: &str, init_fields: Option<&str>) -> Result<BuilderData> {
-    let contract = Contract::load(abi.as_bytes())?;
-
-    let init_fields = if let Some(init_fields) = init_fields {
-        let v: Value =
-            serde_json::from_str(&init_fields).map_err(|err| AbiError::SerdeError { err })?;
-        Tokenizer::tokenize_optional_params(&contract.fields(), &v)?
-    } else {
-        HashMap::new()
-    };
-
-    contract.encode_storage_fields(init_fields)
+    JsonAbiSession::new(abi)?.encode_storage_fields(init_fields)
 }
 
 #[cfg(test)]
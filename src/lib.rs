@@ -11,24 +11,62 @@
 * limitations under the License.
 */
 
+pub mod address;
+pub mod bundle;
+pub mod compiler_json;
+pub mod compression;
 pub mod contract;
+pub mod coverage;
+pub mod custom_types;
+pub mod debot;
 pub mod error;
 pub mod event;
+#[cfg(any(test, feature = "fixed_bytes_compat"))]
+pub mod fixed_bytes_compat;
 pub mod function;
+#[cfg(any(test, feature = "golden"))]
+pub mod golden;
 pub mod int;
 pub mod json_abi;
+pub mod lint;
+pub mod multisig;
 pub mod param;
 pub mod param_type;
+pub mod resolver;
+pub mod selector;
+pub mod state_diff;
+pub mod stats;
+#[cfg(any(test, feature = "well_known"))]
+pub mod system;
 pub mod token;
+#[cfg(any(test, feature = "well_known"))]
+pub mod well_known;
 
 pub use contract::{Contract, DataItem, PublicKeyData, SignatureData};
 pub use error::*;
-pub use event::Event;
-pub use function::Function;
-pub use int::{Int, Uint};
+pub use event::{Event, EventFilter};
+pub use function::{
+    Function, FunctionId, HeaderInfo, HeaderValue, PayloadEnvelope, SignaturePatch,
+};
+pub use int::{Int, IntBounds, Uint};
 pub use json_abi::*;
-pub use param::Param;
-pub use param_type::ParamType;
-pub use token::{Token, TokenValue};
+pub use param::{HeaderParam, Param};
+pub use param_type::{ParamType, ValueRange};
+pub use token::{Token, TokenValue, Tokens};
+
+// ABI values are shared between threads of multi-threaded decoders, so the types must stay
+// `Send + Sync`. The assertion fails to compile if a non thread-safe member is introduced.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Contract>();
+    assert_send_sync::<Function>();
+    assert_send_sync::<Event>();
+    assert_send_sync::<Token>();
+    assert_send_sync::<TokenValue>();
+    assert_send_sync::<contract::DecodedMessage>();
+    assert_send_sync::<token::DetokenizeOptions>();
+    assert_send_sync::<custom_types::CustomContract>();
+    assert_send_sync::<coverage::CoverageContract>();
+};
 
 include!("../common/src/info.rs");
@@ -0,0 +1,70 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI lints.
+//!
+//! Lints report ABI constructions which are valid but may lead to surprising results, e.g.
+//! explicit `"id"` values making function answer and event indistinguishable.
+
+use crate::contract::Contract;
+
+use std::fmt;
+
+/// Suspicious ABI construction found by `Contract::lint`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AbiWarning {
+    /// Function output ID equals event ID. `Contract::decode_output` and `Contract::peek`
+    /// resolve such bodies as function answers, so the event can not be decoded by ID.
+    OutputEventIdCollision {
+        id: u32,
+        function: String,
+        event: String,
+    },
+}
+
+impl fmt::Display for AbiWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiWarning::OutputEventIdCollision { id, function, event } => write!(
+                f,
+                "Output of function `{}` and event `{}` have the same ID {:08X}; \
+                    bodies with this ID are decoded as function output",
+                function, event, id
+            ),
+        }
+    }
+}
+
+impl Contract {
+    /// Checks loaded ABI for suspicious constructions. Warnings are sorted by ID and names.
+    pub fn lint(&self) -> Vec<AbiWarning> {
+        let mut warnings = vec![];
+        for function in self.functions().values() {
+            for event in self.events().values() {
+                if function.get_output_id() == event.get_id() {
+                    warnings.push(AbiWarning::OutputEventIdCollision {
+                        id: event.get_id(),
+                        function: function.name.clone(),
+                        event: event.name.clone(),
+                    });
+                }
+            }
+        }
+        warnings.sort();
+        warnings
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_lint.rs"]
+mod tests;
@@ -0,0 +1,203 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Multi-signature function call bodies.
+//!
+//! Body with several signature slots is encoded as a regular external call body where signature
+//! bits are replaced with a reference to the signature slots chain. Every slot is stored in
+//! a separate cell: `1` bit followed by signature or `0` bit for an empty slot, and a reference
+//! to the next slot cell if any. All signers sign the same hash - the hash of the body without
+//! signature slots (prefixed with destination address since ABI 2.3).
+
+use crate::{
+    contract::AbiVersion,
+    error::AbiError,
+    function::Function,
+    token::{Cursor, SerializedValue, Token, TokenValue},
+    SignatureData,
+};
+
+use std::collections::HashMap;
+use ever_block::{
+    fail, BuilderData, Cell, IBitstring, MsgAddressInt, Result, SliceData,
+    ED25519_SIGNATURE_LENGTH,
+};
+
+impl Function {
+    fn check_multisig_support(abi_version: &AbiVersion) -> Result<()> {
+        if !abi_version.at_least(2, 0) {
+            fail!(AbiError::NotSupported {
+                subject: "Multi-signature body".to_owned(),
+                version: *abi_version,
+            });
+        }
+        Ok(())
+    }
+
+    /// Encodes external function call without signature slots. Returns body and hash to be
+    /// signed by every signer. Slots should be added by `fill_signatures` function.
+    pub fn create_unsigned_multisig_call(
+        &self,
+        header: &HashMap<String, TokenValue>,
+        input: &[Token],
+        address: Option<MsgAddressInt>,
+    ) -> Result<(BuilderData, Vec<u8>)> {
+        Self::check_multisig_support(&self.abi_version)?;
+
+        if !Token::types_check(input, self.input_params().as_slice()) {
+            fail!(AbiError::WrongParameterType);
+        }
+
+        let mut cells = self.encode_header(header, false)?;
+
+        // reserve reference for signature slots
+        let mut slots_builder = BuilderData::new();
+        slots_builder.checked_append_reference(Cell::default())?;
+        cells.insert(
+            0,
+            SerializedValue {
+                data: slots_builder,
+                max_bits: 0,
+                max_refs: 1,
+            },
+        );
+
+        let builder = TokenValue::pack_values_into_chain(input, cells, &self.abi_version)?;
+        let mut slice = SliceData::load_builder(builder)?;
+        slice.checked_drain_reference()?;
+
+        let hash = Self::signing_hash(&self.abi_version, slice.clone(), address)?;
+
+        Ok((slice.as_builder(), hash))
+    }
+
+    /// Adds signature slots to the body returned by `create_unsigned_multisig_call`. Empty slots
+    /// are placeholders which can be filled later by `add_sign_at` function.
+    pub fn fill_signatures(
+        signatures: &[Option<SignatureData>],
+        mut builder: BuilderData,
+    ) -> Result<BuilderData> {
+        if signatures.is_empty() {
+            fail!(AbiError::InvalidInputData {
+                msg: "At least one signature slot is required".to_owned()
+            });
+        }
+        if builder.references_free() == 0 {
+            fail!(AbiError::InvalidInputData {
+                msg: "No free reference for signature slots".to_owned()
+            });
+        }
+
+        let mut next: Option<Cell> = None;
+        for signature in signatures.iter().rev() {
+            let mut slot = BuilderData::new();
+            if let Some(signature) = signature {
+                slot.append_bit_one()?;
+                slot.append_raw(signature, ED25519_SIGNATURE_LENGTH * 8)?;
+            } else {
+                slot.append_bit_zero()?;
+            }
+            if let Some(next) = next {
+                slot.checked_append_reference(next)?;
+            }
+            next = Some(slot.into_cell()?);
+        }
+
+        // `signatures` is not empty so `next` is always set
+        builder.checked_prepend_reference(next.unwrap_or_default())?;
+        Ok(builder)
+    }
+
+    /// Reads signature slots of the multi-signature body. Returns signatures and the body without
+    /// signature slots.
+    pub fn decode_signatures(
+        mut body: SliceData,
+    ) -> Result<(Vec<Option<SignatureData>>, SliceData)> {
+        let mut signatures = vec![];
+        let mut next = Some(body.checked_drain_reference()?);
+        while let Some(cell) = next {
+            let mut slot = SliceData::load_cell(cell)?;
+            if slot.get_next_bit()? {
+                let mut signature = [0u8; ED25519_SIGNATURE_LENGTH];
+                signature.copy_from_slice(&slot.get_next_bytes(ED25519_SIGNATURE_LENGTH)?);
+                signatures.push(Some(signature));
+            } else {
+                signatures.push(None);
+            }
+            next = slot.reference_opt(0);
+        }
+        Ok((signatures, body))
+    }
+
+    /// Puts signature into the slot with provided index of the multi-signature body
+    pub fn add_sign_at(
+        body: SliceData,
+        index: usize,
+        signature: &SignatureData,
+    ) -> Result<BuilderData> {
+        let (mut signatures, body) = Self::decode_signatures(body)?;
+        let slots_count = signatures.len();
+        let slot = signatures.get_mut(index).ok_or_else(|| AbiError::InvalidInputData {
+            msg: format!(
+                "Signature slot index {} is out of range ({} slots)",
+                index, slots_count
+            ),
+        })?;
+        *slot = Some(*signature);
+
+        Self::fill_signatures(&signatures, body.as_builder())
+    }
+
+    /// Decodes multi-signature function call. Returns signatures and function parameters.
+    pub fn decode_multisig_input(
+        &self,
+        body: SliceData,
+        allow_partial: bool,
+    ) -> Result<(Vec<Option<SignatureData>>, Vec<Token>)> {
+        Self::check_multisig_support(&self.abi_version)?;
+
+        let (signatures, body) = Self::decode_signatures(body)?;
+        let cursor = Cursor {
+            used_bits: 0,
+            used_refs: 1,
+            slice: body,
+        };
+        let (_, mut cursor) = TokenValue::decode_params_with_cursor(
+            self.header_params(),
+            cursor,
+            &self.abi_version,
+            true,
+            false,
+        )?;
+
+        let id = cursor.slice.get_next_u32()?;
+        cursor.used_bits += 32;
+        if id != self.get_input_id() {
+            fail!(AbiError::WrongId { id });
+        }
+
+        let (tokens, _) = TokenValue::decode_params_with_cursor(
+            self.input_params(),
+            cursor,
+            &self.abi_version,
+            allow_partial,
+            true,
+        )?;
+
+        Ok((signatures, tokens))
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_multisig.rs"]
+mod tests;
@@ -12,11 +12,11 @@
 */
 
 //! Function param.
-use crate::param_type::ParamType;
+use crate::{error::AbiError, param_type::ParamType};
 use serde::de::{Deserialize, Deserializer, Error};
 
 /// Function param.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Param {
     /// Param name.
     pub name: String,
@@ -47,6 +47,55 @@ impl Param {
     }
 }
 
+/// Standard header parameters which can be declared in ABI `header` section by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeaderParam {
+    Time,
+    Expire,
+    PublicKey,
+}
+
+impl HeaderParam {
+    pub const ALL: [HeaderParam; 3] =
+        [HeaderParam::Time, HeaderParam::Expire, HeaderParam::PublicKey];
+
+    /// Returns header parameter declared by `name`: `time`, `expire` or `pubkey`
+    pub fn from_name(name: &str) -> ever_block::Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|param| param.name() == name)
+            .ok_or_else(|| {
+                AbiError::InvalidName {
+                    name: format!("`{}` is not a header parameter", name),
+                }
+                .into()
+            })
+    }
+
+    /// Returns parameter name used in ABI and in header values
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeaderParam::Time => "time",
+            HeaderParam::Expire => "expire",
+            HeaderParam::PublicKey => "pubkey",
+        }
+    }
+
+    pub fn param_type(&self) -> ParamType {
+        match self {
+            HeaderParam::Time => ParamType::Time,
+            HeaderParam::Expire => ParamType::Expire,
+            HeaderParam::PublicKey => ParamType::PublicKey,
+        }
+    }
+
+    /// Returns header parameter template
+    pub fn to_param(&self) -> Param {
+        Param::new(self.name(), self.param_type())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 pub(crate) struct SerdeParam {
     /// Param name.
@@ -74,23 +123,14 @@ impl<'a> Deserialize<'a> for Param {
         // if parameter is a tuple repack tuple components from `SerdeParam::components`
         // into `ParamType::Tuple`
         let value = serde_json::Value::deserialize(deserializer)?;
-        if value.is_string() {
-            let type_str = value.as_str().unwrap();
-            let param_type: ParamType =
-                serde_json::from_value(value.clone()).map_err(|err| D::Error::custom(err))?;
-            match param_type {
-                ParamType::Tuple(_) |
-                ParamType::Array(_) |
-                ParamType::FixedArray(_, _) |
-                ParamType::Map(_, _) =>
-                    return Err(D::Error::custom(
-                        format!("Invalid parameter specification: {}. Only simple types can be represented as strings",
-                            type_str))),
-                _ => {}
-            }
-            Ok(Self {
-                name: type_str.to_owned(),
-                kind: param_type,
+        if let Some(name) = value.as_str() {
+            // only standard header parameters can be declared by name
+            HeaderParam::from_name(name).map(|param| param.to_param()).map_err(|_| {
+                D::Error::custom(format!(
+                    "Invalid parameter specification: {}. Only `time`, `expire` and `pubkey` \
+                        header parameters can be represented as strings",
+                    name
+                ))
             })
         } else {
             let serde_param: SerdeParam =
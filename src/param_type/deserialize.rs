@@ -144,6 +144,7 @@ pub fn read_type(name: &str) -> Result<ParamType> {
         "expire" => ParamType::Expire,
         "pubkey" => ParamType::PublicKey,
         "string" => ParamType::String,
+        "slice" => ParamType::Slice,
         s if s.starts_with("optional(") && s.ends_with(")") => {
             let inner_type = read_type(&name[9..name.len() - 1])?;
             ParamType::Optional(Box::new(inner_type))
@@ -17,7 +17,7 @@ mod deserialize;
 mod param_type;
 
 pub use self::deserialize::read_type;
-pub use self::param_type::ParamType;
+pub use self::param_type::{ParamType, ValueRange, MAX_TYPE_DEPTH};
 
 #[cfg(test)]
 mod tests;
@@ -13,14 +13,22 @@
 
 //! Function and event param types.
 
-use crate::{AbiError, Param};
-use crate::contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_1, ABI_VERSION_2_4};
+use crate::{
+    token::{MAX_SLICE_BITS, SLICE_LENGTH_BITS},
+    AbiError, IntBounds, Param, TokenValue,
+};
+use crate::contract::{AbiVersion, ABI_VERSION_2_4};
+use num_bigint::{BigInt, BigUint};
+use serde_json::{json, Value};
 use std::fmt;
 
-use ever_block::{error, Result};
+use ever_block::{error, fail, BuilderData, Result, ED25519_PUBLIC_KEY_LENGTH};
+
+/// Maximum nesting depth of parameter types accepted by `Contract::load`
+pub const MAX_TYPE_DEPTH: usize = 64;
 
 /// Function and event param types.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ParamType {
     /// uint<M>: unsigned integer type of M bits.
     Uint(usize),
@@ -62,6 +70,21 @@ pub enum ParamType {
     Optional(Box<ParamType>),
     /// Parameter stored in reference
     Ref(Box<ParamType>),
+    /// Raw bitstring stored in place with 10-bit length prefix (non-standard extension)
+    Slice,
+}
+
+/// Range of values accepted by a type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRange {
+    /// Integer-like value within the bounds
+    Int(IntBounds),
+    /// Raw data of up to N bits
+    Bits(usize),
+    /// Byte array of exactly N bytes
+    Bytes(usize),
+    /// Value size is limited only by message or storage size
+    Unbounded,
 }
 
 impl fmt::Display for ParamType {
@@ -110,6 +133,7 @@ impl ParamType {
                 format!("optional({})", param_type.type_signature())
             }
             ParamType::Ref(ref param_type) => format!("ref({})", param_type.type_signature()),
+            ParamType::Slice => "slice".to_owned(),
         }
     }
 
@@ -137,18 +161,369 @@ impl ParamType {
         }
     }
 
+    /// Checks that type nesting is not deeper than `max_depth`. Types without inner types have
+    /// depth 0. The check does not use recursion, so it is safe for arbitrary deep types.
+    pub fn validate(&self, max_depth: usize) -> Result<()> {
+        let mut stack = vec![(self, 0)];
+        while let Some((param_type, depth)) = stack.pop() {
+            if depth > max_depth {
+                fail!(AbiError::TypeTooDeep { max_depth })
+            }
+            match param_type {
+                ParamType::Tuple(params) => {
+                    stack.extend(params.iter().map(|param| (&param.kind, depth + 1)))
+                }
+                ParamType::Array(inner)
+                | ParamType::FixedArray(inner, _)
+                | ParamType::Optional(inner)
+                | ParamType::Ref(inner) => stack.push((inner, depth + 1)),
+                ParamType::Map(key, value) => {
+                    stack.push((key, depth + 1));
+                    stack.push((value, depth + 1));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Check if parameter type is supoorted in particular ABI version
     pub fn is_supported(&self, abi_version: &AbiVersion) -> bool {
         match self {
             ParamType::Time | ParamType::Expire | ParamType::PublicKey => {
-                abi_version >= &ABI_VERSION_2_0
+                abi_version.at_least(2, 0)
             }
             ParamType::String
             | ParamType::Optional(_)
             | ParamType::VarInt(_)
-            | ParamType::VarUint(_) => abi_version >= &ABI_VERSION_2_1,
-            ParamType::Ref(_) => abi_version >= &ABI_VERSION_2_4,
-            _ => abi_version >= &ABI_VERSION_1_0,
+            | ParamType::VarUint(_) => abi_version.at_least(2, 1),
+            ParamType::Ref(_) | ParamType::Slice => abi_version.at_least(2, 4),
+            _ => abi_version.at_least(1, 0),
         }
     }
+
+    /// Returns range of values accepted by the type. `optional(T)` and `ref(T)` have the range
+    /// of `T`.
+    pub fn value_range(&self) -> ValueRange {
+        if let Some(bounds) = IntBounds::for_type(self) {
+            return ValueRange::Int(bounds);
+        }
+        match self {
+            ParamType::Bool => ValueRange::Bits(1),
+            ParamType::Cell => ValueRange::Bits(BuilderData::bits_capacity()),
+            ParamType::Slice => ValueRange::Bits(MAX_SLICE_BITS),
+            ParamType::Address => {
+                ValueRange::Bits(self.max_bit_size(&ABI_VERSION_2_4))
+            }
+            ParamType::FixedBytes(size) => ValueRange::Bytes(*size),
+            ParamType::PublicKey => ValueRange::Bytes(ED25519_PUBLIC_KEY_LENGTH),
+            ParamType::Optional(inner) | ParamType::Ref(inner) => inner.value_range(),
+            _ => ValueRange::Unbounded,
+        }
+    }
+
+    /// Wraps type into `ref(T)`
+    pub fn into_ref(self) -> ParamType {
+        ParamType::Ref(Box::new(self))
+    }
+
+    /// Returns maximum number of bits value of the type takes in the current cell
+    pub fn max_bit_size(&self, abi_version: &AbiVersion) -> usize {
+        self.max_size(abi_version).0
+    }
+
+    /// Returns maximum number of references value of the type takes in the current cell
+    pub fn max_refs_count(&self, abi_version: &AbiVersion) -> usize {
+        self.max_size(abi_version).1
+    }
+
+    // maximum numbers of bits and references value of the type takes in the current cell
+    // without inner values serialized into separate cells
+    fn in_cell_size(&self, abi_version: &AbiVersion) -> (usize, usize) {
+        match self {
+            ParamType::Uint(size) | ParamType::Int(size) => (*size, 0),
+            ParamType::VarUint(size) | ParamType::VarInt(size) => {
+                (TokenValue::varint_size_len(*size) + (size - 1) * 8, 0)
+            }
+            ParamType::Bool => (1, 0),
+            ParamType::Array(_) => (33, 1),
+            ParamType::FixedArray(_, _) => (1, 1),
+            ParamType::Cell => (0, 1),
+            ParamType::Map(_, _) => (1, 1),
+            ParamType::Address => (591, 0),
+            ParamType::FixedBytes(size) if abi_version.at_least(2, 4) => (size * 8, 0),
+            ParamType::Bytes | ParamType::FixedBytes(_) => (0, 1),
+            ParamType::String => (0, 1),
+            ParamType::Token => (124, 0),
+            ParamType::Time => (64, 0),
+            ParamType::Expire => (32, 0),
+            ParamType::PublicKey => (257, 0),
+            ParamType::Slice => (SLICE_LENGTH_BITS + MAX_SLICE_BITS, 0),
+            ParamType::Ref(_) => (0, 1),
+            // composite types are computed by `max_size`
+            ParamType::Tuple(_) | ParamType::Optional(_) => (0, 0),
+        }
+    }
+
+    // Computes maximum numbers of bits and references together. Tuples and optionals are
+    // traversed with explicit stack, so deeply nested types can not overflow the thread stack.
+    fn max_size(&self, abi_version: &AbiVersion) -> (usize, usize) {
+        enum Step<'a> {
+            Enter(&'a ParamType),
+            Exit(&'a ParamType),
+        }
+
+        let mut steps = vec![Step::Enter(self)];
+        let mut sizes: Vec<(usize, usize)> = vec![];
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Enter(param_type) => match param_type {
+                    ParamType::Tuple(params) => {
+                        steps.push(Step::Exit(param_type));
+                        steps.extend(params.iter().map(|param| Step::Enter(&param.kind)));
+                    }
+                    ParamType::Optional(inner) => {
+                        steps.push(Step::Exit(param_type));
+                        steps.push(Step::Enter(inner));
+                    }
+                    _ => sizes.push(param_type.in_cell_size(abi_version)),
+                },
+                // tuple size is sum of components sizes
+                Step::Exit(ParamType::Tuple(params)) => {
+                    let components = sizes.split_off(sizes.len() - params.len());
+                    sizes.push(components.iter().fold((0, 0), |(bits, refs), size| {
+                        (bits + size.0, refs + size.1)
+                    }));
+                }
+                // large optional is serialized into reference
+                Step::Exit(_) => {
+                    let (bits, refs) = sizes.pop().unwrap_or_default();
+                    if Self::is_large_size(bits, refs) {
+                        sizes.push((1, 1));
+                    } else {
+                        sizes.push((1 + bits, refs));
+                    }
+                }
+            }
+        }
+        sizes.pop().unwrap_or_default()
+    }
+
+    fn is_large_size(bits: usize, refs: usize) -> bool {
+        bits >= BuilderData::bits_capacity() || refs >= BuilderData::references_capacity()
+    }
+
+    /// Checks if `optional(T)` value is serialized into separate cell. It is true when `T` can
+    /// take the whole cell data or all cell references. Returns `false` for non-optional types.
+    pub fn is_large_optional(&self, abi_version: &AbiVersion) -> bool {
+        match self {
+            ParamType::Optional(inner) => inner.is_large_optional_value(abi_version),
+            _ => false,
+        }
+    }
+
+    // checks if value of the type is serialized into separate cell when wrapped into `optional(T)`
+    pub(crate) fn is_large_optional_value(&self, abi_version: &AbiVersion) -> bool {
+        let (bits, refs) = self.max_size(abi_version);
+        Self::is_large_size(bits, refs)
+    }
+
+    /// Check if value of the type can not fit into one cell
+    pub fn exceeds_cell(&self, abi_version: &AbiVersion) -> bool {
+        let (bits, refs) = self.max_size(abi_version);
+        bits > BuilderData::bits_capacity() || refs > BuilderData::references_capacity()
+    }
+
+    /// Returns type with tuples which can not fit into one cell wrapped into `ref(T)`.
+    /// Type is returned unchanged if ABI version doesn't support `ref(T)`.
+    pub fn promote_large_tuples(&self, abi_version: &AbiVersion) -> ParamType {
+        if !abi_version.at_least(2, 4) {
+            return self.clone();
+        }
+        match self {
+            ParamType::Tuple(_) => {
+                let promoted = self.promote_tuple_components(abi_version);
+                if promoted.exceeds_cell(abi_version) {
+                    promoted.into_ref()
+                } else {
+                    promoted
+                }
+            }
+            ParamType::Array(inner) => {
+                ParamType::Array(Box::new(inner.promote_large_tuples(abi_version)))
+            }
+            ParamType::FixedArray(inner, size) => {
+                ParamType::FixedArray(Box::new(inner.promote_large_tuples(abi_version)), *size)
+            }
+            ParamType::Map(key, value) => {
+                ParamType::Map(key.clone(), Box::new(value.promote_large_tuples(abi_version)))
+            }
+            ParamType::Optional(inner) => {
+                ParamType::Optional(Box::new(inner.promote_large_tuples(abi_version)))
+            }
+            // tuple is already stored in reference so only its components are promoted
+            ParamType::Ref(inner) => match inner.as_ref() {
+                ParamType::Tuple(_) => inner.promote_tuple_components(abi_version).into_ref(),
+                _ => inner.promote_large_tuples(abi_version).into_ref(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    fn promote_tuple_components(&self, abi_version: &AbiVersion) -> ParamType {
+        match self {
+            ParamType::Tuple(params) => ParamType::Tuple(
+                params
+                    .iter()
+                    .map(|param| Param {
+                        name: param.name.clone(),
+                        kind: param.kind.promote_large_tuples(abi_version),
+                    })
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns JSON Schema describing values accepted by tokenizer for this type
+    pub fn json_schema(&self) -> Value {
+        match self {
+            ParamType::Uint(size) => Self::uint_schema(*size),
+            ParamType::Int(size) => Self::int_schema(*size),
+            ParamType::VarUint(size) => Self::uint_schema((size - 1) * 8),
+            ParamType::VarInt(size) => Self::int_schema((size - 1) * 8),
+            ParamType::Bool => json!({
+                "anyOf": [
+                    { "type": "boolean" },
+                    { "type": "string", "enum": ["true", "false"] },
+                ]
+            }),
+            ParamType::Tuple(params) => Self::object_schema(params),
+            ParamType::Array(item_type) => json!({
+                "type": "array",
+                "items": item_type.json_schema(),
+            }),
+            ParamType::FixedArray(item_type, size) => json!({
+                "type": "array",
+                "items": item_type.json_schema(),
+                "minItems": size,
+                "maxItems": size,
+            }),
+            ParamType::Cell => json!({
+                "type": "string",
+                "contentEncoding": "base64",
+                "description": "base64-encoded cell BOC",
+            }),
+            ParamType::Map(key_type, value_type) => {
+                let mut schema = json!({
+                    "type": "object",
+                    "additionalProperties": value_type.json_schema(),
+                });
+                if let Some(pattern) = Self::string_pattern(key_type) {
+                    schema["propertyNames"] = json!({ "pattern": pattern });
+                }
+                schema
+            }
+            ParamType::Address => json!({
+                "type": "string",
+                "pattern": "^(-?[0-9]+:[0-9a-fA-F]+|:[0-9a-fA-F]*)?$",
+                "description": "message address",
+            }),
+            ParamType::Bytes => json!({
+                "type": "string",
+                "pattern": "^([0-9a-fA-F]{2})*$",
+                "description": "hex-encoded bytes",
+            }),
+            ParamType::FixedBytes(size) => json!({
+                "type": "string",
+                "pattern": format!("^([0-9a-fA-F]{{2}}){{{}}}$", size),
+                "description": format!("hex-encoded {} bytes", size),
+            }),
+            ParamType::String => json!({ "type": "string" }),
+            ParamType::Token => Self::uint_schema(120),
+            ParamType::Time => Self::uint_schema(64),
+            ParamType::Expire => Self::uint_schema(32),
+            ParamType::PublicKey => json!({
+                "type": "string",
+                "pattern": "^(0x)?([0-9a-fA-F]{64})?$",
+                "description": "hex-encoded public key or empty string",
+            }),
+            ParamType::Optional(inner_type) => json!({
+                "anyOf": [
+                    { "type": "null" },
+                    inner_type.json_schema(),
+                ]
+            }),
+            ParamType::Ref(inner_type) => inner_type.json_schema(),
+            ParamType::Slice => json!({
+                "type": "object",
+                "properties": {
+                    "bits": { "type": "integer", "minimum": 0, "maximum": MAX_SLICE_BITS },
+                    "base64": { "type": "string", "contentEncoding": "base64" },
+                },
+                "required": ["bits", "base64"],
+                "description": "raw bitstring, base64-encoded bytes with bits aligned to the left",
+            }),
+        }
+    }
+
+    /// Returns JSON Schema of the object with provided parameters as properties
+    pub(crate) fn object_schema(params: &[Param]) -> Value {
+        let properties: serde_json::Map<String, Value> = params
+            .iter()
+            .map(|param| (param.name.clone(), param.kind.json_schema()))
+            .collect();
+        let required: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    fn string_pattern(param_type: &ParamType) -> Option<&'static str> {
+        match param_type {
+            ParamType::Uint(_) => Some("^(0x[0-9a-fA-F]+|[0-9]+)$"),
+            ParamType::Int(_) => Some("^-?(0x[0-9a-fA-F]+|[0-9]+)$"),
+            ParamType::Address => Some("^(-?[0-9]+:[0-9a-fA-F]+|:[0-9a-fA-F]*)?$"),
+            _ => None,
+        }
+    }
+
+    fn uint_schema(size: usize) -> Value {
+        let max = (BigUint::from(1u8) << size) - 1u8;
+        // JSON numbers are accepted only if they fit into u64
+        let number_max = if size < 64 { (1u64 << size) - 1 } else { u64::MAX };
+        json!({
+            "anyOf": [
+                { "type": "integer", "minimum": 0, "maximum": number_max },
+                { "type": "string", "pattern": Self::string_pattern(&ParamType::Uint(size)) },
+            ],
+            "description": format!("unsigned integer in range [0, {}]", max),
+        })
+    }
+
+    fn int_schema(size: usize) -> Value {
+        let (min, max) = if size == 0 {
+            (BigInt::from(0), BigInt::from(0))
+        } else {
+            let max = (BigInt::from(1) << (size - 1)) - 1;
+            (-&max - 1, max)
+        };
+        // JSON numbers are accepted only if they fit into i64
+        let (number_min, number_max) = if size == 0 {
+            (0, 0)
+        } else if size < 64 {
+            (-(1i64 << (size - 1)), (1i64 << (size - 1)) - 1)
+        } else {
+            (i64::MIN, i64::MAX)
+        };
+        json!({
+            "anyOf": [
+                { "type": "integer", "minimum": number_min, "maximum": number_max },
+                { "type": "string", "pattern": Self::string_pattern(&ParamType::Int(size)) },
+            ],
+            "description": format!("signed integer in range [{}, {}]", min, max),
+        })
+    }
 }
@@ -12,7 +12,9 @@
 */
 
 mod param_type_tests {
-    use crate::{Param, ParamType};
+    use crate::param_type::MAX_TYPE_DEPTH;
+    use crate::{AbiError, Contract, IntBounds, Param, ParamType, ValueRange};
+    use num_bigint::BigInt;
 
     #[test]
     fn test_param_type_signature() {
@@ -98,6 +100,227 @@ mod param_type_tests {
             "ref(uint123)".to_owned()
         );
     }
+
+    #[test]
+    fn test_value_range() {
+        assert_eq!(ParamType::Uint(8).value_range(), ValueRange::Int(IntBounds::uint(8)));
+        assert_eq!(
+            ParamType::Optional(Box::new(ParamType::Int(16))).value_range(),
+            ValueRange::Int(IntBounds::int(16))
+        );
+        assert_eq!(ParamType::Expire.value_range(), ValueRange::Int(IntBounds::uint(32)));
+        assert_eq!(ParamType::Bool.value_range(), ValueRange::Bits(1));
+        assert_eq!(ParamType::FixedBytes(32).value_range(), ValueRange::Bytes(32));
+        assert_eq!(ParamType::PublicKey.value_range(), ValueRange::Bytes(32));
+        assert_eq!(ParamType::Bytes.value_range(), ValueRange::Unbounded);
+
+        if let ValueRange::Int(bounds) = ParamType::VarInt(16).value_range() {
+            assert_eq!(bounds.max, (BigInt::from(1) << 119) - 1);
+            assert_eq!(bounds.min, -(BigInt::from(1) << 119));
+        } else {
+            panic!("Wrong value range");
+        }
+    }
+
+    #[test]
+    fn test_validate_depth() {
+        let nested = |depth: usize| {
+            (0..depth).fold(ParamType::Bool, |kind, i| match i % 3 {
+                0 => ParamType::Array(Box::new(kind)),
+                1 => ParamType::Tuple(vec![
+                    Param::new("a", ParamType::Cell),
+                    Param::new("b", kind),
+                ]),
+                _ => ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(kind)),
+            })
+        };
+        assert!(ParamType::Bool.validate(0).is_ok());
+        assert!(nested(10).validate(10).is_ok());
+        assert!(matches!(
+            nested(11).validate(10).unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::TypeTooDeep { max_depth: 10 }
+        ));
+
+        let abi = |depth: usize| {
+            let kind = format!("uint8{}", "[]".repeat(depth));
+            format!(
+                r#"{{
+                    "version": "2.4",
+                    "functions": [{{
+                        "name": "f",
+                        "inputs": [{{ "name": "a", "type": "{}" }}],
+                        "outputs": []
+                    }}],
+                    "fields": [{{ "name": "b", "type": "{}" }}]
+                }}"#,
+                kind, kind
+            )
+        };
+        assert!(Contract::load(abi(MAX_TYPE_DEPTH).as_bytes()).is_ok());
+        assert!(Contract::load(abi(MAX_TYPE_DEPTH + 1).as_bytes()).is_err());
+    }
+}
+
+mod layout_tests {
+    use crate::contract::{
+        ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_1, ABI_VERSION_2_2, ABI_VERSION_2_3,
+        ABI_VERSION_2_4,
+    };
+    use crate::{Param, ParamType};
+
+    fn optional(param_type: ParamType) -> ParamType {
+        ParamType::Optional(Box::new(param_type))
+    }
+
+    #[test]
+    fn test_is_large_optional() {
+        let small = optional(ParamType::Uint(256));
+        assert!(!small.is_large_optional(&ABI_VERSION_2_4));
+        assert_eq!(small.max_bit_size(&ABI_VERSION_2_4), 257);
+        assert_eq!(small.max_refs_count(&ABI_VERSION_2_4), 0);
+
+        let bits = optional(ParamType::Tuple(
+            (0..4).map(|i| Param::new(&format!("a{}", i), ParamType::Uint(256))).collect(),
+        ));
+        assert!(bits.is_large_optional(&ABI_VERSION_2_4));
+        assert_eq!(bits.max_bit_size(&ABI_VERSION_2_4), 1);
+        assert_eq!(bits.max_refs_count(&ABI_VERSION_2_4), 1);
+
+        let refs = optional(ParamType::Tuple(
+            (0..4).map(|i| Param::new(&format!("a{}", i), ParamType::Cell)).collect(),
+        ));
+        assert!(refs.is_large_optional(&ABI_VERSION_2_4));
+
+        // fixedbytes are stored in cell data only since 2.4
+        let bytes = optional(ParamType::FixedBytes(128));
+        assert!(bytes.is_large_optional(&ABI_VERSION_2_4));
+        assert!(!bytes.is_large_optional(&ABI_VERSION_2_3));
+        assert_eq!(bytes.max_refs_count(&ABI_VERSION_2_3), 1);
+
+        assert!(!ParamType::Uint(256).is_large_optional(&ABI_VERSION_2_4));
+    }
+
+    #[test]
+    fn test_max_size() {
+        let bool_type = || Box::new(ParamType::Bool);
+        let tuple = ParamType::Tuple(vec![
+            Param::new("a", ParamType::Uint(8)),
+            Param::new("b", ParamType::Cell),
+            Param::new("c", ParamType::FixedBytes(4)),
+        ]);
+        let old_versions = [
+            ABI_VERSION_1_0,
+            ABI_VERSION_2_0,
+            ABI_VERSION_2_1,
+            ABI_VERSION_2_2,
+            ABI_VERSION_2_3,
+        ];
+        // type, (bits, refs) before 2.4, (bits, refs) since 2.4
+        let cases = [
+            (ParamType::Uint(8), (8, 0), (8, 0)),
+            (ParamType::Int(256), (256, 0), (256, 0)),
+            (ParamType::VarUint(16), (124, 0), (124, 0)),
+            (ParamType::VarInt(32), (253, 0), (253, 0)),
+            (ParamType::Bool, (1, 0), (1, 0)),
+            (ParamType::Array(bool_type()), (33, 1), (33, 1)),
+            (ParamType::FixedArray(bool_type(), 5), (1, 1), (1, 1)),
+            (ParamType::Cell, (0, 1), (0, 1)),
+            (ParamType::Map(Box::new(ParamType::Uint(8)), bool_type()), (1, 1), (1, 1)),
+            (ParamType::Address, (591, 0), (591, 0)),
+            (ParamType::Bytes, (0, 1), (0, 1)),
+            (ParamType::FixedBytes(32), (0, 1), (256, 0)),
+            (ParamType::String, (0, 1), (0, 1)),
+            (ParamType::Token, (124, 0), (124, 0)),
+            (ParamType::Time, (64, 0), (64, 0)),
+            (ParamType::Expire, (32, 0), (32, 0)),
+            (ParamType::PublicKey, (257, 0), (257, 0)),
+            (ParamType::Slice, (1023, 0), (1023, 0)),
+            (ParamType::Ref(bool_type()), (0, 1), (0, 1)),
+            (tuple.clone(), (8, 2), (40, 1)),
+            (optional(ParamType::Uint(32)), (33, 0), (33, 0)),
+            (optional(ParamType::Slice), (1, 1), (1, 1)),
+            (optional(tuple), (9, 2), (41, 1)),
+        ];
+        for (param_type, old, new) in cases {
+            for version in &old_versions {
+                assert_eq!(
+                    (param_type.max_bit_size(version), param_type.max_refs_count(version)),
+                    old,
+                    "{} {}",
+                    param_type,
+                    version
+                );
+            }
+            assert_eq!(
+                (
+                    param_type.max_bit_size(&ABI_VERSION_2_4),
+                    param_type.max_refs_count(&ABI_VERSION_2_4)
+                ),
+                new,
+                "{}",
+                param_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_deep_layout() {
+        // nested optionals were computed in exponential time by recursive implementation
+        let deep = (0..2000).fold(ParamType::Uint(8), |kind, i| match i % 2 {
+            0 => optional(kind),
+            _ => ParamType::Tuple(vec![Param::new("a", kind), Param::new("b", ParamType::Bool)]),
+        });
+        // optional is moved into reference once its value can take the whole cell
+        assert_eq!(deep.max_bit_size(&ABI_VERSION_2_4), 984);
+        assert_eq!(deep.max_refs_count(&ABI_VERSION_2_4), 1);
+        assert!(!deep.exceeds_cell(&ABI_VERSION_2_4));
+
+        let flat = (0..30).fold(ParamType::Bool, |kind, _| optional(kind));
+        assert_eq!(flat.max_bit_size(&ABI_VERSION_2_4), 31);
+        assert_eq!(flat.max_refs_count(&ABI_VERSION_2_4), 0);
+    }
+}
+
+mod promote_tests {
+    use crate::contract::{ABI_VERSION_2_3, ABI_VERSION_2_4};
+    use crate::{Param, ParamType, Token, TokenValue, Uint};
+
+    fn uint_tuple(count: usize) -> ParamType {
+        ParamType::Tuple(
+            (0..count)
+                .map(|i| Param::new(&format!("a{}", i), ParamType::Uint(256)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_promote_large_tuples() {
+        let small = uint_tuple(3);
+        let large = uint_tuple(4);
+
+        assert_eq!(small.promote_large_tuples(&ABI_VERSION_2_4), small);
+        assert_eq!(large.promote_large_tuples(&ABI_VERSION_2_4), large.clone().into_ref());
+        assert_eq!(large.promote_large_tuples(&ABI_VERSION_2_3), large);
+
+        let array = ParamType::Array(Box::new(large.clone()));
+        assert_eq!(
+            array.promote_large_tuples(&ABI_VERSION_2_4),
+            ParamType::Array(Box::new(large.clone().into_ref()))
+        );
+
+        // tuple already stored in reference is not wrapped again
+        let reference = large.clone().into_ref();
+        assert_eq!(reference.promote_large_tuples(&ABI_VERSION_2_4), reference);
+
+        let value = TokenValue::Tuple(
+            (0..4)
+                .map(|i| Token::new(&format!("a{}", i), TokenValue::Uint(Uint::new(i, 256))))
+                .collect(),
+        );
+        let promoted = value.clone().promote_large_tuples(&ABI_VERSION_2_4);
+        assert_eq!(promoted, value.into_ref());
+        assert!(promoted.type_check(&large.promote_large_tuples(&ABI_VERSION_2_4)));
+    }
 }
 
 mod deserialize_tests {
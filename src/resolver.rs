@@ -0,0 +1,135 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI lookup by contract address or code hash.
+//!
+//! Crate does not access network or databases itself. Applications implement `AbiResolver`
+//! over their ABI storage and use decoding functions of this module to decode messages of
+//! contracts which ABI is not known in advance.
+
+use crate::{contract::DecodedMessage, error::AbiError, Contract};
+
+use ever_block::{error, MsgAddressInt, Result, SliceData, UInt256};
+use std::collections::HashMap;
+
+/// Key to look contract ABI up by
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiKey {
+    Address(MsgAddressInt),
+    CodeHash(UInt256),
+}
+
+/// Source of contract ABIs
+pub trait AbiResolver {
+    fn resolve(&self, key: &AbiKey) -> Option<Contract>;
+}
+
+impl<F: Fn(&AbiKey) -> Option<Contract>> AbiResolver for F {
+    fn resolve(&self, key: &AbiKey) -> Option<Contract> {
+        self(key)
+    }
+}
+
+/// In-memory ABI resolver
+#[derive(Clone, Debug, Default)]
+pub struct AbiMap {
+    by_address: HashMap<String, Contract>,
+    by_code_hash: HashMap<UInt256, Contract>,
+}
+
+impl AbiMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: AbiKey, contract: Contract) {
+        match key {
+            AbiKey::Address(address) => {
+                self.by_address.insert(address.to_string(), contract);
+            }
+            AbiKey::CodeHash(hash) => {
+                self.by_code_hash.insert(hash, contract);
+            }
+        }
+    }
+}
+
+impl AbiResolver for AbiMap {
+    fn resolve(&self, key: &AbiKey) -> Option<Contract> {
+        match key {
+            AbiKey::Address(address) => self.by_address.get(&address.to_string()),
+            AbiKey::CodeHash(hash) => self.by_code_hash.get(hash),
+        }
+        .cloned()
+    }
+}
+
+fn resolve(resolver: &dyn AbiResolver, key: &AbiKey) -> Result<Contract> {
+    resolver.resolve(key).ok_or_else(|| {
+        error!(AbiError::InvalidData {
+            msg: format!("No ABI found for {:?}", key)
+        })
+    })
+}
+
+/// Decodes function call with ABI resolved by `key`
+pub fn decode_input(
+    resolver: &dyn AbiResolver,
+    key: &AbiKey,
+    data: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> Result<DecodedMessage> {
+    resolve(resolver, key)?.decode_input(data, internal, allow_partial)
+}
+
+/// Decodes contract answer or event with ABI resolved by `key`
+pub fn decode_output(
+    resolver: &dyn AbiResolver,
+    key: &AbiKey,
+    data: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> Result<DecodedMessage> {
+    resolve(resolver, key)?.decode_output(data, internal, allow_partial)
+}
+
+/// Decodes function call with ABIs resolved by every key and returns all successful results.
+/// Keys without ABI are skipped. See `Contract::decode_input_all`.
+pub fn decode_input_all(
+    resolver: &dyn AbiResolver,
+    keys: &[AbiKey],
+    data: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> Vec<DecodedMessage> {
+    let contracts: Vec<Contract> = keys.iter().filter_map(|key| resolver.resolve(key)).collect();
+    Contract::decode_input_all(&contracts, data, internal, allow_partial)
+}
+
+/// Decodes contract answer or event with ABIs resolved by every key and returns all successful
+/// results. Keys without ABI are skipped. See `Contract::decode_output_all`.
+pub fn decode_output_all(
+    resolver: &dyn AbiResolver,
+    keys: &[AbiKey],
+    data: SliceData,
+    internal: bool,
+    allow_partial: bool,
+) -> Vec<DecodedMessage> {
+    let contracts: Vec<Contract> = keys.iter().filter_map(|key| resolver.resolve(key)).collect();
+    Contract::decode_output_all(&contracts, data, internal, allow_partial)
+}
+
+#[cfg(test)]
+#[path = "tests/test_resolver.rs"]
+mod tests;
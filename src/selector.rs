@@ -0,0 +1,116 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Function IDs computed at compile time.
+//!
+//! `function_id` is a `const fn` equivalent of `Function::calc_function_id`, so IDs of known
+//! functions can be declared as constants and used in `match`-based message routers:
+//!
+//! ```
+//! const TRANSFER: u32 = ever_abi::selector!("transfer(address,uint128)()v2");
+//! # assert_eq!(TRANSFER, ever_abi::Function::calc_function_id("transfer(address,uint128)()v2"));
+//! ```
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Computes function ID from its signature, e.g. `transfer(address,uint128)()v2`, same as
+/// `Function::calc_function_id` but in const context
+pub const fn function_id(signature: &str) -> u32 {
+    sha256(signature.as_bytes())[0]
+}
+
+/// Declares function ID computed at compile time from function signature
+#[macro_export]
+macro_rules! selector {
+    ($signature:expr) => {{
+        const ID: u32 = $crate::selector::function_id($signature);
+        ID
+    }};
+}
+
+// returns SHA256 hash as big-endian words
+const fn sha256(data: &[u8]) -> [u32; 8] {
+    // data, 0x80 byte, zero padding and 64-bit data length fill whole blocks
+    let total = ((data.len() + 8) / 64 + 1) * 64;
+    let mut hash = H0;
+    let mut block = 0;
+    while block < total {
+        let mut w = [0u32; 64];
+        let mut i = 0;
+        while i < 16 {
+            let mut j = 0;
+            while j < 4 {
+                w[i] = (w[i] << 8) | padded_byte(data, total, block + i * 4 + j) as u32;
+                j += 1;
+            }
+            i += 1;
+        }
+        while i < 64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+            i += 1;
+        }
+
+        let mut state = hash;
+        i = 0;
+        while i < 64 {
+            let [a, b, c, d, e, f, g, h] = state;
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let temp1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+            state = [temp1.wrapping_add(temp2), a, b, c, d.wrapping_add(temp1), e, f, g];
+            i += 1;
+        }
+
+        i = 0;
+        while i < 8 {
+            hash[i] = hash[i].wrapping_add(state[i]);
+            i += 1;
+        }
+        block += 64;
+    }
+    hash
+}
+
+const fn padded_byte(data: &[u8], total: usize, index: usize) -> u8 {
+    if index < data.len() {
+        data[index]
+    } else if index == data.len() {
+        0x80
+    } else if index >= total - 8 {
+        ((data.len() as u64 * 8) >> ((total - 1 - index) * 8)) as u8
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_selector.rs"]
+mod tests;
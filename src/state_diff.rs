@@ -0,0 +1,84 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Contract state changes.
+
+use crate::{AbiError, Contract, Token, TokenValue};
+
+use ever_block::{fail, Result, SliceData};
+
+/// Change of storage field or initial data item value
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateChange {
+    /// Field or data item name
+    pub name: String,
+    /// Value in the old state. `None` if data item is absent.
+    pub old: Option<TokenValue>,
+    /// Value in the new state. `None` if data item is absent.
+    pub new: Option<TokenValue>,
+}
+
+impl Contract {
+    /// Decodes both contract states and returns changed values. States are decoded as storage
+    /// fields if ABI declares them and as initial data dictionary otherwise. Fields are
+    /// reported in declaration order and data items in order of their keys.
+    pub fn diff_states(
+        &self,
+        old_data: SliceData,
+        new_data: SliceData,
+    ) -> Result<Vec<StateChange>> {
+        let (old, new, names) = if !self.fields().is_empty() {
+            let names = self.fields().iter().map(|param| param.name.as_str()).collect();
+            (
+                self.decode_storage_fields(old_data, false)?,
+                self.decode_storage_fields(new_data, false)?,
+                names,
+            )
+        } else if !self.data().is_empty() {
+            (
+                self.decode_data(old_data, false)?,
+                self.decode_data(new_data, false)?,
+                self.data_keys().into_values().collect(),
+            )
+        } else {
+            fail!(AbiError::InvalidData {
+                msg: "Contract ABI declares neither storage fields nor data items".to_owned()
+            });
+        };
+
+        let mut changes = vec![];
+        for name in names {
+            let old = find_value(&old, name);
+            let new = find_value(&new, name);
+            if old != new {
+                changes.push(StateChange {
+                    name: name.to_owned(),
+                    old,
+                    new,
+                });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn find_value(tokens: &[Token], name: &str) -> Option<TokenValue> {
+    tokens
+        .iter()
+        .find(|token| token.name == name)
+        .map(|token| token.value.clone())
+}
+
+#[cfg(test)]
+#[path = "tests/test_state_diff.rs"]
+mod tests;
@@ -0,0 +1,141 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Contract ABI statistics.
+
+use crate::{contract::AbiVersion, Contract, Function, Param, ParamType};
+
+use ever_block::{BuilderData, ED25519_SIGNATURE_LENGTH};
+use std::collections::BTreeMap;
+
+/// Contract ABI statistics and complexity estimations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContractStats {
+    /// Number of functions
+    pub functions: usize,
+    /// Number of events
+    pub events: usize,
+    /// Largest sum of maximum in-cell sizes of function input parameters in bits
+    pub max_input_bits: usize,
+    /// Deepest nesting of compound types. Types without inner types have depth 0.
+    pub max_type_depth: usize,
+    /// Largest number of tuple components
+    pub largest_tuple: usize,
+    /// Estimated worst case number of cells in external call body of every function. Cells of
+    /// the body chain and one cell per reference are counted, content of arrays, maps, bytes,
+    /// strings and cells is not.
+    pub body_cells: BTreeMap<String, usize>,
+}
+
+impl Contract {
+    /// Collects ABI statistics
+    pub fn stats(&self) -> ContractStats {
+        let mut stats = ContractStats {
+            functions: self.functions().len(),
+            events: self.events().len(),
+            ..Default::default()
+        };
+        let params = self
+            .header()
+            .iter()
+            .chain(self.functions().values().flat_map(|function| {
+                function.input_params().iter().chain(function.output_params())
+            }))
+            .chain(self.events().values().flat_map(|event| event.inputs.iter()))
+            .chain(self.fields());
+        for param in params {
+            stats.max_type_depth = stats.max_type_depth.max(type_depth(&param.kind));
+            stats.largest_tuple = stats.largest_tuple.max(largest_tuple(&param.kind));
+        }
+        for function in self.functions().values() {
+            let input_bits = function
+                .input_params()
+                .iter()
+                .map(|param| param.kind.max_bit_size(self.version()))
+                .sum();
+            stats.max_input_bits = stats.max_input_bits.max(input_bits);
+            stats
+                .body_cells
+                .insert(function.name.clone(), body_cells(function, self.version()));
+        }
+        stats
+    }
+}
+
+fn type_depth(kind: &ParamType) -> usize {
+    match kind {
+        ParamType::Tuple(params) => {
+            1 + params.iter().map(|param| type_depth(&param.kind)).max().unwrap_or(0)
+        }
+        ParamType::Array(inner)
+        | ParamType::FixedArray(inner, _)
+        | ParamType::Optional(inner)
+        | ParamType::Ref(inner) => 1 + type_depth(inner),
+        ParamType::Map(key, value) => 1 + type_depth(key).max(type_depth(value)),
+        _ => 0,
+    }
+}
+
+fn largest_tuple(kind: &ParamType) -> usize {
+    match kind {
+        ParamType::Tuple(params) => params
+            .iter()
+            .map(|param| largest_tuple(&param.kind))
+            .fold(params.len(), usize::max),
+        ParamType::Array(inner)
+        | ParamType::FixedArray(inner, _)
+        | ParamType::Optional(inner)
+        | ParamType::Ref(inner) => largest_tuple(inner),
+        ParamType::Map(key, value) => largest_tuple(key).max(largest_tuple(value)),
+        _ => 0,
+    }
+}
+
+// greedy estimation of body cells count using maximum sizes of values
+fn body_cells(function: &Function, abi_version: &AbiVersion) -> usize {
+    let signature = if abi_version.major == 1 {
+        (0, 1)
+    } else {
+        (1 + ED25519_SIGNATURE_LENGTH * 8, 0)
+    };
+    let size = |param: &Param| {
+        (
+            param.kind.max_bit_size(abi_version),
+            param.kind.max_refs_count(abi_version),
+        )
+    };
+    let sizes = std::iter::once(signature)
+        .chain(function.header_params().iter().map(size))
+        .chain(std::iter::once((32, 0)))
+        .chain(function.input_params().iter().map(size));
+
+    // one reference of every cell except the last one is used for chaining
+    let refs_capacity = BuilderData::references_capacity() - 1;
+    let (mut cells, mut refs, mut bits, mut cell_refs) = (1, 0, 0, 0);
+    for (value_bits, value_refs) in sizes {
+        if bits + value_bits > BuilderData::bits_capacity() || cell_refs + value_refs > refs_capacity
+        {
+            cells += 1;
+            bits = 0;
+            cell_refs = 0;
+        }
+        bits += value_bits;
+        cell_refs += value_refs;
+        refs += value_refs;
+    }
+    cells + refs
+}
+
+#[cfg(test)]
+#[path = "tests/test_stats.rs"]
+mod tests;
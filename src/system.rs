@@ -0,0 +1,81 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Decoding of system contracts messages.
+//!
+//! Elector and config contracts are not ABI contracts, but their internal messages have the same
+//! layout as internal ABI calls: 32-bit operation code followed by parameters. Embedded ABIs
+//! from `well_known` module describe these messages as functions with operation codes as IDs,
+//! so explorers decode them as usual ABI calls. Tick-tock transactions of system contracts have
+//! no inbound message and are not covered.
+
+use crate::{
+    contract::{Contract, DecodedMessage},
+    error::AbiError,
+    well_known,
+};
+
+use ever_block::{fail, AccountId, MsgAddressInt, Result, SliceData};
+
+/// System contracts with embedded ABIs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SystemContract {
+    Elector,
+    Config,
+}
+
+impl SystemContract {
+    pub const ALL: [SystemContract; 2] = [SystemContract::Elector, SystemContract::Config];
+
+    /// Returns embedded contract ABI
+    pub fn abi(&self) -> &'static Contract {
+        match self {
+            SystemContract::Elector => well_known::elector_contract(),
+            SystemContract::Config => well_known::config_contract(),
+        }
+    }
+
+    /// Returns address of the contract in the standard network configuration
+    pub fn address(&self) -> Result<MsgAddressInt> {
+        let id = match self {
+            SystemContract::Elector => [0x33; 32],
+            SystemContract::Config => [0x55; 32],
+        };
+        MsgAddressInt::with_standart(None, -1, AccountId::from(id))
+    }
+
+    /// Returns system contract with the standard address
+    pub fn from_address(address: &MsgAddressInt) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|contract| contract.address().ok().as_ref() == Some(address))
+    }
+}
+
+/// Decodes internal message body sent to or by system contract. Body is checked against elector
+/// messages first and then against config contract messages.
+pub fn decode_system_message(body: SliceData) -> Result<(SystemContract, DecodedMessage)> {
+    for contract in SystemContract::ALL {
+        if let Ok(decoded) = contract.abi().decode_input(body.clone(), true, false) {
+            return Ok((contract, decoded));
+        }
+    }
+    fail!(AbiError::InvalidFunctionId {
+        id: body.clone().get_next_u32()?
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/test_system.rs"]
+mod tests;
@@ -0,0 +1,56 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use ever_block::{AccountId, AnycastInfo, MsgAddress, SliceData};
+
+use crate::address::{addresses_eq, from_address_int, normalize_address, to_address_int};
+use crate::TokenValue;
+
+#[test]
+fn test_normalize_address() {
+    let anycast =
+        AnycastInfo::with_rewrite_pfx(SliceData::new(vec![0x77, 0x78, 0x79, 0x80])).unwrap();
+    let plain = MsgAddress::with_standart(None, -1, AccountId::from([0x11; 32])).unwrap();
+    let with_anycast =
+        MsgAddress::with_standart(Some(anycast.clone()), -1, AccountId::from([0x11; 32])).unwrap();
+    let variant = MsgAddress::with_variant(
+        Some(anycast),
+        -1,
+        SliceData::from_raw(vec![0x11; 32], 256),
+    )
+    .unwrap();
+
+    assert_eq!(normalize_address(&with_anycast).unwrap(), plain);
+    assert_eq!(normalize_address(&variant).unwrap(), plain);
+    assert!(addresses_eq(&with_anycast, &variant).unwrap());
+    assert_ne!(with_anycast, plain);
+
+    assert_eq!(
+        TokenValue::Address(with_anycast).normalize_address().unwrap(),
+        TokenValue::Address(plain.clone())
+    );
+    assert!(TokenValue::Bool(true).normalize_address().is_err());
+
+    assert_eq!(normalize_address(&MsgAddress::AddrNone).unwrap(), MsgAddress::AddrNone);
+}
+
+#[test]
+fn test_address_int_conversion() {
+    let address = MsgAddress::with_standart(None, 0, AccountId::from([0x22; 32])).unwrap();
+    let address_int = to_address_int(&address).unwrap();
+    assert_eq!(address_int.to_string(), address.to_string());
+    assert_eq!(from_address_int(&address_int), address);
+
+    assert!(to_address_int(&MsgAddress::AddrNone).is_err());
+    assert!(to_address_int(&MsgAddress::with_extern(SliceData::new(vec![0x55, 0x80])).unwrap()).is_err());
+}
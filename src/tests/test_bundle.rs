@@ -0,0 +1,92 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::bundle::AbiBundle;
+use crate::resolver::{AbiKey, AbiResolver};
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::{SliceData, UInt256};
+use std::collections::HashMap;
+
+const WALLET_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.3",
+    "functions": [{
+        "name": "send",
+        "inputs": [{"name": "value", "type": "uint128"}],
+        "outputs": [{"name": "ok", "type": "bool"}]
+    }],
+    "events": [{
+        "name": "Sent",
+        "inputs": [{"name": "value", "type": "uint128"}]
+    }]
+}"#;
+
+#[test]
+fn test_bundle_load_save() {
+    let code_hash = UInt256::from([1; 32]);
+    let mut bundle = AbiBundle::new();
+    assert!(bundle.is_empty());
+    bundle.insert(code_hash.clone(), WALLET_ABI).unwrap();
+    assert!(bundle.insert(UInt256::default(), "{}").is_err());
+    assert_eq!(bundle.len(), 1);
+
+    let saved = bundle.save().unwrap();
+    let loaded = AbiBundle::load(&saved).unwrap();
+    assert_eq!(loaded.save().unwrap(), saved);
+    assert_eq!(
+        loaded.get(&code_hash),
+        Some(&Contract::load(WALLET_ABI.as_bytes()).unwrap())
+    );
+    assert_eq!(
+        loaded.resolve(&AbiKey::CodeHash(code_hash)),
+        Some(Contract::load(WALLET_ABI.as_bytes()).unwrap())
+    );
+
+    assert!(AbiBundle::load(r#"{"version": 2, "abis": {}}"#).is_err());
+    assert!(AbiBundle::load(r#"{"version": 1, "abis": {"00": {}}}"#).is_err());
+    assert!(AbiBundle::load(r#"{"version": 1, "abis": {}}"#).unwrap().is_empty());
+}
+
+#[test]
+fn test_decode_for_code_hash() {
+    let code_hash = UInt256::from([1; 32]);
+    let mut bundle = AbiBundle::new();
+    bundle.insert(code_hash.clone(), WALLET_ABI).unwrap();
+
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let input = [Token::new("value", TokenValue::Uint(Uint::new(100, 128)))];
+    let function = contract.function("send").unwrap();
+
+    let call = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let decoded = bundle
+        .decode_for_code_hash(&code_hash, SliceData::load_builder(call).unwrap())
+        .unwrap();
+    assert_eq!(decoded.function_name, "send");
+    assert_eq!(decoded.tokens, input);
+
+    // event body has the same layout as function answer
+    let event_id = contract.event("Sent").unwrap().get_id();
+    let event = function.encode_internal_output(event_id, &input).unwrap();
+    let decoded = bundle
+        .decode_for_code_hash(&code_hash, SliceData::load_builder(event.clone()).unwrap())
+        .unwrap();
+    assert_eq!(decoded.function_name, "Sent");
+    assert!(decoded.event().is_some());
+
+    assert!(bundle
+        .decode_for_code_hash(&UInt256::default(), SliceData::load_builder(event).unwrap())
+        .is_err());
+}
@@ -0,0 +1,101 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::contract::{ABI_VERSION_2_0, ABI_VERSION_2_3, ABI_VERSION_2_4};
+use crate::Contract;
+
+const ABI: &str = r#"{
+    "version": "2.4",
+    "header": ["pubkey", "time", {"name": "flags", "type": "uint8"}],
+    "functions": [
+        {"name": "constructor", "inputs": [], "outputs": []},
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name": "dest", "type": "address"},
+                {"name": "info", "type": "tuple[]", "components": [{"name": "a", "type": "uint32"}]}
+            ],
+            "outputs": [{"name": "ok", "type": "bool"}]
+        },
+        {"name": "upgrade", "id": "0x11", "inputs": [], "outputs": []}
+    ],
+    "events": [{"name": "Done", "inputs": [{"name": "value", "type": "map(uint32,int8)"}]}],
+    "fields": [
+        {"name": "_pubkey", "type": "uint256", "init": true},
+        {"name": "_timestamp", "type": "uint64"}
+    ]
+}"#;
+
+const EXPECTED: &str = r#"{
+    "ABI version": 2,
+    "version": "2.4",
+    "header": ["pubkey", "time", {"name":"flags","type":"uint8"}],
+    "functions": [
+        {
+            "name": "constructor",
+            "inputs": [
+            ],
+            "outputs": [
+            ]
+        },
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name":"dest","type":"address"},
+                {"components":[{"name":"a","type":"uint32"}],"name":"info","type":"tuple[]"}
+            ],
+            "outputs": [
+                {"name":"ok","type":"bool"}
+            ]
+        },
+        {
+            "name": "upgrade",
+            "id": "0x00000011",
+            "inputs": [
+            ],
+            "outputs": [
+            ]
+        }
+    ],
+    "events": [
+        {
+            "name": "Done",
+            "inputs": [
+                {"name":"value","type":"map(uint32,int8)"}
+            ],
+            "outputs": [
+            ]
+        }
+    ],
+    "fields": [
+        {"init":true,"name":"_pubkey","type":"uint256"},
+        {"init":false,"name":"_timestamp","type":"uint64"}
+    ]
+}
+"#;
+
+#[test]
+fn test_compiler_json() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let json = contract.to_compiler_json(&ABI_VERSION_2_4).unwrap();
+    assert_eq!(json, EXPECTED.replace("    ", "\t"));
+    assert_eq!(Contract::load(json.as_bytes()).unwrap(), contract);
+
+    let json = contract.to_compiler_json(&ABI_VERSION_2_3).unwrap();
+    assert!(json.contains("\t\"data\": [\n\t],\n"));
+    assert!(json.contains("{\"name\":\"_timestamp\",\"type\":\"uint64\"}"));
+
+    // storage fields are not supported in ABI 2.0
+    assert!(contract.to_compiler_json(&ABI_VERSION_2_0).is_err());
+    assert!(contract.to_compiler_json(&1.into()).is_err());
+}
@@ -0,0 +1,82 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::compression::{compress_body, decompress_body, is_compressed_body, BodyCompressor};
+use crate::contract::ABI_VERSION_2_4;
+use crate::{AbiError, Function, Param, ParamType, Token, TokenValue};
+
+use ever_block::{fail, Result, SliceData};
+use std::collections::HashMap;
+
+// simple run-length encoding: pairs of (count, byte)
+struct Rle;
+
+impl BodyCompressor for Rle {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![];
+        for byte in data {
+            match result.len() {
+                len if len >= 2 && result[len - 1] == *byte && result[len - 2] < 255 => {
+                    result[len - 2] += 1
+                }
+                _ => result.extend_from_slice(&[1, *byte]),
+            }
+        }
+        Ok(result)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if data.len() % 2 != 0 {
+            fail!(AbiError::InvalidData {
+                msg: "odd RLE data length".to_owned()
+            });
+        }
+        Ok(data
+            .chunks(2)
+            .flat_map(|pair| std::iter::repeat(pair[1]).take(pair[0] as usize))
+            .collect())
+    }
+}
+
+#[test]
+fn test_compressed_body() {
+    let function = Function {
+        abi_version: ABI_VERSION_2_4,
+        name: "send".to_owned(),
+        header: vec![],
+        inputs: vec![Param::new("text", ParamType::String)],
+        outputs: vec![],
+        input_id: 0x01234567,
+        output_id: 0x01234567,
+        header_defaults: Default::default(),
+        signed: true,
+    };
+    let input = [Token::new("text", TokenValue::String("a".repeat(2000)))];
+    let body = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap()
+        .into_cell()
+        .unwrap();
+
+    let compressed = compress_body(&body, &Rle).unwrap();
+    let compressed = SliceData::load_builder(compressed).unwrap();
+    assert!(is_compressed_body(&compressed));
+    assert!(compressed.cell().repr_hash() != body.repr_hash());
+
+    let restored = decompress_body(compressed, &Rle).unwrap();
+    assert_eq!(restored.cell().repr_hash(), body.repr_hash());
+    assert_eq!(function.decode_input(restored.clone(), true, false).unwrap(), input);
+
+    // not compressed body is returned unchanged
+    assert_eq!(decompress_body(restored.clone(), &Rle).unwrap(), restored);
+}
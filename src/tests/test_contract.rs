@@ -11,10 +11,11 @@
 * limitations under the License.
 */
 
-use ever_block::{Deserializable, StateInit};
-use ever_block::{read_single_root_boc, Result, SliceData};
+use ever_block::{Deserializable, Serializable, StateInit};
+use ever_block::{ed25519_generate_private_key, read_single_root_boc, Result, SliceData};
+use std::collections::HashMap;
 
-use crate::{Contract, PublicKeyData};
+use crate::{Contract, PublicKeyData, Token, TokenValue, Uint};
 
 const DEPOOL_TVC: &[u8] = include_bytes!("data/DePool.tvc");
 const PUB_KEY: PublicKeyData = [
@@ -39,3 +40,255 @@ fn test_pubkey() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_encode_constructor_data_map() -> Result<()> {
+    let abi = r#"{
+        "ABI version": 2,
+        "version": "2.3",
+        "header": ["time", "expire"],
+        "functions": [{"name": "constructor", "inputs": [{"name": "a", "type": "uint8"}], "outputs": []}],
+        "data": [{"key": 1, "name": "owner", "type": "uint32"}]
+    }"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    let state_init = StateInit::construct_from_cell(read_single_root_boc(DEPOOL_TVC)?)?;
+    let sign_key = ed25519_generate_private_key()?;
+    let input = [Token::new("a", TokenValue::Uint(Uint::new(3, 8)))];
+    let init = HashMap::from([("owner".to_owned(), TokenValue::Uint(Uint::new(5, 32)))]);
+
+    let deploy =
+        contract.encode_constructor(&state_init, 0, &HashMap::new(), &input, init, Some(&sign_key))?;
+
+    let data = SliceData::load_cell(deploy.state_init.data.clone().unwrap())?;
+    assert_eq!(Contract::get_pubkey(&data)?, Some(sign_key.verifying_key()));
+    assert_eq!(contract.pubkey_from_data(&data)?, Some(sign_key.verifying_key()));
+    assert_eq!(
+        contract.decode_data(data, false)?,
+        vec![Token::new("owner", TokenValue::Uint(Uint::new(5, 32)))]
+    );
+    assert_eq!(deploy.state_init.code, state_init.code);
+    let account_id: ever_block::AccountId = deploy.state_init.serialize()?.repr_hash().into();
+    assert_eq!(deploy.address.address(), account_id);
+
+    let decoded = contract.decode_input(SliceData::load_builder(deploy.body)?, false, false)?;
+    assert_eq!(decoded.function_name, "constructor");
+    assert_eq!(decoded.tokens, input);
+
+    Ok(())
+}
+
+#[test]
+fn test_encode_constructor_init_fields() -> Result<()> {
+    let abi = r#"{
+        "version": "2.4",
+        "header": ["pubkey", "time", "expire"],
+        "functions": [{"name": "constructor", "inputs": [], "outputs": []}],
+        "fields": [
+            {"name": "_pubkey", "type": "uint256", "init": true},
+            {"name": "_timestamp", "type": "uint64"},
+            {"name": "owner", "type": "uint32", "init": true}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    let sign_key = ed25519_generate_private_key()?;
+    let init = HashMap::from([("owner".to_owned(), TokenValue::Uint(Uint::new(5, 32)))]);
+
+    let deploy = contract.encode_constructor(
+        &StateInit::default(),
+        0,
+        &HashMap::new(),
+        &[],
+        init.clone(),
+        Some(&sign_key),
+    )?;
+    let data = SliceData::load_cell(deploy.state_init.data.clone().unwrap())?;
+    let fields = contract.decode_storage_fields(data, false)?;
+    assert_eq!(
+        fields[0].value,
+        TokenValue::Uint(Uint {
+            number: num_bigint::BigUint::from_bytes_be(&sign_key.verifying_key()),
+            size: 256
+        })
+    );
+    assert_eq!(fields[2].value, TokenValue::Uint(Uint::new(5, 32)));
+    let data = SliceData::load_cell(deploy.state_init.data.unwrap())?;
+    assert_eq!(contract.pubkey_from_data(&data)?, Some(sign_key.verifying_key()));
+
+    // `_pubkey` should be supplied if there is no signing key
+    assert!(contract
+        .encode_constructor(&StateInit::default(), 0, &HashMap::new(), &[], init, None)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_pubkey_from_fields() -> Result<()> {
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name": "__pubkey", "type": "fixedbytes32", "init": true},
+            {"name": "flag", "type": "bool"}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    assert_eq!(contract.pubkey_field().unwrap().name, "__pubkey");
+
+    let init = HashMap::from([("__pubkey".to_owned(), TokenValue::FixedBytes(PUB_KEY.to_vec()))]);
+    let data = SliceData::load_builder(contract.encode_storage_fields(init)?)?;
+    assert_eq!(contract.pubkey_from_data(&data)?, Some(PUB_KEY));
+
+    let abi = r#"{"version": "2.4", "functions": [], "fields": [{"name": "a", "type": "bool"}]}"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    assert_eq!(contract.pubkey_from_data(&data)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_system_fields() -> Result<()> {
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [],
+        "fields": [
+            {"name": "_pubkey", "type": "uint256", "init": true},
+            {"name": "_timestamp", "type": "uint64"},
+            {"name": "flag", "type": "bool"}
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    let system = contract.system_fields();
+    assert_eq!(system.pubkey.unwrap().name, "_pubkey");
+    assert_eq!(system.timestamp.unwrap().name, "_timestamp");
+
+    let init = HashMap::from([("_pubkey".to_owned(), TokenValue::Uint(Uint::new(0, 256)))]);
+    let data = SliceData::load_builder(contract.encode_storage_fields(init)?)?;
+    assert_eq!(contract.pubkey_from_data(&data)?, Some(PublicKeyData::default()));
+
+    let data = contract.set_storage_pubkey(data, &PUB_KEY)?;
+    let data = contract.set_storage_timestamp(data, 1700000000)?;
+    assert_eq!(contract.pubkey_from_data(&data)?, Some(PUB_KEY));
+    let fields = contract.decode_storage_fields(data, false)?;
+    assert_eq!(fields[1].value, TokenValue::Uint(Uint::new(1700000000, 64)));
+    assert_eq!(fields[2].value, TokenValue::Bool(false));
+
+    let abi = r#"{"version": "2.4", "functions": [], "fields": [{"name": "a", "type": "bool"}]}"#;
+    let contract = Contract::load(abi.as_bytes())?;
+    assert_eq!(contract.system_fields(), Default::default());
+    let data = SliceData::load_builder(contract.encode_storage_fields(HashMap::new())?)?;
+    assert!(contract.set_storage_pubkey(data.clone(), &PUB_KEY).is_err());
+    assert!(contract.set_storage_timestamp(data, 0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_between_threads() -> Result<()> {
+    let abi = r#"{
+        "version": "2.4",
+        "functions": [{"name": "transfer", "inputs": [{"name": "value", "type": "uint32"}]}]
+    }"#;
+    let contract = std::sync::Arc::new(Contract::load(abi.as_bytes())?);
+
+    let handles: Vec<_> = (0..4u64)
+        .map(|value| {
+            let contract = contract.clone();
+            std::thread::spawn(move || -> Result<Vec<Token>> {
+                let input = [Token::new("value", TokenValue::Uint(Uint::new(value.into(), 32)))];
+                let body = contract.function("transfer")?.encode_input(
+                    &HashMap::new(),
+                    &input,
+                    true,
+                    None,
+                    None,
+                )?;
+                Ok(contract.decode_input(SliceData::load_builder(body)?, true, false)?.tokens)
+            })
+        })
+        .collect();
+    for (value, handle) in handles.into_iter().enumerate() {
+        let tokens = handle.join().unwrap()?;
+        assert_eq!(tokens[0].value, TokenValue::Uint(Uint::new(value as u128, 32)));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_abi_version_parse() {
+    use crate::contract::{AbiVersion, ABI_VERSION_2_0, ABI_VERSION_2_2, ABI_VERSION_2_4};
+    use std::collections::BTreeSet;
+
+    assert_eq!(AbiVersion::parse("2.4").unwrap(), ABI_VERSION_2_4);
+    assert_eq!(AbiVersion::parse("2").unwrap(), ABI_VERSION_2_0);
+    assert_eq!("2.2.0".parse::<AbiVersion>().unwrap(), ABI_VERSION_2_2);
+    for invalid in ["", "2.", "two", "2.4.0.1", "2.4.x", "256.0"] {
+        assert!(AbiVersion::parse(invalid).is_err(), "{}", invalid);
+    }
+
+    let versions: BTreeSet<_> = [ABI_VERSION_2_4, AbiVersion::from(1), ABI_VERSION_2_2]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        versions.into_iter().map(|version| version.to_string()).collect::<Vec<_>>(),
+        vec!["1.0", "2.2", "2.4"]
+    );
+    assert_eq!(ABI_VERSION_2_2.max(ABI_VERSION_2_4), ABI_VERSION_2_4);
+
+    assert_eq!(serde_json::to_string(&ABI_VERSION_2_4).unwrap(), r#""2.4""#);
+    let versions: Vec<AbiVersion> = serde_json::from_str(r#"["2.2", 2, "2.4.1"]"#).unwrap();
+    assert_eq!(versions, vec![ABI_VERSION_2_2, ABI_VERSION_2_0, ABI_VERSION_2_4]);
+    assert!(serde_json::from_str::<AbiVersion>(r#""2.x""#).is_err());
+}
+
+#[test]
+fn test_version_gates() {
+    use crate::contract::{
+        ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_1, ABI_VERSION_2_2, ABI_VERSION_2_3,
+        ABI_VERSION_2_4,
+    };
+    use crate::ParamType;
+
+    let versions = [
+        ABI_VERSION_1_0,
+        ABI_VERSION_2_0,
+        ABI_VERSION_2_1,
+        ABI_VERSION_2_2,
+        ABI_VERSION_2_3,
+        ABI_VERSION_2_4,
+    ];
+    for (i, version) in versions.iter().enumerate() {
+        assert!(version.is_supported());
+        for (j, other) in versions.iter().enumerate() {
+            assert_eq!(version.at_least(other.major, other.minor), i >= j, "{} {}", version, other);
+            assert_eq!(version.cmp(other), i.cmp(&j));
+        }
+        assert!(!version.at_least(3, 0));
+        assert!(version.at_least(0, 255));
+
+        let supported = |param_type: ParamType| param_type.is_supported(version);
+        assert_eq!(supported(ParamType::Time), i >= 1, "{}", version);
+        assert_eq!(supported(ParamType::PublicKey), i >= 1, "{}", version);
+        assert_eq!(supported(ParamType::String), i >= 2, "{}", version);
+        assert_eq!(supported(ParamType::VarUint(16)), i >= 2, "{}", version);
+        assert_eq!(supported(ParamType::Slice), i >= 5, "{}", version);
+        assert_eq!(supported(ParamType::Bool.into_ref()), i >= 5, "{}", version);
+
+        let fixed_bytes = ParamType::FixedBytes(4);
+        let inline = (32, 0);
+        let in_ref = (0, 1);
+        assert_eq!(
+            (fixed_bytes.max_bit_size(version), fixed_bytes.max_refs_count(version)),
+            if i >= 5 { inline } else { in_ref },
+            "{}",
+            version
+        );
+
+        let abi = format!(
+            r#"{{"version": "{}", "functions": [], "fields": [{{"name": "a", "type": "bool"}}]}}"#,
+            version
+        );
+        assert_eq!(Contract::load(abi.as_bytes()).is_ok(), i >= 2, "{}", version);
+    }
+    assert!(!crate::contract::AbiVersion::from_parts(2, 5).is_supported());
+}
@@ -0,0 +1,73 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::coverage::{CoverageContract, CoverageReport};
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::SliceData;
+use std::collections::HashMap;
+
+const ABI: &str = r#"{
+    "version": "2.3",
+    "functions": [{
+        "name": "transfer",
+        "inputs": [{"name": "value", "type": "uint128"}],
+        "outputs": []
+    }, {
+        "name": "setOwners",
+        "inputs": [{"name": "owners", "type": "address[]"}],
+        "outputs": [{"name": "ok", "type": "bool"}]
+    }],
+    "events": [{
+        "name": "Transferred",
+        "inputs": [{"name": "value", "type": "uint128"}]
+    }]
+}"#;
+
+#[test]
+fn test_coverage() {
+    let contract = CoverageContract::new(Contract::load(ABI.as_bytes()).unwrap());
+    assert_eq!(
+        contract.report(),
+        CoverageReport {
+            untested_functions: vec!["setOwners".to_owned(), "transfer".to_owned()],
+            untested_events: vec!["Transferred".to_owned()],
+            untested_types: vec!["address[]".to_owned(), "bool".to_owned(), "uint128".to_owned()],
+        }
+    );
+
+    let input = [Token::new("value", TokenValue::Uint(Uint::new(5, 128)))];
+    let body = contract
+        .encode_input("transfer", &HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let decoded = contract
+        .decode_input(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap();
+    assert_eq!(decoded.tokens, input);
+
+    let event = contract.contract().event("Transferred").unwrap();
+    let body = event.encode(&input).unwrap();
+    contract
+        .decode_output(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap();
+
+    let report = contract.report();
+    assert_eq!(report.untested_functions, vec!["setOwners".to_owned()]);
+    assert!(report.untested_events.is_empty());
+    assert_eq!(report.untested_types, vec!["address[]".to_owned(), "bool".to_owned()]);
+    assert!(!report.is_complete());
+
+    contract.record_function("setOwners");
+    assert!(contract.report().is_complete());
+    assert!(contract.report().untested_types.is_empty());
+}
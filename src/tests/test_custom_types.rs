@@ -0,0 +1,135 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::custom_types::{CustomType, CustomTypeRegistry};
+use crate::{Int, ParamType, TokenValue};
+
+use ever_block::{fail, Result};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const DECIMALS: u32 = 3;
+
+// fixed point number with 3 decimals represented in JSON as a string
+struct Decimal;
+
+impl CustomType for Decimal {
+    fn base_type(&self) -> ParamType {
+        ParamType::Int(64)
+    }
+
+    fn tokenize(&self, value: &Value) -> Result<Value> {
+        let value: f64 = value.as_str().unwrap_or_default().parse()?;
+        Ok(json!((value * 10f64.powi(DECIMALS as i32)).round() as i64))
+    }
+
+    fn detokenize(&self, value: Value) -> Result<Value> {
+        let value: i64 = value.as_str().unwrap_or_default().parse()?;
+        Ok(json!(format!("{}", value as f64 / 10f64.powi(DECIMALS as i32))))
+    }
+
+    fn serialize(&self, value: TokenValue) -> Result<TokenValue> {
+        match &value {
+            TokenValue::Int(Int { number, .. }) if number.sign() == num_bigint::Sign::Minus => {
+                fail!(crate::error::AbiError::InvalidData {
+                    msg: "negative decimal".to_owned()
+                })
+            }
+            _ => Ok(value),
+        }
+    }
+}
+
+const DECIMAL_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.3",
+    "header": ["time"],
+    "functions": [{
+        "name": "setPrice",
+        "inputs": [
+            {"name": "price", "type": "decimal"},
+            {"name": "limits", "type": "tuple", "components": [
+                {"name": "min", "type": "decimal"},
+                {"name": "count", "type": "uint8"}
+            ]}
+        ],
+        "outputs": [{"name": "total", "type": "decimal"}]
+    }],
+    "events": [{
+        "name": "PriceChanged",
+        "inputs": [{"name": "price", "type": "decimal"}]
+    }],
+    "fields": [{"name": "price", "type": "decimal"}]
+}"#;
+
+fn registry() -> CustomTypeRegistry {
+    let mut registry = CustomTypeRegistry::new();
+    registry.register("decimal", Arc::new(Decimal)).unwrap();
+    registry
+}
+
+#[test]
+fn test_register_custom_type() {
+    let mut registry = registry();
+    assert!(registry.get("decimal").is_some());
+    assert!(registry.get("money").is_none());
+
+    assert!(registry.register("decimal", Arc::new(Decimal)).is_err());
+    assert!(registry.register("int64", Arc::new(Decimal)).is_err());
+
+    struct Pair;
+    impl CustomType for Pair {
+        fn base_type(&self) -> ParamType {
+            ParamType::Tuple(vec![])
+        }
+    }
+    assert!(registry.register("pair", Arc::new(Pair)).is_err());
+}
+
+#[test]
+fn test_custom_type_roundtrip() {
+    let contract = registry().load_contract(DECIMAL_ABI).unwrap();
+    let function = contract.contract().function("setPrice").unwrap();
+    assert_eq!(function.input_params()[0].kind, ParamType::Int(64));
+
+    let input = json!({
+        "price": "1.5",
+        "limits": {"min": "0.25", "count": "3"}
+    });
+    let tokens = contract.tokenize_input("setPrice", &input).unwrap();
+    assert_eq!(tokens[0].value, TokenValue::Int(Int::new(1500, 64)));
+    match &tokens[1].value {
+        TokenValue::Tuple(tokens) => {
+            assert_eq!(tokens[0].value, TokenValue::Int(Int::new(250, 64)))
+        }
+        _ => panic!("tuple expected"),
+    }
+    assert_eq!(contract.detokenize_input("setPrice", &tokens).unwrap(), input);
+
+    let output = json!({"total": "2.75"});
+    let tokens = contract.tokenize_output("setPrice", &output).unwrap();
+    assert_eq!(contract.detokenize_output("setPrice", &tokens).unwrap(), output);
+
+    let fields = json!({"price": "0.001"});
+    let tokens = contract.tokenize_fields(&fields).unwrap();
+    assert_eq!(contract.detokenize_fields(&tokens).unwrap(), fields);
+    assert_eq!(contract.detokenize_event("PriceChanged", &tokens).unwrap(), fields);
+
+    assert!(contract.tokenize_output("setPrice", &json!({"total": "-1"})).is_err());
+    assert!(contract.tokenize_input("unknown", &input).is_err());
+}
+
+#[test]
+fn test_unknown_custom_type() {
+    assert!(CustomTypeRegistry::new().load_contract(DECIMAL_ABI).is_err());
+}
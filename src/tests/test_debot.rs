@@ -0,0 +1,71 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::debot::{interface_id, DebotInterface, DEBOT_WC};
+use crate::{Token, TokenValue, Uint};
+
+use ever_block::{sha256_digest, SliceData};
+
+const ECHO_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.2",
+    "header": ["time"],
+    "functions": [{
+        "name": "echo",
+        "id": "0x3d5f5b2b",
+        "inputs": [
+            {"name": "answerId", "type": "uint32"},
+            {"name": "request", "type": "bytes"}
+        ],
+        "outputs": [{"name": "response", "type": "bytes"}]
+    }],
+    "events": []
+}"#;
+
+#[test]
+fn test_interface_id() {
+    let id = interface_id("Echo");
+    assert_eq!(id, hex::encode(sha256_digest(b"Echo")));
+    assert_ne!(id, interface_id("Terminal"));
+
+    let interface = DebotInterface::load("Echo", ECHO_ABI).unwrap();
+    assert_eq!(interface.id(), id);
+    let address = interface.address().unwrap();
+    assert_eq!(address.workchain_id(), DEBOT_WC as i32);
+    assert_eq!(address.address().as_hex_string(), id);
+    assert!(interface.is_my_address(&address));
+
+    let fixed = DebotInterface::with_id("Echo", &"AB".repeat(32), interface.abi().clone());
+    assert_eq!(fixed.id(), "ab".repeat(32));
+    assert!(!fixed.is_my_address(&address));
+    assert!(DebotInterface::with_id("Echo", "abcd", interface.abi().clone()).address().is_err());
+}
+
+#[test]
+fn test_interface_call() {
+    let interface = DebotInterface::load("Echo", ECHO_ABI).unwrap();
+    let input = [
+        Token::new("answerId", TokenValue::Uint(Uint::new(0x11223344, 32))),
+        Token::new("request", TokenValue::Bytes(b"hello".to_vec())),
+    ];
+    let body = interface.encode_call("echo", &input).unwrap();
+    let mut slice = SliceData::load_builder(body).unwrap();
+    assert_eq!(slice.clone().get_next_u32().unwrap(), 0x3d5f5b2b);
+
+    let decoded = interface.decode_call(slice.clone()).unwrap();
+    assert_eq!(decoded.function_name, "echo");
+    assert_eq!(decoded.tokens, input);
+
+    slice.move_by(32).unwrap();
+    assert!(interface.decode_call(slice).is_err());
+}
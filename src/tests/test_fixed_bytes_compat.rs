@@ -0,0 +1,92 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::SliceData;
+
+const ABI: &str = r#"{
+    "version": "2.4",
+    "functions": [{
+        "name": "transfer",
+        "id": "0x01020304",
+        "inputs": [
+            {"name": "hash", "type": "fixedbytes4"},
+            {"name": "items", "type": "tuple[]", "components": [
+                {"name": "key", "type": "fixedbytes2"}
+            ]},
+            {"name": "value", "type": "uint32"}
+        ],
+        "outputs": []
+    }]
+}"#;
+
+fn encode(abi: &str, tokens: &[Token]) -> SliceData {
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    let body = contract
+        .function("transfer")
+        .unwrap()
+        .encode_input(&Default::default(), tokens, true, None, None)
+        .unwrap();
+    SliceData::load_builder(body).unwrap()
+}
+
+fn tokens(hash: TokenValue, key: TokenValue) -> Vec<Token> {
+    let item = TokenValue::Tuple(vec![Token::new("key", key.clone())]);
+    vec![
+        Token::new("hash", hash),
+        Token::new("items", TokenValue::Array(item.get_param_type(), vec![item])),
+        Token::new("value", TokenValue::Uint(Uint::new(17, 32))),
+    ]
+}
+
+#[test]
+fn test_fixed_bytes_compat() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let function = contract.function("transfer").unwrap();
+    let expected = tokens(
+        TokenValue::FixedBytes(vec![1, 2, 3, 4]),
+        TokenValue::FixedBytes(vec![5, 6]),
+    );
+
+    let body = encode(ABI, &expected);
+    let decoded = function.decode_input_fixed_bytes_compat(body, true, false).unwrap();
+    assert_eq!(decoded.tokens, expected);
+    assert!(!decoded.legacy_fixed_bytes);
+
+    // older encoders stored `fixedbytes<N>` the same way as `bytes`
+    let legacy_abi = ABI.replace("fixedbytes4", "bytes").replace("fixedbytes2", "bytes");
+    let legacy = tokens(TokenValue::Bytes(vec![1, 2, 3, 4]), TokenValue::Bytes(vec![5, 6]));
+    let body = encode(&legacy_abi, &legacy);
+    assert!(function.decode_input(body.clone(), true, false).is_err());
+    let decoded = function.decode_input_fixed_bytes_compat(body.clone(), true, false).unwrap();
+    assert_eq!(decoded.tokens, expected);
+    assert!(decoded.legacy_fixed_bytes);
+
+    let mut params = body.clone();
+    params.move_by(32).unwrap();
+    let decoded = TokenValue::decode_params_fixed_bytes_compat(
+        function.input_params(),
+        params,
+        &crate::contract::ABI_VERSION_2_4,
+        false,
+    )
+    .unwrap();
+    assert_eq!(decoded.tokens, expected);
+    assert!(decoded.legacy_fixed_bytes);
+
+    // legacy layout with wrong size is still rejected
+    let wrong = tokens(TokenValue::Bytes(vec![1, 2, 3]), TokenValue::Bytes(vec![5, 6]));
+    let body = encode(&legacy_abi, &wrong);
+    assert!(function.decode_input_fixed_bytes_compat(body, true, false).is_err());
+}
@@ -0,0 +1,95 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::error::AbiError;
+use crate::golden::run_corpus;
+use crate::json_abi::{encode_function_call_ext, EncodeOptions};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use ever_block::write_boc;
+
+const ABI: &str = r#"{
+    "version": "2.3",
+    "header": ["time", "expire"],
+    "functions": [{
+        "name": "transfer",
+        "inputs": [
+            {"name": "value", "type": "uint128"},
+            {"name": "comment", "type": "string"}
+        ],
+        "outputs": []
+    }]
+}"#;
+
+fn write_case(corpus: &Path, name: &str, params: &str, options: &EncodeOptions) {
+    let dir = corpus.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let input = serde_json::from_str::<serde_json::Value>(params).unwrap()["input"].to_string();
+    let body = encode_function_call_ext(ABI, "transfer", &input, options).unwrap();
+    fs::write(dir.join("abi.json"), ABI).unwrap();
+    fs::write(dir.join("params.json"), params).unwrap();
+    fs::write(dir.join("expected.boc"), write_boc(&body.into_cell().unwrap()).unwrap()).unwrap();
+}
+
+fn make_corpus(name: &str) -> PathBuf {
+    let corpus = std::env::temp_dir().join(format!("ever_abi_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&corpus);
+    fs::create_dir_all(&corpus).unwrap();
+    fs::write(corpus.join("corpus.json"), r#"{"version": 1}"#).unwrap();
+
+    write_case(
+        &corpus,
+        "internal",
+        r#"{"function": "transfer", "internal": true, "input": {"value": "5", "comment": "hi"}}"#,
+        &EncodeOptions::new().internal(true),
+    );
+    write_case(
+        &corpus,
+        "external",
+        r#"{
+            "function": "transfer",
+            "header": {"expire": 100},
+            "time": 1000,
+            "input": {"value": "7", "comment": ""}
+        }"#,
+        &EncodeOptions::new().with_header(r#"{"expire": 100}"#).fixed_time(1000),
+    );
+    corpus
+}
+
+#[test]
+fn test_run_corpus() {
+    let corpus = make_corpus("golden");
+    assert_eq!(run_corpus(&corpus).unwrap(), 2);
+
+    let params = corpus.join("internal").join("params.json");
+    fs::write(
+        &params,
+        r#"{"function": "transfer", "internal": true, "input": {"value": "6", "comment": "hi"}}"#,
+    )
+    .unwrap();
+    let err = run_corpus(&corpus).unwrap_err();
+    match err.downcast_ref::<AbiError>() {
+        Some(AbiError::GoldenCasesFailed { failures }) => {
+            assert_eq!(failures.len(), 1);
+            assert!(failures[0].starts_with("internal: "));
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+
+    fs::write(corpus.join("corpus.json"), r#"{"version": 2}"#).unwrap();
+    assert!(run_corpus(&corpus).is_err());
+
+    fs::remove_dir_all(&corpus).unwrap();
+}
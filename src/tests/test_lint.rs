@@ -0,0 +1,71 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::lint::AbiWarning;
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::{BuilderData, IBitstring, SliceData};
+
+const COLLISION_ABI: &str = r#"{
+    "version": "2.3",
+    "functions": [{
+        "name": "answer",
+        "id": "0x00000011",
+        "inputs": [],
+        "outputs": [{"name": "value", "type": "uint8"}]
+    }, {
+        "name": "plain",
+        "inputs": [],
+        "outputs": []
+    }],
+    "events": [{
+        "name": "Changed",
+        "id": "0x00000011",
+        "inputs": [{"name": "value", "type": "uint8"}]
+    }, {
+        "name": "Other",
+        "inputs": []
+    }]
+}"#;
+
+#[test]
+fn test_output_event_id_collision() {
+    let contract = Contract::load(COLLISION_ABI.as_bytes()).unwrap();
+    let warnings = contract.lint();
+    assert_eq!(
+        warnings,
+        vec![AbiWarning::OutputEventIdCollision {
+            id: 0x11,
+            function: "answer".to_owned(),
+            event: "Changed".to_owned(),
+        }]
+    );
+    assert!(warnings[0].to_string().contains("00000011"));
+
+    let mut body = BuilderData::new();
+    body.append_u32(0x11).unwrap();
+    body.append_u8(7).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let decoded = contract.decode_output(body.clone(), true, false).unwrap();
+    assert_eq!(decoded.function_name, "answer");
+    assert_eq!(decoded.tokens, vec![Token::new("value", TokenValue::Uint(Uint::new(7, 8)))]);
+    assert!(!contract.peek(body).unwrap().is_event);
+
+    let contract = Contract::load(
+        r#"{"version": "2.3", "functions": [], "events": [{"name": "Changed", "inputs": []}]}"#
+            .as_bytes(),
+    )
+    .unwrap();
+    assert!(contract.lint().is_empty());
+}
@@ -0,0 +1,77 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::contract::{ABI_VERSION_1_0, ABI_VERSION_2_2};
+use crate::{Function, Param, ParamType, Token, TokenValue, Uint};
+
+use ever_block::SliceData;
+use std::collections::HashMap;
+
+fn multisig_function() -> Function {
+    Function {
+        abi_version: ABI_VERSION_2_2,
+        name: "func".to_string(),
+        header: vec![
+            Param::new("time", ParamType::Time),
+            Param::new("expire", ParamType::Expire),
+        ],
+        inputs: vec![
+            Param::new("a", ParamType::Uint(8)),
+            Param::new("b", ParamType::Bytes),
+        ],
+        outputs: vec![],
+        input_id: 0x12345678,
+        output_id: 0,
+        header_defaults: Default::default(),
+        signed: true,
+    }
+}
+
+#[test]
+fn test_multisig_body() {
+    let func = multisig_function();
+    let header = HashMap::from([
+        ("time".to_owned(), TokenValue::Time(1)),
+        ("expire".to_owned(), TokenValue::Expire(123)),
+    ]);
+    let input = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(5, 8))),
+        Token::new("b", TokenValue::Bytes(vec![1, 2, 3])),
+    ];
+
+    let (unsigned, hash) = func.create_unsigned_multisig_call(&header, &input, None).unwrap();
+    let body = Function::fill_signatures(&[None, None, None], unsigned).unwrap();
+
+    let body = Function::add_sign_at(SliceData::load_builder(body).unwrap(), 1, &[1; 64]).unwrap();
+    let body = Function::add_sign_at(SliceData::load_builder(body).unwrap(), 2, &[2; 64]).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let (signatures, tokens) = func.decode_multisig_input(body.clone(), false).unwrap();
+    assert_eq!(signatures, vec![None, Some([1; 64]), Some([2; 64])]);
+    assert_eq!(tokens, input);
+
+    let (_, unsigned) = Function::decode_signatures(body.clone()).unwrap();
+    assert_eq!(unsigned.into_cell().repr_hash().into_vec(), hash);
+
+    assert!(Function::add_sign_at(body, 3, &[3; 64]).is_err());
+    assert!(Function::fill_signatures(&[], Default::default()).is_err());
+}
+
+#[test]
+fn test_multisig_not_supported() {
+    let mut func = multisig_function();
+    func.abi_version = ABI_VERSION_1_0;
+    assert!(func
+        .create_unsigned_multisig_call(&HashMap::new(), &[], None)
+        .is_err());
+}
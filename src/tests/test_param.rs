@@ -12,12 +12,32 @@
 */
 
 use crate::token::Detokenizer;
-use crate::{Function, Int, Param, ParamType, Token, TokenValue, Uint};
+use crate::{
+    Contract, Function, HeaderParam, HeaderValue, Int, Param, ParamType, PayloadEnvelope,
+    SignaturePatch, Token, TokenValue, Uint,
+};
 
-use ever_block::BuilderData;
+use ever_block::{AccountId, BuilderData, MsgAddress, MsgAddressInt, SliceData};
+use std::str::FromStr;
 use ever_block::IBitstring;
 
-use crate::contract::ABI_VERSION_2_0;
+use crate::contract::{ABI_VERSION_2_0, ABI_VERSION_2_2, ABI_VERSION_2_3, ABI_VERSION_2_4};
+use crate::token::{ChainBreak, ChainReason, RefPlacement, RefReason, SerializedValue};
+use std::collections::HashMap;
+
+fn test_function(inputs: Vec<Param>, outputs: Vec<Param>) -> Function {
+    Function {
+        abi_version: ABI_VERSION_2_4,
+        name: "func".to_string(),
+        header: vec![],
+        inputs,
+        outputs,
+        input_id: 0x12345678,
+        output_id: 0,
+        header_defaults: Default::default(),
+        signed: true,
+    }
+}
 
 #[test]
 fn int_json_representation() {
@@ -49,12 +69,8 @@ fn int_json_representation() {
 fn test_encode_internal_output() {
     let func: Function = Function {
         abi_version: ABI_VERSION_2_0,
-        name: "func".to_string(),
-        header: vec![],
-        inputs: vec![],
-        outputs: vec![],
         input_id: 0,
-        output_id: 0,
+        ..test_function(vec![], vec![])
     };
 
     let tokens = [
@@ -93,6 +109,556 @@ fn test_encode_internal_output() {
     assert_eq!(test_tree, expected_tree);
 }
 
+#[test]
+fn test_encode_input_with_report() {
+    let uint256 = |name: &str| Param::new(name, ParamType::Uint(256));
+    let large_tuple = ParamType::Tuple(vec![uint256("x"), uint256("y"), uint256("z"), uint256("w")]);
+    let func: Function = Function {
+        input_id: 0,
+        ..test_function(
+            vec![
+                uint256("a"),
+                uint256("b"),
+                uint256("c"),
+                uint256("d"),
+                Param::new("e", ParamType::Optional(Box::new(large_tuple.clone()))),
+                Param::new("f", ParamType::Ref(Box::new(ParamType::Uint(8)))),
+            ],
+            vec![],
+        )
+    };
+
+    let mut tokens: Vec<Token> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|name| Token::new(name, TokenValue::Uint(Uint::new(1, 256))))
+        .collect();
+    tokens.push(Token::new(
+        "e",
+        TokenValue::Optional(
+            large_tuple.clone(),
+            Some(Box::new(TokenValue::default_value(&large_tuple))),
+        ),
+    ));
+    tokens.push(Token::new(
+        "f",
+        TokenValue::Ref(Box::new(TokenValue::Uint(Uint::new(1, 8)))),
+    ));
+
+    let (builder, report) = func
+        .encode_input_with_report(&HashMap::new(), &tokens, true, None, None)
+        .unwrap();
+
+    assert_eq!(
+        builder,
+        func.encode_input(&HashMap::new(), &tokens, true, None, None).unwrap()
+    );
+    assert_eq!(report.cells_count(), 2);
+    assert_eq!(
+        report.chain,
+        vec![ChainBreak {
+            path: "d".to_owned(),
+            reason: ChainReason::NotEnoughBits
+        }]
+    );
+    assert_eq!(
+        report.refs,
+        vec![
+            RefPlacement {
+                path: "e".to_owned(),
+                reason: RefReason::LargeOptional
+            },
+            RefPlacement {
+                path: "f".to_owned(),
+                reason: RefReason::Ref
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_encode_input_with_report_promoted_tuple() {
+    let uint256 = |name: &str| Param::new(name, ParamType::Uint(256));
+    let large_tuple = ParamType::Tuple(vec![uint256("x"), uint256("y"), uint256("z"), uint256("w")]);
+    let promoted = large_tuple.promote_large_tuples(&ABI_VERSION_2_4);
+    assert_eq!(promoted, ParamType::Ref(Box::new(large_tuple.clone())));
+    let func: Function = Function {
+        input_id: 0,
+        ..test_function(
+            vec![
+                uint256("a"),
+                Param::new("t", promoted),
+                uint256("b"),
+                uint256("c"),
+                uint256("d"),
+            ],
+            vec![],
+        )
+    };
+
+    let uint = || TokenValue::Uint(Uint::new(1, 256));
+    let tokens = vec![
+        Token::new("a", uint()),
+        Token::new(
+            "t",
+            TokenValue::default_value(&large_tuple).promote_large_tuples(&ABI_VERSION_2_4),
+        ),
+        Token::new("b", uint()),
+        Token::new("c", uint()),
+        Token::new("d", uint()),
+    ];
+
+    let (builder, report) = func
+        .encode_input_with_report(&HashMap::new(), &tokens, true, None, None)
+        .unwrap();
+
+    assert_eq!(
+        builder,
+        func.encode_input(&HashMap::new(), &tokens, true, None, None).unwrap()
+    );
+    // promoted tuple takes only a reference in the root cell
+    assert_eq!(
+        report.chain,
+        vec![ChainBreak {
+            path: "d".to_owned(),
+            reason: ChainReason::NotEnoughBits
+        }]
+    );
+    assert_eq!(
+        report.refs,
+        vec![RefPlacement {
+            path: "t".to_owned(),
+            reason: RefReason::Ref
+        }]
+    );
+}
+
+#[test]
+fn test_sample_input_json() {
+    let func: Function = test_function(
+        vec![
+            Param::new("a", ParamType::Uint(8)),
+            Param::new("b", ParamType::Array(Box::new(ParamType::Bool))),
+            Param::new(
+                "c",
+                ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::String)),
+            ),
+            Param::new(
+                "d",
+                ParamType::Tuple(vec![
+                    Param::new("x", ParamType::Int(16)),
+                    Param::new("y", ParamType::Optional(Box::new(ParamType::Bytes))),
+                ]),
+            ),
+        ],
+        vec![],
+    );
+
+    let sample = func.sample_input_json().unwrap();
+    assert_eq!(
+        sample,
+        serde_json::json!({
+            "a": "0",
+            "b": [false],
+            "c": { "0": "" },
+            "d": { "x": "0", "y": "" },
+        })
+    );
+
+    let tokens = crate::token::Tokenizer::tokenize_all_params(&func.inputs, &sample).unwrap();
+    assert!(Token::types_check(&tokens, &func.inputs));
+}
+
+#[test]
+fn test_strip_header() {
+    let header = vec![
+        Param::new("time", ParamType::Time),
+        Param::new("expire", ParamType::Expire),
+        Param::new("pubkey", ParamType::PublicKey),
+    ];
+    let func: Function = Function {
+        abi_version: ABI_VERSION_2_2,
+        header: header.clone(),
+        ..test_function(vec![Param::new("a", ParamType::Uint(8))], vec![])
+    };
+
+    let header_values = HashMap::from([
+        ("time".to_owned(), TokenValue::Time(1)),
+        ("expire".to_owned(), TokenValue::Expire(123)),
+        ("pubkey".to_owned(), TokenValue::PublicKey(Some([1; 32]))),
+    ]);
+    let input = [Token::new("a", TokenValue::Uint(Uint::new(5, 8)))];
+    let (unsigned, _) = func
+        .create_unsigned_call(&header_values, &input, false, true, None)
+        .unwrap();
+
+    let signed = Function::fill_sign(&ABI_VERSION_2_2, Some(&[7; 64]), None, unsigned.clone())
+        .unwrap();
+    let (info, mut rest) = Function::strip_header(
+        &ABI_VERSION_2_2,
+        SliceData::load_builder(signed).unwrap(),
+        &header,
+        false,
+    )
+    .unwrap();
+    assert_eq!(info.signature, Some(vec![7; 64]));
+    assert_eq!(info.time(), Some(1));
+    assert_eq!(info.expire(), Some(123));
+    assert_eq!(info.pubkey(), Some([1; 32]));
+    assert_eq!(info.function_id, 0x12345678);
+    assert_eq!(rest.get_next_byte().unwrap(), 5);
+
+    let unsigned = Function::fill_sign(&ABI_VERSION_2_2, None, None, unsigned).unwrap();
+    let (info, _) = Function::strip_header(
+        &ABI_VERSION_2_2,
+        SliceData::load_builder(unsigned).unwrap(),
+        &header,
+        false,
+    )
+    .unwrap();
+    assert_eq!(info.signature, None);
+    assert_eq!(info.function_id, 0x12345678);
+}
+
+#[test]
+fn test_body_hash_for_signing() {
+    let func: Function = Function {
+        abi_version: ABI_VERSION_2_3,
+        header: vec![Param::new("expire", ParamType::Expire)],
+        ..test_function(vec![Param::new("a", ParamType::Uint(8))], vec![])
+    };
+    let address = MsgAddressInt::from_str(
+        "0:1111111111111111111111111111111111111111111111111111111111111111",
+    )
+    .unwrap();
+
+    let header = HashMap::from([("expire".to_owned(), TokenValue::Expire(123))]);
+    let input = [Token::new("a", TokenValue::Uint(Uint::new(5, 8)))];
+    let (unsigned, hash) = func
+        .create_unsigned_call(&header, &input, false, true, Some(address.clone()))
+        .unwrap();
+
+    let placeholder = Function::fill_sign(&ABI_VERSION_2_3, None, None, unsigned.clone()).unwrap();
+    let recomputed = Function::body_hash_for_signing(
+        SliceData::load_builder(placeholder).unwrap(),
+        &ABI_VERSION_2_3,
+        Some(address.clone()),
+    )
+    .unwrap();
+    assert_eq!(recomputed, hash);
+
+    let signed = Function::fill_sign(&ABI_VERSION_2_3, Some(&[7; 64]), None, unsigned).unwrap();
+    let signed = SliceData::load_builder(signed).unwrap();
+    let recomputed =
+        Function::body_hash_for_signing(signed.clone(), &ABI_VERSION_2_3, Some(address)).unwrap();
+    assert_eq!(recomputed, hash);
+
+    assert!(Function::body_hash_for_signing(signed, &ABI_VERSION_2_3, None).is_err());
+}
+
+#[test]
+fn test_encode_input_with_placeholder() {
+    let mut func: Function = Function {
+        abi_version: ABI_VERSION_2_3,
+        header: vec![Param::new("expire", ParamType::Expire)],
+        ..test_function(vec![Param::new("a", ParamType::Uint(8))], vec![])
+    };
+    let address = MsgAddressInt::from_str(
+        "0:1111111111111111111111111111111111111111111111111111111111111111",
+    )
+    .unwrap();
+    let header = HashMap::from([("expire".to_owned(), TokenValue::Expire(123))]);
+    let input = [Token::new("a", TokenValue::Uint(Uint::new(5, 8)))];
+
+    let (body, patch) = func
+        .encode_input_with_placeholder(&header, &input, "box", Some(address.clone()))
+        .unwrap();
+    let (unsigned, hash) = func
+        .create_unsigned_call(&header, &input, false, true, Some(address.clone()))
+        .unwrap();
+    assert_eq!(
+        patch,
+        SignaturePatch {
+            signer: "box".to_owned(),
+            offset_bits: 1,
+            len: 64,
+            hash,
+        }
+    );
+
+    let signed = patch.apply(body, &[7; 64]).unwrap();
+    let expected =
+        Function::fill_sign(&ABI_VERSION_2_3, Some(&[7; 64]), None, unsigned.clone()).unwrap();
+    assert_eq!(signed, expected);
+
+    // patch can be applied to already signed body
+    let resigned = patch.apply(signed, &[9; 64]).unwrap();
+    let expected = Function::fill_sign(&ABI_VERSION_2_3, Some(&[9; 64]), None, unsigned).unwrap();
+    assert_eq!(resigned, expected);
+
+    func.abi_version = crate::contract::ABI_VERSION_1_0;
+    assert!(func.encode_input_with_placeholder(&header, &input, "box", None).is_err());
+}
+
+#[test]
+fn test_encode_batch() {
+    let func: Function = Function {
+        abi_version: ABI_VERSION_2_3,
+        name: "airdrop".to_string(),
+        header: vec![
+            Param::new("time", ParamType::Time),
+            Param::new("expire", ParamType::Expire),
+        ],
+        ..test_function(
+            vec![
+                Param::new("token", ParamType::Address),
+                Param::new("amount", ParamType::Uint(128)),
+                Param::new("comment", ParamType::String),
+            ],
+            vec![],
+        )
+    };
+    let address = MsgAddressInt::from_str(
+        "0:1111111111111111111111111111111111111111111111111111111111111111",
+    )
+    .unwrap();
+    let token = TokenValue::Address(
+        MsgAddress::with_standart(None, 0, AccountId::from([0x22; 32])).unwrap(),
+    );
+    let inputs: Vec<Vec<Token>> = (0..5)
+        .map(|i| {
+            vec![
+                Token::new("token", token.clone()),
+                Token::new("amount", TokenValue::Uint(Uint::new(100 + i, 128))),
+                Token::new("comment", TokenValue::String(format!("drop {}", i))),
+            ]
+        })
+        .collect();
+    let header = HashMap::from([
+        ("time".to_owned(), TokenValue::Time(1_700_000_000_000)),
+        ("expire".to_owned(), TokenValue::Expire(1_700_000_060)),
+    ]);
+    let sign_key = ever_block::ed25519_generate_private_key().unwrap();
+
+    for (internal, key) in [(true, None), (false, None), (false, Some(&sign_key))] {
+        let batch = func
+            .encode_batch(&inputs, &header, internal, key, Some(address.clone()))
+            .unwrap();
+        assert_eq!(batch.len(), inputs.len());
+        for (i, (body, input)) in batch.into_iter().zip(&inputs).enumerate() {
+            let mut header = header.clone();
+            if !internal {
+                header.insert("time".to_owned(), TokenValue::Time(1_700_000_000_000 + i as u64));
+            }
+            let expected = func
+                .encode_input(&header, input, internal, key, Some(address.clone()))
+                .unwrap();
+            assert_eq!(body, expected);
+        }
+    }
+
+    // calls without explicit time are not rejected by replay protection as duplicates
+    let batch = func.encode_batch(&inputs, &HashMap::new(), false, None, None).unwrap();
+    let times: Vec<TokenValue> = batch
+        .into_iter()
+        .map(|body| {
+            let body = SliceData::load_builder(body).unwrap();
+            Function::decode_header(&ABI_VERSION_2_3, body, &func.header, false).unwrap().0[0]
+                .value
+                .clone()
+        })
+        .collect();
+    for pair in times.windows(2) {
+        match pair {
+            [TokenValue::Time(prev), TokenValue::Time(next)] => assert!(prev < next),
+            _ => panic!("unexpected header values: {:?}", pair),
+        }
+    }
+
+    assert!(func.encode_batch(&[], &header, true, None, None).unwrap().is_empty());
+    let wrong = vec![inputs[0][..2].to_vec()];
+    assert!(func.encode_batch(&wrong, &header, true, None, None).is_err());
+}
+
+#[test]
+fn test_encode_input_with_envelope() {
+    let func: Function = test_function(vec![Param::new("a", ParamType::Uint(8))], vec![]);
+    let input = [Token::new("a", TokenValue::Uint(Uint::new(5, 8)))];
+
+    let mut prefix = BuilderData::new();
+    prefix.append_u8(0xAB).unwrap();
+    let mut suffix = BuilderData::new();
+    suffix.append_u16(0xCDEF).unwrap();
+    let envelope = PayloadEnvelope {
+        prefix: Some(prefix.into()),
+        suffix: Some(suffix.into()),
+    };
+
+    let body = func
+        .encode_input_with_envelope(&HashMap::new(), &input, true, None, None, &envelope)
+        .unwrap();
+    let mut body = SliceData::load_builder(body).unwrap();
+
+    // envelope protocol parses its own prefix and the rest of body is decoded by ABI
+    let mut without_prefix = body.clone();
+    without_prefix.get_next_byte().unwrap();
+    let (tokens, mut remainder) = func.decode_input_with_remainder(without_prefix, true).unwrap();
+    assert_eq!(tokens, input);
+    assert_eq!(remainder.get_next_u16().unwrap(), 0xCDEF);
+
+    assert_eq!(body.get_next_byte().unwrap(), 0xAB);
+    assert_eq!(body.get_next_u32().unwrap(), 0x12345678);
+    assert_eq!(body.get_next_byte().unwrap(), 5);
+    assert_eq!(body.get_next_u16().unwrap(), 0xCDEF);
+    assert_eq!(body.remaining_bits(), 0);
+
+    // suffix with reserved layout size doesn't fit into the root cell
+    let mut suffix = BuilderData::new();
+    suffix.append_u16(0xCDEF).unwrap();
+    let envelope = PayloadEnvelope {
+        prefix: None,
+        suffix: Some(SerializedValue::with_layout(suffix, 1000, 0).unwrap()),
+    };
+    let body = func
+        .encode_input_with_envelope(&HashMap::new(), &input, true, None, None, &envelope)
+        .unwrap();
+    assert_eq!(body.bits_used(), 40);
+    assert_eq!(body.references_used(), 1);
+
+    let (tokens, mut remainder) = func
+        .decode_input_with_remainder(SliceData::load_builder(body).unwrap(), true)
+        .unwrap();
+    assert_eq!(tokens, input);
+    // remainder is at the end of the root cell, suffix is in the next chain cell
+    assert_eq!(remainder.remaining_bits(), 0);
+    let mut suffix = SliceData::load_cell(remainder.checked_drain_reference().unwrap()).unwrap();
+    assert_eq!(suffix.get_next_u16().unwrap(), 0xCDEF);
+
+    let envelope = PayloadEnvelope {
+        prefix: Some(SerializedValue {
+            data: BuilderData::new(),
+            max_bits: 0,
+            max_refs: 4,
+        }),
+        suffix: None,
+    };
+    assert!(func
+        .encode_input_with_envelope(&HashMap::new(), &input, true, None, None, &envelope)
+        .is_err());
+}
+
+#[test]
+fn test_to_json_schema() {
+    let func: Function = test_function(
+        vec![
+            Param::new("a", ParamType::Uint(8)),
+            Param::new("b", ParamType::Int(256)),
+            Param::new("c", ParamType::Array(Box::new(ParamType::Address))),
+            Param::new(
+                "d",
+                ParamType::Tuple(vec![
+                    Param::new("x", ParamType::Bool),
+                    Param::new("y", ParamType::Optional(Box::new(ParamType::Bytes))),
+                ]),
+            ),
+            Param::new(
+                "e",
+                ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::String)),
+            ),
+        ],
+        vec![],
+    );
+
+    let schema = func.to_json_schema();
+    assert_eq!(schema["title"], "func");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], serde_json::json!(["a", "b", "c", "d", "e"]));
+
+    let a = &schema["properties"]["a"];
+    assert_eq!(a["anyOf"][0]["maximum"], 255);
+    assert_eq!(a["description"], "unsigned integer in range [0, 255]");
+
+    let b = &schema["properties"]["b"];
+    assert_eq!(b["anyOf"][0]["minimum"], i64::MIN);
+    assert_eq!(b["anyOf"][1]["pattern"], "^-?(0x[0-9a-fA-F]+|[0-9]+)$");
+
+    assert_eq!(schema["properties"]["c"]["type"], "array");
+    assert_eq!(schema["properties"]["c"]["items"]["type"], "string");
+
+    let d = &schema["properties"]["d"];
+    assert_eq!(d["required"], serde_json::json!(["x", "y"]));
+    assert_eq!(d["properties"]["y"]["anyOf"][0]["type"], "null");
+
+    let e = &schema["properties"]["e"];
+    assert_eq!(e["additionalProperties"]["type"], "string");
+    assert_eq!(e["propertyNames"]["pattern"], "^(0x[0-9a-fA-F]+|[0-9]+)$");
+}
+
+#[test]
+fn test_signing_summary() {
+    let func = Function {
+        name: "sendTransaction".to_string(),
+        input_id: 0,
+        ..test_function(
+            vec![
+                Param::new("dest", ParamType::Address),
+                Param::new("value", ParamType::VarUint(16)),
+                Param::new("bounce", ParamType::Bool),
+                Param::new("payload", ParamType::Cell),
+                Param::new(
+                    "extra",
+                    ParamType::Tuple(vec![
+                        Param::new("flags", ParamType::Array(Box::new(ParamType::Uint(8)))),
+                        Param::new("comment", ParamType::Optional(Box::new(ParamType::String))),
+                    ]),
+                ),
+            ],
+            vec![],
+        )
+    };
+
+    let dest = MsgAddress::with_standart(None, 0, AccountId::from([0x11; 32])).unwrap();
+    let payload = BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap();
+    let input = vec![
+        Token::new("dest", TokenValue::Address(dest.clone())),
+        Token::new("value", TokenValue::VarUint(16, 1_500_000_000u64.into())),
+        Token::new("bounce", TokenValue::Bool(false)),
+        Token::new("payload", TokenValue::Cell(payload.clone())),
+        Token::new(
+            "extra",
+            TokenValue::Tuple(vec![
+                Token::new(
+                    "flags",
+                    TokenValue::Array(
+                        ParamType::Uint(8),
+                        vec![TokenValue::Uint(Uint::new(3, 8)), TokenValue::Uint(Uint::new(1, 8))],
+                    ),
+                ),
+                Token::new("comment", TokenValue::Optional(ParamType::String, None)),
+            ]),
+        ),
+    ];
+
+    let summary = func.signing_summary(&input).unwrap();
+    let expected = [
+        ("dest", dest.to_string()),
+        ("value", "1.5".to_owned()),
+        ("bounce", "false".to_owned()),
+        ("payload", format!("hash:{}", hex::encode(payload.repr_hash().into_vec()))),
+        ("extra.flags[0]", "3".to_owned()),
+        ("extra.flags[1]", "1".to_owned()),
+        ("extra.comment", "none".to_owned()),
+    ];
+    assert_eq!(
+        summary,
+        expected
+            .iter()
+            .map(|(label, value)| (label.to_string(), value.clone()))
+            .collect::<Vec<_>>()
+    );
+
+    assert!(func.signing_summary(&input[..2]).is_err());
+}
+
 #[test]
 fn test_simple_param_deserialization() {
     let s = r#"{
@@ -298,3 +864,67 @@ fn test_optional_tuple_param_deserialization() {
         }
     );
 }
+
+#[test]
+fn test_header_param() {
+    for param in HeaderParam::ALL {
+        assert_eq!(HeaderParam::from_name(param.name()).unwrap(), param);
+        let json = format!("\"{}\"", param.name());
+        assert_eq!(serde_json::from_str::<Param>(&json).unwrap(), param.to_param());
+    }
+    assert_eq!(HeaderParam::Expire.to_param(), Param::new("expire", ParamType::Expire));
+    assert!(HeaderParam::from_name("timestamp").is_err());
+    assert!(serde_json::from_str::<Param>("\"uint32\"").is_err());
+
+    let abi = r#"{"version": "2.4", "header": ["time", "uint32"], "functions": []}"#;
+    assert!(Contract::load(abi.as_bytes()).is_err());
+    let abi = r#"{
+        "version": "2.4",
+        "header": ["pubkey", {"name": "flags", "type": "uint32"}],
+        "functions": []
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    assert_eq!(
+        contract.header(),
+        &vec![HeaderParam::PublicKey.to_param(), Param::new("flags", ParamType::Uint(32))]
+    );
+}
+
+#[test]
+fn test_header_values() {
+    let func = Function {
+        abi_version: ABI_VERSION_2_2,
+        header: vec![
+            HeaderParam::Time.to_param(),
+            HeaderParam::Expire.to_param(),
+            HeaderParam::PublicKey.to_param(),
+            Param::new("flags", ParamType::Uint(8)),
+        ],
+        ..test_function(vec![], vec![])
+    };
+
+    let values = [
+        HeaderValue::Time(1),
+        HeaderValue::Expire(123),
+        HeaderValue::PublicKey(Some([1; 32])),
+        HeaderValue::custom("flags", TokenValue::Uint(Uint::new(3, 8))).unwrap(),
+    ];
+    let header = HashMap::from([
+        ("time".to_owned(), TokenValue::Time(1)),
+        ("expire".to_owned(), TokenValue::Expire(123)),
+        ("pubkey".to_owned(), TokenValue::PublicKey(Some([1; 32]))),
+        ("flags".to_owned(), TokenValue::Uint(Uint::new(3, 8))),
+    ]);
+    assert_eq!(HeaderValue::to_header_map(&values).unwrap(), header);
+    assert_eq!(
+        func.encode_input_with_header_values(&values, &[], false, None, None).unwrap(),
+        func.encode_input(&header, &[], false, None, None).unwrap()
+    );
+
+    assert!(HeaderValue::custom("pubkey", TokenValue::Bytes(vec![1; 32])).is_err());
+    assert!(HeaderValue::to_header_map(&[HeaderValue::Time(1), HeaderValue::Time(2)]).is_err());
+    let undeclared = [HeaderValue::custom("nonce", TokenValue::Uint(Uint::new(1, 8))).unwrap()];
+    assert!(func.encode_input_with_header_values(&undeclared, &[], false, None, None).is_err());
+    let wrong_type = [HeaderValue::custom("flags", TokenValue::Bool(true)).unwrap()];
+    assert!(func.encode_input_with_header_values(&wrong_type, &[], false, None, None).is_err());
+}
@@ -0,0 +1,83 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::resolver::{decode_input, decode_input_all, AbiKey, AbiMap, AbiResolver};
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::{MsgAddressInt, SliceData, UInt256};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const WALLET_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.3",
+    "functions": [{
+        "name": "send",
+        "inputs": [{"name": "value", "type": "uint128"}],
+        "outputs": []
+    }]
+}"#;
+
+fn encode_send() -> SliceData {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap();
+    let input = [Token::new("value", TokenValue::Uint(Uint::new(100, 128)))];
+    let body = contract
+        .function("send")
+        .unwrap()
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    SliceData::load_builder(body).unwrap()
+}
+
+#[test]
+fn test_abi_map() {
+    let contract = Contract::load(WALLET_ABI.as_bytes()).unwrap().with_name("Wallet");
+    let address = MsgAddressInt::from_str(&format!("0:{}", "1".repeat(64))).unwrap();
+    let code_hash = UInt256::from([2; 32]);
+
+    let mut resolver = AbiMap::new();
+    resolver.insert(AbiKey::Address(address.clone()), contract.clone());
+    resolver.insert(AbiKey::CodeHash(code_hash.clone()), contract.clone());
+
+    assert_eq!(resolver.resolve(&AbiKey::Address(address.clone())), Some(contract.clone()));
+    assert_eq!(resolver.resolve(&AbiKey::CodeHash(code_hash.clone())), Some(contract));
+    assert_eq!(resolver.resolve(&AbiKey::CodeHash(UInt256::default())), None);
+
+    let decoded = decode_input(&resolver, &AbiKey::Address(address), encode_send(), true, false)
+        .unwrap();
+    assert_eq!(decoded.function_name, "send");
+    assert_eq!(decoded.source.as_deref(), Some("Wallet"));
+
+    let unknown = AbiKey::CodeHash(UInt256::default());
+    assert!(decode_input(&resolver, &unknown, encode_send(), true, false).is_err());
+
+    let decoded = decode_input_all(
+        &resolver,
+        &[AbiKey::CodeHash(code_hash), unknown],
+        encode_send(),
+        true,
+        false,
+    );
+    assert_eq!(decoded.len(), 1);
+}
+
+#[test]
+fn test_closure_resolver() {
+    let resolver = |key: &AbiKey| match key {
+        AbiKey::CodeHash(_) => Contract::load(WALLET_ABI.as_bytes()).ok(),
+        AbiKey::Address(_) => None,
+    };
+    let key = AbiKey::CodeHash(UInt256::default());
+    let decoded = decode_input(&resolver, &key, encode_send(), true, false).unwrap();
+    assert_eq!(decoded.to_json().unwrap(), r#"{"value":"100"}"#);
+}
@@ -0,0 +1,36 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::selector::function_id;
+use crate::Function;
+
+const TRANSFER: u32 = selector!("transfer(address,uint128)()v2");
+const CONSTRUCTOR: u32 = selector!("constructor()()v2");
+
+#[test]
+fn test_function_id() {
+    for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 200] {
+        let signature = "f".repeat(len);
+        assert_eq!(function_id(&signature), Function::calc_function_id(&signature), "{}", len);
+    }
+    assert_eq!(TRANSFER, Function::calc_function_id("transfer(address,uint128)()v2"));
+
+    let route = |id: u32| match id & 0x7FFFFFFF {
+        id if id == TRANSFER & 0x7FFFFFFF => "transfer",
+        id if id == CONSTRUCTOR & 0x7FFFFFFF => "constructor",
+        _ => "unknown",
+    };
+    assert_eq!(route(TRANSFER | 0x80000000), "transfer");
+    assert_eq!(route(CONSTRUCTOR), "constructor");
+    assert_eq!(route(0), "unknown");
+}
@@ -0,0 +1,108 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::contract::ABI_VERSION_2_4;
+use crate::state_diff::StateChange;
+use crate::{Contract, Token, TokenValue, Uint};
+
+use ever_block::{BuilderData, SliceData};
+
+fn uint(value: u128, size: usize) -> TokenValue {
+    TokenValue::Uint(Uint::new(value, size))
+}
+
+#[test]
+fn test_diff_fields() {
+    let contract = Contract::load(
+        r#"{
+            "version": "2.4",
+            "functions": [],
+            "fields": [
+                {"name": "a", "type": "uint32"},
+                {"name": "b", "type": "bool"},
+                {"name": "c", "type": "bytes"}
+            ]
+        }"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let state = |b: bool, c: &[u8]| {
+        let tokens = [
+            Token::new("a", uint(1, 32)),
+            Token::new("b", TokenValue::Bool(b)),
+            Token::new("c", TokenValue::Bytes(c.to_vec())),
+        ];
+        let data = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_4).unwrap();
+        SliceData::load_builder(data).unwrap()
+    };
+
+    assert_eq!(contract.diff_states(state(false, b"x"), state(false, b"x")).unwrap(), vec![]);
+    assert_eq!(
+        contract.diff_states(state(false, b"x"), state(true, b"y")).unwrap(),
+        vec![
+            StateChange {
+                name: "b".to_owned(),
+                old: Some(TokenValue::Bool(false)),
+                new: Some(TokenValue::Bool(true)),
+            },
+            StateChange {
+                name: "c".to_owned(),
+                old: Some(TokenValue::Bytes(b"x".to_vec())),
+                new: Some(TokenValue::Bytes(b"y".to_vec())),
+            },
+        ]
+    );
+    assert!(contract.diff_states(state(false, b"x"), SliceData::default()).is_err());
+}
+
+#[test]
+fn test_diff_data() {
+    let contract = Contract::load(
+        r#"{
+            "version": "2.2",
+            "functions": [],
+            "data": [
+                {"key": 2, "name": "y", "type": "uint16"},
+                {"key": 1, "name": "x", "type": "uint8"}
+            ]
+        }"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let empty = SliceData::load_builder(BuilderData::new()).unwrap();
+    let old = contract
+        .update_data(empty, &[Token::new("x", uint(1, 8))])
+        .unwrap();
+    let new = contract
+        .update_data(old.clone(), &[Token::new("x", uint(2, 8)), Token::new("y", uint(5, 16))])
+        .unwrap();
+
+    assert_eq!(
+        contract.diff_states(old, new).unwrap(),
+        vec![
+            StateChange {
+                name: "x".to_owned(),
+                old: Some(uint(1, 8)),
+                new: Some(uint(2, 8)),
+            },
+            StateChange {
+                name: "y".to_owned(),
+                old: None,
+                new: Some(uint(5, 16)),
+            },
+        ]
+    );
+
+    let contract = Contract::load(r#"{"version": "2.2", "functions": []}"#.as_bytes()).unwrap();
+    assert!(contract.diff_states(SliceData::default(), SliceData::default()).is_err());
+}
@@ -0,0 +1,62 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::Contract;
+
+const STATS_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.3",
+    "header": ["time", "expire"],
+    "functions": [{
+        "name": "small",
+        "inputs": [{"name": "a", "type": "uint8"}],
+        "outputs": []
+    }, {
+        "name": "large",
+        "inputs": [
+            {"name": "a", "type": "address"},
+            {"name": "b", "type": "address"},
+            {"name": "c", "type": "tuple[]", "components": [
+                {"name": "x", "type": "uint8"},
+                {"name": "y", "type": "map(uint32,tuple)", "components": [
+                    {"name": "p", "type": "bool"},
+                    {"name": "q", "type": "bool"},
+                    {"name": "r", "type": "bool"},
+                    {"name": "s", "type": "bool"}
+                ]}
+            ]}
+        ],
+        "outputs": [{"name": "d", "type": "optional(cell)"}]
+    }],
+    "events": [{
+        "name": "Done",
+        "inputs": []
+    }]
+}"#;
+
+#[test]
+fn test_contract_stats() {
+    let stats = Contract::load(STATS_ABI.as_bytes()).unwrap().stats();
+    assert_eq!(stats.functions, 2);
+    assert_eq!(stats.events, 1);
+    assert_eq!(stats.max_input_bits, 591 * 2 + 33);
+    // array -> tuple -> map -> tuple
+    assert_eq!(stats.max_type_depth, 4);
+    assert_eq!(stats.largest_tuple, 4);
+
+    // signature, time, expire, id and uint8 fit into one cell
+    assert_eq!(stats.body_cells["small"], 1);
+    // every address takes its own cell, array fits into the cell with the second address and
+    // adds a reference
+    assert_eq!(stats.body_cells["large"], 4);
+}
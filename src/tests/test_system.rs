@@ -0,0 +1,86 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::system::{decode_system_message, SystemContract};
+use crate::{Token, TokenValue, Uint};
+
+use ever_block::{AccountId, BuilderData, IBitstring, MsgAddressInt, SliceData};
+use std::collections::HashMap;
+
+#[test]
+fn test_elector_messages() {
+    let elector = SystemContract::Elector.abi();
+    let input = [
+        Token::new("query_id", TokenValue::Uint(Uint::new(1, 64))),
+        Token::new("validator_pubkey", TokenValue::Uint(Uint::new(2, 256))),
+        Token::new("stake_at", TokenValue::Uint(Uint::new(3, 32))),
+        Token::new("max_factor", TokenValue::Uint(Uint::new(0x30000, 32))),
+        Token::new("adnl_addr", TokenValue::Uint(Uint::new(4, 256))),
+        Token::new("signature", TokenValue::Ref(Box::new(TokenValue::FixedBytes(vec![5; 64])))),
+    ];
+    let body = elector
+        .function("process_new_stake")
+        .unwrap()
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let mut slice = SliceData::load_builder(body).unwrap();
+    assert_eq!(slice.clone().get_next_u32().unwrap(), 0x4e73744b);
+    assert_eq!(slice.remaining_references(), 1);
+
+    let (contract, decoded) = decode_system_message(slice.clone()).unwrap();
+    assert_eq!(contract, SystemContract::Elector);
+    assert_eq!(decoded.function_name, "process_new_stake");
+    assert_eq!(decoded.source.as_deref(), Some("elector"));
+    assert_eq!(decoded.tokens, input);
+
+    // answer built the way elector does
+    let mut answer = BuilderData::new();
+    answer.append_u32(0xf374484c).unwrap();
+    answer.append_u64(1).unwrap();
+    answer.append_u32(0).unwrap();
+    let (_, decoded) = decode_system_message(SliceData::load_builder(answer).unwrap()).unwrap();
+    assert_eq!(decoded.function_name, "new_stake_confirmation");
+
+    slice.get_next_u32().unwrap();
+    assert!(decode_system_message(slice).is_err());
+}
+
+#[test]
+fn test_config_messages() {
+    let mut body = BuilderData::new();
+    body.append_u32(0x6e565052).unwrap();
+    body.append_u64(7).unwrap();
+    body.append_u32(1_700_000_000).unwrap();
+    body.append_bit_one().unwrap();
+    let proposal = BuilderData::with_raw(vec![0xf3], 8).unwrap().into_cell().unwrap();
+    body.checked_append_reference(proposal).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+    let (contract, decoded) = decode_system_message(body).unwrap();
+    assert_eq!(contract, SystemContract::Config);
+    assert_eq!(decoded.function_name, "new_proposal");
+    assert_eq!(decoded.tokens[3].value, TokenValue::Bool(true));
+}
+
+#[test]
+fn test_system_addresses() {
+    for contract in SystemContract::ALL {
+        let address = contract.address().unwrap();
+        assert_eq!(SystemContract::from_address(&address), Some(contract));
+    }
+    assert_eq!(
+        SystemContract::Config.address().unwrap().to_string(),
+        format!("-1:{}", "5".repeat(64))
+    );
+    let address = MsgAddressInt::with_standart(None, 0, AccountId::from([0x33; 32])).unwrap();
+    assert_eq!(SystemContract::from_address(&address), None);
+}
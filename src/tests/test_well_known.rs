@@ -0,0 +1,42 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::well_known::{config_contract, dens_root_contract, elector_contract};
+use crate::{Token, TokenValue, Uint};
+
+use ever_block::SliceData;
+
+#[test]
+fn test_well_known_contracts() {
+    let config = config_contract();
+    assert_eq!(config.name(), Some("config"));
+    assert_eq!(config.function("new_proposal").unwrap().get_input_id(), 0x6E565052);
+
+    let elector = elector_contract();
+    assert_eq!(elector.name(), Some("elector"));
+    assert_eq!(elector.function("recover_stake").unwrap().get_input_id(), 0x47657424);
+    assert!(std::ptr::eq(elector, elector_contract()));
+
+    let dens_root = dens_root_contract();
+    assert_eq!(dens_root.name(), Some("dens_root"));
+    let resolve = dens_root.function("resolve").unwrap();
+    let input = [
+        Token::new("answerId", TokenValue::Uint(Uint::new(0, 32))),
+        Token::new("path", TokenValue::String("everscale/wallet".to_owned())),
+    ];
+    let body = resolve.encode_input(&Default::default(), &input, true, None, None).unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+    let decoded = dens_root.decode_input(body, true, false).unwrap();
+    assert_eq!(decoded.function_name, "resolve");
+    assert_eq!(decoded.tokens, input);
+}
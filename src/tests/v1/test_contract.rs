@@ -12,7 +12,9 @@
 */
 
 use crate::{Contract, DataItem, Event, Function, Param, ParamType};
+use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 const TEST_ABI: &str = r#"
 {
@@ -67,7 +69,7 @@ const TEST_ABI: &str = r#"
 fn test_abi_parse() {
     let parsed_contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
 
-    let mut functions = HashMap::new();
+    let mut functions = IndexMap::new();
     let header = vec![Param {
         name: "time".into(),
         kind: ParamType::Time,
@@ -109,6 +111,8 @@ fn test_abi_parse() {
             output_id: Function::calc_function_id(
                 "input_and_output(time,uint64,uint8[],bytes)(int16,uint8)v1",
             ) | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -125,6 +129,8 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: Function::calc_function_id("no_output(time,uint15)()v1") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("no_output(time,uint15)()v1") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -141,6 +147,8 @@ fn test_abi_parse() {
             }],
             input_id: Function::calc_function_id("no_input(time)(uint8)v1") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("no_input(time)(uint8)v1") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -154,6 +162,8 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: Function::calc_function_id("constructor(time)()v1") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("constructor(time)()v1") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -167,10 +177,12 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: 0x01234567,
             output_id: 0x01234567,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
-    let mut events = HashMap::new();
+    let mut events = IndexMap::new();
 
     events.insert(
         "input".to_owned(),
@@ -205,7 +217,7 @@ fn test_abi_parse() {
         },
     );
 
-    let mut data = HashMap::new();
+    let mut data = IndexMap::new();
 
     data.insert(
         "a".to_owned(),
@@ -221,11 +233,15 @@ fn test_abi_parse() {
     let expected_contract = Contract {
         abi_version: 1.into(),
         header,
-        functions,
-        events,
-        data,
-        fields: vec![],
+        functions: Arc::new(functions),
+        events: Arc::new(events),
+        data: Arc::new(data),
+        fields: Arc::new(vec![]),
         init_fields: Default::default(),
+        name: None,
+        function_sources: HashMap::new(),
+        event_sources: HashMap::new(),
+        header_defaults: Default::default(),
     };
 
     assert_eq!(parsed_contract, expected_contract);
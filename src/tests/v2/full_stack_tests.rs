@@ -18,9 +18,13 @@ use ever_block::{
     ED25519_SIGNATURE_LENGTH,
 };
 
+use crate::contract::{HeaderDefaults, PubkeyPolicy};
 use crate::json_abi::*;
+use crate::token::Utf8Handling;
+use crate::{Token, TokenValue, Uint};
 
 use serde_json::json;
+use std::collections::HashMap;
 
 const WALLET_ABI: &str = r#"{
     "ABI version": 2,
@@ -342,6 +346,320 @@ fn test_not_signed_call() {
     assert_eq!(test_tree_v23, expected_tree);
 }
 
+#[test]
+fn test_json_abi_session() {
+    let params = r#"{
+        "limitId": "0x2"
+    }"#;
+    let header = r#"{
+        "pubkey": "11c0a428b6768562df09db05326595337dbb5f8dde0e128224d4df48df760f17",
+        "expire": 123
+    }"#;
+
+    let session = JsonAbiSession::new(WALLET_ABI).unwrap();
+    assert!(session.contract().function("getLimit").is_ok());
+
+    for _ in 0..2 {
+        let test_tree = session
+            .encode_function_call("getLimit", Some(header), params, false, None, None)
+            .unwrap();
+        let expected_tree =
+            encode_function_call(WALLET_ABI, "getLimit", Some(header), params, false, None, None)
+                .unwrap();
+        assert_eq!(test_tree, expected_tree);
+
+        let response = session
+            .decode_unknown_function_call(SliceData::load_builder(test_tree).unwrap(), false, false)
+            .unwrap();
+        assert_eq!(response.function_name, "getLimit");
+        assert_eq!(response.params, r#"{"limitId":"2"}"#);
+    }
+
+    assert!(JsonAbiSession::new("{}").is_err());
+}
+
+#[test]
+fn test_header_defaults() {
+    let params = r#"{"limitId": "0x2"}"#;
+    let contract = crate::Contract::load(WALLET_ABI.as_bytes())
+        .unwrap()
+        .with_header_defaults(HeaderDefaults {
+            expire_delta: Some(60),
+            pubkey: PubkeyPolicy::Required,
+        });
+    let session = JsonAbiSession::from_contract(contract);
+
+    assert!(session
+        .encode_function_call("getLimit", None, params, false, None, None)
+        .is_err());
+    // header is not encoded into internal message
+    assert!(session
+        .encode_function_call("getLimit", None, params, true, None, None)
+        .is_ok());
+
+    let sign_key = ed25519_generate_private_key().unwrap();
+    let now = chrono::Utc::now().timestamp() as u32;
+    let body = session
+        .encode_function_call("getLimit", None, params, false, Some(&sign_key), None)
+        .unwrap();
+    let peek = session
+        .contract()
+        .peek(SliceData::load_builder(body).unwrap())
+        .unwrap();
+    let expire = peek.expire.unwrap();
+    assert!(expire >= now + 60 && expire <= now + 70);
+
+    let header = r#"{"expire": 123}"#;
+    let body = session
+        .encode_function_call("getLimit", Some(header), params, false, Some(&sign_key), None)
+        .unwrap();
+    let peek = session
+        .contract()
+        .peek(SliceData::load_builder(body).unwrap())
+        .unwrap();
+    assert_eq!(peek.expire, Some(123));
+}
+
+#[test]
+fn test_header_defaults_from_abi() {
+    let abi = |defaults: &str| {
+        format!(
+            r#"{{
+                "ABI version": 2,
+                "version": "2.2",
+                "header": ["pubkey", "expire"],
+                "headerDefaults": {},
+                "functions": [{{
+                    "name": "set",
+                    "inputs": [{{"name": "value", "type": "uint8"}}],
+                    "outputs": []
+                }}],
+                "events": []
+            }}"#,
+            defaults
+        )
+    };
+    let contract =
+        crate::Contract::load(abi(r#"{"expireDelta": 60, "pubkey": "required"}"#).as_bytes())
+            .unwrap();
+    assert_eq!(
+        contract.header_defaults(),
+        &HeaderDefaults {
+            expire_delta: Some(60),
+            pubkey: PubkeyPolicy::Required,
+        }
+    );
+
+    let function = contract.function("set").unwrap();
+    let input = [Token::new("value", TokenValue::Uint(Uint::new(5, 8)))];
+    assert!(function.encode_input(&HashMap::new(), &input, false, None, None).is_err());
+    // header is not encoded into internal message
+    assert!(function.encode_input(&HashMap::new(), &input, true, None, None).is_ok());
+
+    let sign_key = ed25519_generate_private_key().unwrap();
+    let header = HashMap::from([(
+        "pubkey".to_owned(),
+        TokenValue::PublicKey(Some(sign_key.verifying_key())),
+    )]);
+    let now = chrono::Utc::now().timestamp() as u32;
+    let body = function.encode_input(&header, &input, false, Some(&sign_key), None).unwrap();
+    let expire = contract.peek(SliceData::load_builder(body).unwrap()).unwrap().expire.unwrap();
+    assert!(expire >= now + 60 && expire <= now + 70);
+
+    let contract = crate::Contract::load(abi(r#"{"pubkey": null}"#).as_bytes()).unwrap();
+    assert_eq!(contract.header_defaults(), &HeaderDefaults::default());
+    assert!(crate::Contract::load(abi(r#"{"pubkey": "always"}"#).as_bytes()).is_err());
+}
+
+#[test]
+fn test_fixed_time() {
+    let abi = r#"{
+        "ABI version": 2,
+        "version": "2.3",
+        "header": ["time", "expire"],
+        "functions": [{
+            "name": "set",
+            "inputs": [{"name": "value", "type": "uint8"}],
+            "outputs": []
+        }],
+        "events": []
+    }"#;
+    let contract = crate::Contract::load(abi.as_bytes())
+        .unwrap()
+        .with_header_defaults(HeaderDefaults {
+            expire_delta: Some(60),
+            pubkey: PubkeyPolicy::DefaultNone,
+        });
+    let session = JsonAbiSession::from_contract(contract);
+    let params = r#"{"value": 5}"#;
+    let options = EncodeOptions::new().fixed_time(1_700_000_000_123);
+
+    let body = session.encode_function_call_ext("set", params, &options).unwrap();
+    assert_eq!(body, session.encode_function_call_ext("set", params, &options).unwrap());
+
+    let decoded = session
+        .decode_unknown_function_call_ext(
+            SliceData::load_builder(body).unwrap(),
+            &DecodeOptions::new().decode_header(true),
+        )
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&decoded.header.unwrap()).unwrap(),
+        json!({
+            "time": "1700000000123",
+            "expire": "1700000060",
+        })
+    );
+
+    // explicit header values take precedence
+    let body = session
+        .encode_function_call_ext("set", params, &options.with_header(r#"{"time": 1}"#))
+        .unwrap();
+    let decoded = session
+        .decode_unknown_function_call_ext(
+            SliceData::load_builder(body).unwrap(),
+            &DecodeOptions::new().decode_header(true),
+        )
+        .unwrap();
+    assert!(decoded.header.unwrap().contains(r#""time":"1""#));
+}
+
+#[test]
+fn test_decode_header_never_signed() {
+    let abi = r#"{
+        "ABI version": 2,
+        "version": "2.3",
+        "header": ["time", "expire"],
+        "functions": [{
+            "name": "set",
+            "signed": false,
+            "inputs": [{"name": "value", "type": "uint8"}],
+            "outputs": []
+        }],
+        "events": []
+    }"#;
+    let session = JsonAbiSession::new(abi).unwrap();
+    let body = session
+        .encode_function_call_ext(
+            "set",
+            r#"{"value": 5}"#,
+            &EncodeOptions::new()
+                .with_header(r#"{"expire": 1700000060}"#)
+                .fixed_time(1_700_000_000_123),
+        )
+        .unwrap();
+
+    let decoded = session
+        .decode_unknown_function_call_ext(
+            SliceData::load_builder(body).unwrap(),
+            &DecodeOptions::new().decode_header(true),
+        )
+        .unwrap();
+    assert_eq!(decoded.function_name, "set");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&decoded.header.unwrap()).unwrap(),
+        json!({
+            "time": "1700000000123",
+            "expire": "1700000060",
+        })
+    );
+}
+
+#[test]
+fn test_options_ext() {
+    let params = r#"{
+        "value": 12,
+        "period": 30
+    }"#;
+    let header = r#"{"expire": 123}"#;
+    let sign_key = ed25519_generate_private_key().unwrap();
+
+    let options = EncodeOptions::new().with_header(header).with_sign_key(&sign_key);
+    let test_tree =
+        encode_function_call_ext(WALLET_ABI, "createArbitraryLimit", params, &options).unwrap();
+    let expected_tree = encode_function_call(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        Some(header),
+        params,
+        false,
+        Some(&sign_key),
+        None,
+    )
+    .unwrap();
+    assert_eq!(test_tree, expected_tree);
+
+    let internal_tree = encode_function_call_ext(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        params,
+        &EncodeOptions::new().internal(true),
+    )
+    .unwrap();
+    let response = decode_unknown_function_call_ext(
+        WALLET_ABI,
+        SliceData::load_builder(internal_tree).unwrap(),
+        &DecodeOptions::new().internal(true),
+    )
+    .unwrap();
+    assert_eq!(response.function_name, "createArbitraryLimit");
+    assert_eq!(response.function_id, 0x2238B58A);
+    assert_eq!(response.params, r#"{"value":"12","period":"30"}"#);
+    assert_eq!(response.header, None);
+
+    let response = decode_unknown_function_call_ext(
+        WALLET_ABI,
+        SliceData::load_builder(test_tree).unwrap(),
+        &DecodeOptions::new().decode_header(true),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&response.header.unwrap()).unwrap(),
+        json!({
+            "expire": "123",
+            "pubkey": hex::encode(sign_key.verifying_key()),
+        })
+    );
+
+    let mut response_tree = BuilderData::with_bitstring(vec![
+        0xA2, 0x38, 0xB5, 0x8A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+    ])
+    .unwrap();
+    response_tree.append_u8(0).unwrap();
+    let response_tree = SliceData::load_builder(response_tree).unwrap();
+
+    let options = DecodeOptions::new();
+    assert!(decode_function_response_ext(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        response_tree.clone(),
+        &options
+    )
+    .is_err());
+
+    let options = options.allow_partial(true);
+    let response = decode_function_response_ext(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        response_tree.clone(),
+        &options.with_expected_id(0xA238B58A),
+    )
+    .unwrap();
+    assert_eq!(response, r#"{"value0":"0"}"#);
+    // input ID is not accepted as answer ID
+    assert!(decode_function_response_ext(
+        WALLET_ABI,
+        "createArbitraryLimit",
+        response_tree.clone(),
+        &options.with_expected_id(0x2238B58A),
+    )
+    .is_err());
+    let response =
+        decode_unknown_function_response_ext(WALLET_ABI, response_tree, &options).unwrap();
+    assert_eq!(response.function_name, "createArbitraryLimit");
+    assert_eq!(response.function_id, 0xA238B58A);
+}
+
 #[test]
 fn test_add_signature_full() {
     let params = r#"{"limitId":"2"}"#;
@@ -364,6 +682,19 @@ fn test_add_signature_full() {
     assert_eq!(decoded.params, params);
 }
 
+#[test]
+fn test_compute_signing_hash() {
+    let params = r#"{"limitId":"2"}"#;
+    let header = "{}";
+
+    let (_, data_to_sign) =
+        prepare_function_call_for_sign(WALLET_ABI, "getLimit", Some(header), params, None).unwrap();
+    assert_eq!(
+        compute_signing_hash(WALLET_ABI, "getLimit", Some(header), params, None).unwrap(),
+        data_to_sign
+    );
+}
+
 #[test]
 fn test_find_event() {
     let event_tree = SliceData::load_builder(
@@ -458,6 +789,47 @@ fn test_update_decode_contract_data() {
     );
 }
 
+#[test]
+fn test_decode_contract_data_with_unknown() {
+    let mut test_map = HashmapE::with_bit_len(Contract::DATA_MAP_KEYLEN);
+    test_map
+        .set_builder(
+            SliceData::load_builder(0u64.write_to_new_cell().unwrap()).unwrap(),
+            &BuilderData::with_raw(vec![0u8; 32], 256).unwrap(),
+        )
+        .unwrap();
+
+    let params = r#"{
+        "subscription": "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "owner": "0x2222222222222222222222222222222222222222222222222222222222222222"
+     }
+    "#;
+
+    let data = SliceData::load_cell(test_map.serialize().unwrap()).unwrap();
+    let data = update_contract_data(WALLET_ABI, params, data).unwrap();
+
+    // value under the key not declared in ABI
+    let mut map = HashmapE::with_hashmap(Contract::DATA_MAP_KEYLEN, data.reference_opt(0));
+    let unknown = BuilderData::with_raw(vec![0xAB], 8).unwrap();
+    map.set_builder(
+        SliceData::load_builder(7u64.write_to_new_cell().unwrap()).unwrap(),
+        &unknown,
+    )
+    .unwrap();
+    let data = SliceData::load_cell(map.serialize().unwrap()).unwrap();
+
+    let decoded = decode_contract_data_with_unknown(WALLET_ABI, data, false).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Value>(&decoded).unwrap(),
+        json!({
+            "data": serde_json::from_str::<Value>(params).unwrap(),
+            "unknown": {
+                "7": hex::encode(ever_block::write_boc(&unknown.into_cell().unwrap()).unwrap()),
+            },
+        })
+    );
+}
+
 const ABI_WITH_FIELDS: &str = r#"{
     "version": "2.1",
     "functions": [],
@@ -626,6 +998,76 @@ fn test_signed_call_v23() {
     assert_eq!(response.function_name, "createArbitraryLimit");
 }
 
+#[test]
+fn test_signed_call_with_signer() {
+    let params = r#"
+    {
+        "value": 12,
+        "period": 30
+    }"#;
+
+    let sign_key = ed25519_generate_private_key().unwrap();
+    let public_key = sign_key.verifying_key();
+    let address = "0:5555555555555555555555555555555555555555555555555555555555555555";
+
+    let expected_tree = encode_function_call(
+        WALLET_ABI_V23,
+        "createArbitraryLimit",
+        None,
+        params,
+        false,
+        Some(&sign_key),
+        Some(address),
+    )
+    .unwrap();
+
+    let test_tree = encode_function_call_with_signer(
+        WALLET_ABI_V23,
+        "createArbitraryLimit",
+        None,
+        params,
+        false,
+        Some(&public_key),
+        |hash| Ok(sign_key.sign(hash)),
+        Some(address),
+    )
+    .unwrap();
+
+    assert_eq!(test_tree, expected_tree);
+
+    let fixed_tree = encode_function_call_with_signer(
+        WALLET_ABI_V23,
+        "createArbitraryLimit",
+        None,
+        params,
+        false,
+        Some(&public_key),
+        |_| Ok([7; ED25519_SIGNATURE_LENGTH]),
+        Some(address),
+    )
+    .unwrap();
+
+    let (sign, _) = get_signature_data(
+        WALLET_ABI_V23,
+        SliceData::load_builder(fixed_tree).unwrap(),
+        Some(address),
+    )
+    .unwrap();
+    assert_eq!(sign, vec![7; ED25519_SIGNATURE_LENGTH]);
+
+    let result = encode_function_call_with_signer(
+        WALLET_ABI_V23,
+        "createArbitraryLimit",
+        None,
+        params,
+        false,
+        Some(&public_key),
+        |_| Err(AbiError::NotImplemented.into()),
+        Some(address),
+    );
+    assert!(result.is_err());
+}
+
 fn value_helper(abi_type: &str, value: &str) -> Result<BuilderData> {
     let abi = json!({
         "ABI version": 2,
@@ -790,3 +1232,109 @@ fn test_wrong_storage_layout() {
 
     assert!(decode_storage_fields(ABI_WRONG_STORAGE_LAYOUT, SliceData::load_cell(image.data.unwrap()).unwrap(), false).is_ok());
 }
+
+#[test]
+fn test_skip_layout_check() {
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [{
+            "name": "get",
+            "inputs": [],
+            "outputs": [{"name":"a","type":"uint32"},{"name":"b","type":"uint64"}]
+        }]
+    }"#;
+
+    // `b` fits into the root cell but is placed into the next one
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.append_u32(123).unwrap();
+    builder.checked_append_reference(
+        BuilderData::with_raw(456u64.to_be_bytes().as_slice(), 64).unwrap().into_cell().unwrap(),
+    ).unwrap();
+    let response = SliceData::load_builder(builder).unwrap();
+
+    let options = DecodeOptions::new().internal(true);
+    assert!(decode_function_response_ext(abi, "get", response.clone(), &options).is_err());
+
+    let decoded = decode_function_response_ext(
+        abi,
+        "get",
+        response,
+        &options.skip_layout_check(true),
+    )
+    .unwrap();
+    assert_eq!(decoded, r#"{"a":"123","b":"456"}"#);
+}
+
+#[test]
+fn test_utf8_handling_option() {
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [{
+            "name": "get",
+            "inputs": [],
+            "outputs": [{"name":"s","type":"string"}]
+        }]
+    }"#;
+
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.checked_append_reference(
+        BuilderData::with_raw(vec![0xff, 0xfe], 16).unwrap().into_cell().unwrap(),
+    ).unwrap();
+    let response = SliceData::load_builder(builder).unwrap();
+
+    let options = DecodeOptions::new().internal(true);
+    assert!(decode_function_response_ext(abi, "get", response.clone(), &options).is_err());
+
+    let decoded = decode_function_response_ext(
+        abi,
+        "get",
+        response.clone(),
+        &options.utf8(Utf8Handling::Lossy),
+    )
+    .unwrap();
+    assert_eq!(decoded, "{\"s\":\"\u{FFFD}\u{FFFD}\"}");
+
+    let decoded = decode_function_response_ext(
+        abi,
+        "get",
+        response,
+        &options.utf8(Utf8Handling::AsBytes),
+    )
+    .unwrap();
+    assert_eq!(decoded, r#"{"s":"fffe"}"#);
+}
+
+#[test]
+fn test_verify_roundtrip() {
+    let abi = r#"{
+        "version": "2.2",
+        "functions": [{
+            "name": "set",
+            "inputs": [
+                {"name":"values","type":"map(uint32,uint8)"},
+                {"components":[{"name":"a","type":"uint8[]"},{"name":"b","type":"optional(string)"}],"name":"t","type":"tuple"}
+            ],
+            "outputs": []
+        }]
+    }"#;
+    let params = r#"{"values":{"0x10":"1","17":"2"},"t":{"a":["1","2"],"b":"text"}}"#;
+
+    let options = EncodeOptions::new().internal(true);
+    let body = encode_function_call_ext(abi, "set", params, &options).unwrap();
+    // keys written in other form are not reported as mismatch
+    let verified =
+        encode_function_call_ext(abi, "set", params, &options.verify_roundtrip(true)).unwrap();
+    assert_eq!(verified, body);
+
+    let err = crate::error::AbiError::RoundtripMismatch {
+        path: "/t/a/1".to_owned(),
+        encoded: "2".to_owned(),
+        decoded: "3".to_owned(),
+    };
+    assert_eq!(
+        err.to_string(),
+        "Encoded body does not decode to the same values at `/t/a/1`: encoded 2, decoded 3"
+    );
+}
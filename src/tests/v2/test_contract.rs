@@ -11,10 +11,19 @@
 * limitations under the License.
 */
 
-use crate::{Contract, DataItem, Event, Function, Param, ParamType};
-use std::collections::HashMap;
+use crate::{
+    AbiError, Contract, DataItem, Event, EventFilter, Function, FunctionId, Param, ParamType, Token,
+    TokenValue,
+};
+use ever_block::{IBitstring, SliceData};
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
-use crate::contract::ABI_VERSION_2_4;
+use crate::contract::{
+    DecodedAs, HeaderDefaults, Peek, PubkeyPolicy, VersionPrecedence, ABI_VERSION_2_3,
+    ABI_VERSION_2_4,
+};
 
 const TEST_ABI: &str = r#"
 {
@@ -79,7 +88,7 @@ const TEST_ABI: &str = r#"
 fn test_abi_parse() {
     let parsed_contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
 
-    let mut functions = HashMap::new();
+    let mut functions = IndexMap::new();
     let header = vec![
         Param {
             name: "time".into(),
@@ -136,6 +145,8 @@ fn test_abi_parse() {
             output_id: Function::calc_function_id(
                 "input_and_output(uint64,uint8[],bytes)(int16,uint8)v2",
             ) | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -152,6 +163,8 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: Function::calc_function_id("no_output(uint15)()v2") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("no_output(uint15)()v2") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -168,6 +181,8 @@ fn test_abi_parse() {
             }],
             input_id: Function::calc_function_id("no_input()(uint8)v2") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("no_input()(uint8)v2") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -181,6 +196,8 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: Function::calc_function_id("constructor()()v2") & 0x7FFFFFFF,
             output_id: Function::calc_function_id("constructor()()v2") | 0x80000000,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
@@ -194,10 +211,12 @@ fn test_abi_parse() {
             outputs: vec![],
             input_id: 0x01234567,
             output_id: 0x01234567,
+            header_defaults: Default::default(),
+            signed: true,
         },
     );
 
-    let mut events = HashMap::new();
+    let mut events = IndexMap::new();
 
     events.insert(
         "input".to_owned(),
@@ -232,7 +251,7 @@ fn test_abi_parse() {
         },
     );
 
-    let mut data = HashMap::new();
+    let mut data = IndexMap::new();
 
     data.insert(
         "a".to_owned(),
@@ -261,11 +280,15 @@ fn test_abi_parse() {
     let expected_contract = Contract {
         abi_version,
         header,
-        functions,
-        events,
-        data,
-        fields,
+        functions: Arc::new(functions),
+        events: Arc::new(events),
+        data: Arc::new(data),
+        fields: Arc::new(fields),
         init_fields,
+        name: None,
+        function_sources: HashMap::new(),
+        event_sources: HashMap::new(),
+        header_defaults: Default::default(),
     };
 
     assert_eq!(parsed_contract, expected_contract);
@@ -295,3 +318,593 @@ fn print_function_singnatures() {
         println!("{:X?}\n", id);
     }
 }
+
+#[test]
+fn test_fingerprint() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+
+    let reordered = serde_json::json!({
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [
+            {"name": "has_id", "id": "0x01234567", "inputs": [], "outputs": []},
+            {"name": "constructor", "inputs": [], "outputs": []},
+            {"name": "no_input", "inputs": [], "outputs": [{"name": "a", "type": "uint8"}]},
+            {"name": "no_output", "inputs": [{"name": "a", "type": "uint15"}], "outputs": []},
+            {
+                "name": "input_and_output",
+                "inputs": [
+                    {"name": "a", "type": "uint64"},
+                    {"name": "b", "type": "uint8[]"},
+                    {"name": "c", "type": "bytes"}
+                ],
+                "outputs": [{"name": "a", "type": "int16"}, {"name": "b", "type": "uint8"}]
+            }
+        ],
+        "events": [
+            {"name": "has_id", "id": "0x89abcdef", "inputs": []},
+            {"name": "no_input", "inputs": []},
+            {"name": "input", "inputs": [{"name": "a", "type": "uint64"}]}
+        ],
+        "data": [{"key": 100, "name": "a", "type": "uint256"}],
+        "fields": [
+            {"name": "a", "type": "uint32"},
+            {"name": "b", "type": "int128", "init": true}
+        ]
+    })
+    .to_string();
+    let reordered = Contract::load(reordered.as_bytes()).unwrap();
+    assert_eq!(contract.fingerprint(), reordered.fingerprint());
+
+    let changed = TEST_ABI.replace("uint15", "uint16");
+    let changed = Contract::load(changed.as_bytes()).unwrap();
+    assert_ne!(contract.fingerprint(), changed.fingerprint());
+
+    let mut unsigned = contract.clone();
+    Arc::make_mut(unsigned.functions_mut().get_mut("constructor").unwrap()).signed = false;
+    assert_ne!(contract.fingerprint(), unsigned.fingerprint());
+
+    let defaults = contract.clone().with_header_defaults(HeaderDefaults {
+        expire_delta: Some(60),
+        pubkey: PubkeyPolicy::DefaultNone,
+    });
+    assert_ne!(contract.fingerprint(), defaults.fingerprint());
+    let required = contract.clone().with_header_defaults(HeaderDefaults {
+        expire_delta: None,
+        pubkey: PubkeyPolicy::Required,
+    });
+    assert_ne!(contract.fingerprint(), required.fingerprint());
+}
+
+#[test]
+fn test_merge() {
+    const PART_ABI: &str = r#"
+    {
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [
+            {"name": "constructor", "inputs": [], "outputs": []},
+            {"name": "facet", "inputs": [{"name": "x", "type": "address"}], "outputs": []}
+        ],
+        "events": [{"name": "facet_event", "inputs": []}],
+        "data": [{"key": 101, "name": "b", "type": "uint8"}]
+    }"#;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let part = Contract::load(PART_ABI.as_bytes()).unwrap();
+
+    let merged = Contract::merge(&[contract.clone(), part]).unwrap();
+    assert_eq!(merged.functions().len(), contract.functions().len() + 1);
+    assert!(merged.function("facet").is_ok());
+    assert!(merged.event("facet_event").is_ok());
+    assert!(merged.data().contains_key("b"));
+    assert_eq!(merged.fields(), contract.fields());
+
+    let collision = PART_ABI.replace(r#""type": "address""#, r#""type": "uint8""#);
+    let collision = Contract::load(collision.replace("facet", "no_output").as_bytes()).unwrap();
+    assert!(Contract::merge(&[contract.clone(), collision]).is_err());
+
+    let key_collision = Contract::load(PART_ABI.replace("101", "100").as_bytes()).unwrap();
+    assert!(Contract::merge(&[contract.clone(), key_collision]).is_err());
+
+    let other_header = Contract::load(PART_ABI.replace(r#""time", "#, "").as_bytes()).unwrap();
+    assert!(Contract::merge(&[contract, other_header]).is_err());
+
+    assert!(Contract::merge(&[]).is_err());
+}
+
+#[test]
+fn test_merge_header_defaults() {
+    const PART_ABI: &str = r#"
+    {
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [{"name": "facet", "inputs": [], "outputs": []}]
+    }"#;
+    let header_defaults = HeaderDefaults {
+        expire_delta: Some(60),
+        pubkey: PubkeyPolicy::Required,
+    };
+
+    let contract = Contract::load(TEST_ABI.as_bytes())
+        .unwrap()
+        .with_header_defaults(header_defaults);
+    let part = Contract::load(PART_ABI.as_bytes()).unwrap();
+    assert!(Contract::merge(&[contract.clone(), part.clone()]).is_err());
+
+    let part = part.with_header_defaults(header_defaults);
+    let merged = Contract::merge(&[contract, part]).unwrap();
+    assert_eq!(merged.header_defaults(), &header_defaults);
+    assert_eq!(merged.function("facet").unwrap().header_defaults, header_defaults);
+}
+
+#[test]
+fn test_decoded_message_source() {
+    const PART_ABI: &str = r#"
+    {
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [{"name": "facet", "inputs": [{"name": "x", "type": "uint8"}], "outputs": []}]
+    }"#;
+    const OTHER_ABI: &str = r#"
+    {
+        "version": "2.4",
+        "functions": [{"name": "facet", "inputs": [{"name": "x", "type": "uint8"}], "outputs": []}]
+    }"#;
+
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap().with_name("main");
+    let part = Contract::load(PART_ABI.as_bytes()).unwrap().with_name("facet");
+    let merged = Contract::merge(&[contract, part.clone()]).unwrap();
+
+    assert_eq!(merged.name(), None);
+    assert_eq!(merged.function_source("facet"), Some("facet"));
+    assert_eq!(merged.function_source("no_output"), Some("main"));
+    assert_eq!(merged.event_source("input"), Some("main"));
+
+    let input = [Token::new("x", TokenValue::Uint(crate::Uint::new(1, 8)))];
+    let body = part
+        .function("facet")
+        .unwrap()
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let body = SliceData::load_builder(body).unwrap();
+
+    let decoded = merged.decode_input(body.clone(), true, false).unwrap();
+    assert_eq!(decoded.source.as_deref(), Some("facet"));
+    assert_eq!(decoded.qualified_name(), "facet::facet");
+    assert_eq!(decoded.function(), Some(part.function("facet").unwrap()));
+    assert!(std::ptr::eq(decoded.function().unwrap(), part.function("facet").unwrap()));
+    assert!(decoded.event().is_none());
+    assert_eq!(decoded.to_json().unwrap(), r#"{"x":"1"}"#);
+
+    let other = Contract::load(OTHER_ABI.as_bytes()).unwrap().with_name("other");
+    let decoded = Contract::decode_input_all(&[merged, other], body, true, false);
+    let sources: Vec<_> = decoded.iter().map(|msg| msg.qualified_name()).collect();
+    assert_eq!(sources, vec!["facet::facet", "other::facet"]);
+}
+
+#[test]
+fn test_decode_body_auto() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("input_and_output").unwrap();
+    let input = [
+        Token::new("a", TokenValue::Uint(crate::Uint::new(1, 64))),
+        Token::new(
+            "b",
+            TokenValue::Array(ParamType::Uint(8), vec![TokenValue::Uint(crate::Uint::new(2, 8))]),
+        ),
+        Token::new("c", TokenValue::Bytes(vec![3])),
+    ];
+
+    let internal_body = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let (decoded, internal) = contract
+        .decode_body_auto(SliceData::load_builder(internal_body).unwrap())
+        .unwrap();
+    assert!(internal);
+    assert_eq!(decoded.function_name, "input_and_output");
+    assert_eq!(decoded.tokens, input);
+
+    let header = HashMap::from([("expire".to_owned(), TokenValue::Expire(123))]);
+    let external_body = function
+        .encode_input(&header, &input, false, None, None)
+        .unwrap();
+    let (decoded, internal) = contract
+        .decode_body_auto(SliceData::load_builder(external_body).unwrap())
+        .unwrap();
+    assert!(!internal);
+    assert_eq!(decoded.function_name, "input_and_output");
+    assert_eq!(decoded.tokens, input);
+
+    assert!(contract.decode_body_auto(SliceData::default()).is_err());
+}
+
+#[test]
+fn test_peek() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_output").unwrap();
+    let input = [Token::new("a", TokenValue::Uint(crate::Uint::new(1, 15)))];
+
+    let header = HashMap::from([("expire".to_owned(), TokenValue::Expire(123))]);
+    let external = function
+        .encode_input(&header, &input, false, None, None)
+        .unwrap();
+    assert_eq!(
+        contract.peek(SliceData::load_builder(external).unwrap()).unwrap(),
+        Peek {
+            function_id: function.get_input_id(),
+            is_event: false,
+            has_signature: false,
+            expire: Some(123),
+        }
+    );
+
+    let internal = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    assert_eq!(
+        contract.peek(SliceData::load_builder(internal).unwrap()).unwrap(),
+        Peek {
+            function_id: function.get_input_id(),
+            is_event: false,
+            has_signature: false,
+            expire: None,
+        }
+    );
+
+    let event = contract.event("input").unwrap();
+    let mut event_body = ever_block::BuilderData::new();
+    ever_block::IBitstring::append_u32(&mut event_body, event.get_id()).unwrap();
+    ever_block::IBitstring::append_u64(&mut event_body, 1).unwrap();
+    let peek = contract.peek(SliceData::load_builder(event_body).unwrap()).unwrap();
+    assert!(peek.is_event);
+    assert_eq!(peek.function_id, event.get_id());
+
+    let mut unknown = ever_block::BuilderData::new();
+    ever_block::IBitstring::append_u32(&mut unknown, 0x7777777).unwrap();
+    assert!(contract.peek(SliceData::load_builder(unknown).unwrap()).is_err());
+}
+
+#[test]
+fn test_getter_call() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+
+    let body = contract.encode_getter_call("no_input", &[]).unwrap();
+    let peek = contract.peek(SliceData::load_builder(body.clone()).unwrap()).unwrap();
+    assert!(!peek.has_signature);
+    assert_eq!(peek.expire, Some(u32::MAX));
+    let decoded = contract
+        .decode_input(SliceData::load_builder(body).unwrap(), false, false)
+        .unwrap();
+    assert_eq!(decoded.function_name, "no_input");
+
+    let function = contract.function("no_input").unwrap();
+    let output = [Token::new("a", TokenValue::Uint(crate::Uint::new(42, 8)))];
+    let answer = function
+        .encode_internal_output(function.get_output_id(), &output)
+        .unwrap();
+    assert_eq!(
+        contract
+            .decode_getter_answer("no_input", SliceData::load_builder(answer).unwrap())
+            .unwrap(),
+        output
+    );
+
+    assert!(contract.encode_getter_call("unknown", &[]).is_err());
+}
+
+#[test]
+fn test_getter_call_required_pubkey() {
+    let contract = Contract::load(TEST_ABI.as_bytes())
+        .unwrap()
+        .with_header_defaults(HeaderDefaults {
+            expire_delta: None,
+            pubkey: PubkeyPolicy::Required,
+        });
+
+    let function = contract.function("no_input").unwrap();
+    assert!(function.encode_input(&HashMap::new(), &[], false, None, None).is_err());
+
+    let body = contract.encode_getter_call("no_input", &[]).unwrap();
+    let decoded = contract
+        .decode_input(SliceData::load_builder(body).unwrap(), false, false)
+        .unwrap();
+    assert_eq!(decoded.function_name, "no_input");
+}
+
+#[test]
+fn test_version_precedence() {
+    let abi = |abi_version: u8| {
+        format!(
+            r#"{{"ABI version": {}, "version": "2.3", "functions": []}}"#,
+            abi_version
+        )
+    };
+
+    let contract = Contract::load(abi(2).as_bytes()).unwrap();
+    assert_eq!(contract.version(), &ABI_VERSION_2_3);
+
+    let err = Contract::load(abi(1).as_bytes()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AbiError>(),
+        Some(AbiError::AmbiguousVersion { abi_version: 1, .. })
+    ));
+
+    let contract =
+        Contract::load_with_precedence(abi(1).as_bytes(), VersionPrecedence::Version).unwrap();
+    assert_eq!(contract.version(), &ABI_VERSION_2_3);
+    let contract =
+        Contract::load_with_precedence(abi(1).as_bytes(), VersionPrecedence::AbiVersion).unwrap();
+    assert_eq!(contract.version().major, 1);
+}
+
+#[test]
+fn test_event_encode_and_filter() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let event = contract.event("input").unwrap();
+    let tokens = [Token::new("a", TokenValue::Uint(crate::Uint::new(7, 64)))];
+
+    let body = event.encode(&tokens).unwrap();
+    let decoded = contract
+        .decode_output(SliceData::load_builder(body).unwrap(), false, false)
+        .unwrap();
+    assert_eq!(decoded.function_name, "input");
+    assert_eq!(decoded.tokens, tokens);
+    assert_eq!(decoded.decoded_as, DecodedAs::Event);
+    assert!(event.encode(&[]).is_err());
+
+    let (id, topics) = event.topics();
+    assert_eq!(id, event.get_id());
+    assert_eq!(topics.len(), 1);
+    assert_eq!(topics[0].0, "a");
+    assert_eq!((topics[0].1)(&tokens), Some(tokens[0].value.clone()));
+    assert_eq!((topics[0].1)(&[]), None);
+
+    let filter = EventFilter::new("input");
+    assert!(filter.matches(&decoded));
+    assert!(filter
+        .clone()
+        .where_eq("a", TokenValue::Uint(crate::Uint::new(7, 64)))
+        .matches(&decoded));
+    assert!(!filter
+        .where_eq("a", TokenValue::Uint(crate::Uint::new(8, 64)))
+        .matches(&decoded));
+    assert!(!EventFilter::new("no_input").matches(&decoded));
+}
+
+#[test]
+fn test_decode_bounced() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+
+    let bounce = |body: ever_block::BuilderData| {
+        let mut slice = SliceData::load_builder(body).unwrap();
+        let bits = slice.remaining_bits().min(256);
+        let mut bounced = ever_block::BuilderData::new();
+        bounced.append_u32(crate::contract::BOUNCE_PREFIX).unwrap();
+        bounced
+            .append_raw(&slice.get_next_bits(bits).unwrap(), bits)
+            .unwrap();
+        SliceData::load_builder(bounced).unwrap()
+    };
+
+    let function = contract.function("input_and_output").unwrap();
+    let input = [
+        Token::new("a", TokenValue::Uint(crate::Uint::new(7, 64))),
+        Token::new("b", TokenValue::Array(ParamType::Uint(8), vec![])),
+        Token::new("c", TokenValue::Bytes(vec![1, 2, 3])),
+    ];
+    let body = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let bounced = contract.decode_bounced(bounce(body)).unwrap();
+    assert_eq!(bounced.function_name, "input_and_output");
+    assert_eq!(bounced.tokens, input[..2]);
+    assert!(bounced.truncated);
+
+    let function = contract.function("no_output").unwrap();
+    let input = [Token::new("a", TokenValue::Uint(crate::Uint::new(5, 15)))];
+    let body = function
+        .encode_input(&HashMap::new(), &input, true, None, None)
+        .unwrap();
+    let bounced = contract.decode_bounced(bounce(body.clone())).unwrap();
+    assert_eq!(bounced.tokens, input);
+    assert!(!bounced.truncated);
+
+    assert!(contract
+        .decode_bounced(SliceData::load_builder(body).unwrap())
+        .is_err());
+}
+
+#[test]
+fn test_data_keys() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    assert_eq!(contract.data_keys(), BTreeMap::from([(100, "a")]));
+
+    const ITEM: &str = r#"{"key":100,"name":"a","type":"uint256"}"#;
+    let load = |data: &str| Contract::load(TEST_ABI.replace(ITEM, data).as_bytes());
+
+    let contract = load(&format!(r#"{}, {{"key":1,"name":"b","type":"uint8"}}"#, ITEM)).unwrap();
+    assert_eq!(contract.data_keys(), BTreeMap::from([(1, "b"), (100, "a")]));
+
+    // key 0 is reserved for public key
+    assert!(load(r#"{"key":0,"name":"a","type":"uint256"}"#).is_err());
+    assert!(load(&format!(r#"{}, {{"key":100,"name":"b","type":"uint8"}}"#, ITEM)).is_err());
+    assert!(load(&format!(r#"{}, {{"key":101,"name":"a","type":"uint8"}}"#, ITEM)).is_err());
+}
+
+#[test]
+fn test_decode_output_errors() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("no_input").unwrap();
+
+    let mut body = ever_block::BuilderData::new();
+    body.append_u32(function.get_output_id()).unwrap();
+    body.append_u8(5).unwrap();
+    let decoded = contract
+        .decode_output(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap();
+    assert_eq!(decoded.decoded_as, DecodedAs::FunctionOutput);
+    assert_eq!(
+        decoded.tokens,
+        vec![Token::new("a", TokenValue::Uint(crate::Uint::new(5, 8)))]
+    );
+
+    let mut body = ever_block::BuilderData::new();
+    body.append_u32(function.get_output_id()).unwrap();
+    let err = contract
+        .decode_output(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AbiError>(),
+        Some(AbiError::MatchedItemDecodeFailed { name, .. }) if name == "no_input"
+    ));
+
+    let mut body = ever_block::BuilderData::new();
+    body.append_u32(0xdeadbeef).unwrap();
+    let err = contract
+        .decode_output(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AbiError>(),
+        Some(AbiError::InvalidFunctionId { id: 0xdeadbeef })
+    ));
+
+    let body = function
+        .encode_input(&HashMap::new(), &[], true, None, None)
+        .unwrap();
+    let decoded = contract
+        .decode_input(SliceData::load_builder(body).unwrap(), true, false)
+        .unwrap();
+    assert_eq!(decoded.decoded_as, DecodedAs::FunctionInput);
+}
+
+#[test]
+fn test_declaration_order() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let functions: Vec<&str> = contract.functions().keys().map(String::as_str).collect();
+    assert_eq!(
+        functions,
+        vec!["input_and_output", "no_output", "no_input", "constructor", "has_id"]
+    );
+    let events: Vec<&str> = contract.events().keys().map(String::as_str).collect();
+    assert_eq!(events, vec!["input", "no_input", "has_id"]);
+
+    const PART_ABI: &str = r#"
+    {
+        "version": "2.4",
+        "header": ["time", "expire", "pubkey", {"name": "a", "type": "uint64"}],
+        "functions": [
+            {"name": "z_last", "inputs": [], "outputs": []},
+            {"name": "a_first", "inputs": [], "outputs": []}
+        ]
+    }"#;
+    let part = Contract::load(PART_ABI.as_bytes()).unwrap();
+    let merged = Contract::merge(&[contract, part]).unwrap();
+    let functions: Vec<&str> = merged.functions().keys().map(String::as_str).collect();
+    assert_eq!(functions[5..], ["z_last", "a_first"]);
+}
+
+#[test]
+fn test_function_keys() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let function = contract.function("has_id").unwrap();
+    assert_eq!(function.id(), FunctionId(0x01234567));
+    assert_eq!(FunctionId::from(function), function.id());
+    assert_eq!(contract.function_by_key(function.id()).unwrap(), function);
+    assert!(contract.function_by_key(FunctionId(0)).is_err());
+
+    let routes: HashMap<FunctionId, &str> = contract
+        .functions()
+        .values()
+        .map(|function| (function.id(), function.name.as_str()))
+        .collect();
+    assert_eq!(routes.len(), contract.functions().len());
+    assert_eq!(routes[&FunctionId(0x01234567)], "has_id");
+
+    let functions: std::collections::HashSet<&Function> =
+        contract.functions().values().map(AsRef::as_ref).collect();
+    assert!(functions.contains(function));
+    let events: std::collections::HashSet<&Event> =
+        contract.events().values().map(AsRef::as_ref).collect();
+    assert!(events.contains(contract.event("has_id").unwrap()));
+}
+
+#[test]
+fn test_shared_clone() {
+    let contract = Contract::load(TEST_ABI.as_bytes()).unwrap();
+    let mut clone = contract.clone();
+    assert!(std::ptr::eq(contract.functions(), clone.functions()));
+    assert!(std::ptr::eq(contract.events(), clone.events()));
+    assert!(std::ptr::eq(contract.data(), clone.data()));
+    assert!(std::ptr::eq(contract.fields(), clone.fields()));
+
+    clone.functions_mut().shift_remove("has_id");
+    assert!(clone.function("has_id").is_err());
+    assert!(contract.function("has_id").is_ok());
+    assert!(!std::ptr::eq(contract.functions(), clone.functions()));
+    assert!(std::ptr::eq(contract.events(), clone.events()));
+
+    clone.events_mut().clear();
+    clone.data_mut().clear();
+    assert!(clone.events().is_empty() && clone.data().is_empty());
+    assert_eq!(contract.events().len(), 3);
+    assert_eq!(contract.data().len(), 1);
+}
+
+#[test]
+fn test_never_signed_function() {
+    let abi = r#"{
+        "version": "2.4",
+        "header": ["time", "expire"],
+        "functions": [
+            {"name": "call", "inputs": [{"name": "a", "type": "uint32"}], "outputs": []},
+            {
+                "name": "system",
+                "signed": false,
+                "inputs": [{"name": "a", "type": "uint32"}],
+                "outputs": []
+            }
+        ]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    assert!(contract.function("call").unwrap().signed);
+    let system = contract.function("system").unwrap();
+    assert!(!system.signed);
+
+    let header = HashMap::from([
+        ("time".to_owned(), TokenValue::Time(123)),
+        ("expire".to_owned(), TokenValue::Expire(456)),
+    ]);
+    let input = [Token::new("a", TokenValue::Uint(crate::Uint::new(7, 32)))];
+    let body = system.encode_input(&header, &input, false, None, None).unwrap();
+    let signed_body = contract
+        .function("call")
+        .unwrap()
+        .encode_input(&header, &input, false, None, None)
+        .unwrap();
+    // signature slot is omitted, so the body starts with header
+    assert_eq!(body.length_in_bits() + 1, signed_body.length_in_bits());
+    let mut slice = SliceData::load_builder(body).unwrap();
+    assert_eq!(slice.clone().get_next_u64().unwrap(), 123);
+
+    let decoded = contract.decode_input(slice.clone(), false, false).unwrap();
+    assert_eq!(decoded.function_name, "system");
+    assert_eq!(decoded.tokens, input);
+    let decoded =
+        contract.decode_input(SliceData::load_builder(signed_body).unwrap(), false, false).unwrap();
+    assert_eq!(decoded.function_name, "call");
+
+    let peek = contract.peek(slice.clone()).unwrap();
+    assert_eq!(peek.function_id, system.get_input_id());
+    assert!(!peek.has_signature);
+    assert_eq!(peek.expire, Some(456));
+
+    let key = ever_block::ed25519_generate_private_key().unwrap();
+    assert!(system.encode_input(&header, &input, false, Some(&key), None).is_err());
+    slice.move_by(1).unwrap();
+    assert!(contract.decode_input(slice, false, false).is_err());
+
+    let abi = r#"{"ABI version": 1, "functions": [{"name": "f", "signed": false}]}"#;
+    assert!(Contract::load(abi.as_bytes()).is_err());
+}
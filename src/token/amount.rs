@@ -0,0 +1,73 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Decimal coin amounts representation.
+
+use crate::param_type::ParamType;
+
+use num_bigint::{BigInt, Sign};
+
+/// Decimal representation of `varuint16`/`varint16` coin amounts in JSON, e.g. `12.5`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AmountFormat {
+    /// Number of fractional decimal digits
+    pub decimals: u32,
+    /// Names of parameters treated as amounts. If empty, all `varuint16` and `varint16`
+    /// parameters are treated as amounts
+    pub names: Vec<String>,
+}
+
+impl AmountFormat {
+    /// Check if parameter should be represented as decimal amount
+    pub fn applies_to(&self, param_type: &ParamType, name: &str) -> bool {
+        matches!(param_type, ParamType::VarUint(16) | ParamType::VarInt(16))
+            && (self.names.is_empty() || self.names.iter().any(|item| item == name))
+    }
+
+    /// Parses decimal string into integer number of minimal units
+    pub fn parse(&self, string: &str) -> Option<BigInt> {
+        let decimals = self.decimals as usize;
+        let (negative, string) = match string.strip_prefix('-') {
+            Some(stripped) => (true, stripped),
+            None => (false, string),
+        };
+        let (int_part, frac_part) = string.split_once('.').unwrap_or((string, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || frac_part.len() > decimals
+            || !int_part.chars().chain(frac_part.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let digits = format!("{}{:0<width$}", int_part, frac_part, width = decimals);
+        let number = BigInt::parse_bytes(digits.as_bytes(), 10)?;
+        Some(if negative { -number } else { number })
+    }
+
+    /// Formats integer number of minimal units as decimal string
+    pub fn format(&self, number: &BigInt) -> String {
+        let decimals = self.decimals as usize;
+        let digits = format!(
+            "{:0>width$}",
+            number.magnitude().to_str_radix(10),
+            width = decimals + 1
+        );
+        let (int_part, frac_part) = digits.split_at(digits.len() - decimals);
+        let frac_part = frac_part.trim_end_matches('0');
+        let sign = if number.sign() == Sign::Minus { "-" } else { "" };
+        if frac_part.is_empty() {
+            format!("{}{}", sign, int_part)
+        } else {
+            format!("{}{}.{}", sign, int_part, frac_part)
+        }
+    }
+}
@@ -12,7 +12,7 @@
 */
 
 use crate::{
-    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_2, ABI_VERSION_2_4},
+    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0},
     error::AbiError,
     int::{Int, Uint},
     param::Param,
@@ -29,6 +29,11 @@ use ever_block::{
     error, fail, BuilderData, Cell, HashmapE, HashmapType, IBitstring, Result, SliceData,
 };
 
+// Value may be placed into the next cell only if it does not fit into the current one. The last
+// reference of the current cell is reserved for the chain unless the value is the last one.
+const NEXT_CELL_RULE: &str =
+    "value is placed into the next cell although it fits into the current one";
+
 #[derive(Clone, Debug, Default)]
 pub struct Cursor {
     pub used_bits: usize,
@@ -42,14 +47,58 @@ impl From<SliceData> for Cursor {
     }
 }
 
+/// Handling of `string` values which are not valid UTF-8
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Utf8Handling {
+    /// Fail decoding
+    #[default]
+    Strict,
+    /// Replace invalid sequences with `U+FFFD REPLACEMENT CHARACTER`
+    Lossy,
+    /// Decode value as `bytes`
+    AsBytes,
+}
+
+/// Decoding settings threaded through nested reads
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ReadOptions {
+    pub utf8: Utf8Handling,
+    /// Verify that values are placed into cells as ABI 2.2+ deterministic layout requires
+    pub check_layout: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self { utf8: Utf8Handling::Strict, check_layout: true }
+    }
+}
+
 impl TokenValue {
     /// Deserializes value from `SliceData` to `TokenValue`
-    fn read_from(
+    pub(crate) fn read_from(
+        param_type: &ParamType,
+        cursor: Cursor,
+        last: bool,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+    ) -> Result<(Self, Cursor)> {
+        Self::read_value(
+            param_type,
+            cursor,
+            last,
+            abi_version,
+            allow_partial,
+            ReadOptions::default(),
+        )
+    }
+
+    pub(crate) fn read_value(
         param_type: &ParamType,
         mut cursor: Cursor,
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, Cursor)> {
         let slice = cursor.slice.clone();
         let (value, slice) = match param_type {
@@ -62,18 +111,23 @@ impl TokenValue {
                 Ok((TokenValue::Bool(slice.get_next_bit()?), slice))
             }
             ParamType::Tuple(tuple_params) => {
-                return Self::read_tuple(tuple_params, cursor, last, abi_version, allow_partial);
+                return Self::read_tuple(
+                    tuple_params, cursor, last, abi_version, allow_partial, options
+                );
             }
             ParamType::Array(item_type) => {
-                Self::read_array(&item_type, slice, abi_version, allow_partial)
+                Self::read_array(&item_type, slice, abi_version, allow_partial, options)
             }
             ParamType::FixedArray(item_type, size) => {
-                Self::read_fixed_array(&item_type, *size, slice, abi_version, allow_partial)
+                Self::read_fixed_array(
+                    &item_type, *size, slice, abi_version, allow_partial, options
+                )
             }
             ParamType::Cell => Self::read_cell(slice, last, abi_version)
                 .map(|(cell, slice)| (TokenValue::Cell(cell), slice)),
+            ParamType::Slice => Self::read_slice(slice),
             ParamType::Map(key_type, value_type) => {
-                Self::read_hashmap(key_type, value_type, slice, abi_version, allow_partial)
+                Self::read_hashmap(key_type, value_type, slice, abi_version, allow_partial, options)
             }
             ParamType::Address => {
                 let mut slice = find_next_bits(slice, 1)?;
@@ -83,7 +137,7 @@ impl TokenValue {
             }
             ParamType::Bytes => Self::read_bytes(slice, last, abi_version),
             ParamType::FixedBytes(size) => Self::read_fixed_bytes(*size, slice, last, abi_version),
-            ParamType::String => Self::read_string(slice, last, abi_version),
+            ParamType::String => Self::read_string(slice, last, abi_version, options),
             ParamType::Token => {
                 let mut slice = find_next_bits(slice, 1)?;
                 let gram = <Grams as ever_block::Deserializable>::construct_from(&mut slice)?;
@@ -93,10 +147,10 @@ impl TokenValue {
             ParamType::Expire => Self::read_expire(slice),
             ParamType::PublicKey => Self::read_public_key(slice),
             ParamType::Optional(inner_type) => {
-                Self::read_optional(&inner_type, slice, last, abi_version, allow_partial)
+                Self::read_optional(&inner_type, slice, last, abi_version, allow_partial, options)
             }
             ParamType::Ref(inner_type) => {
-                Self::read_ref(&inner_type, slice, last, abi_version, allow_partial)
+                Self::read_ref(&inner_type, slice, last, abi_version, allow_partial, options)
             }
         }?;
 
@@ -104,7 +158,9 @@ impl TokenValue {
             Self::check_full_decode(allow_partial, &slice)?;
         }
 
-        cursor = Self::check_layout(param_type, cursor, &slice, abi_version, last)?;
+        if options.check_layout {
+            cursor = Self::check_layout(param_type, cursor, &slice, abi_version, last)?;
+        }
         cursor.slice = slice;
 
         Ok((value, cursor))
@@ -120,26 +176,38 @@ impl TokenValue {
         let mut cursor = original_cursor;
         let new_cell = new_slice.cell_opt();
         let orig_cell = cursor.slice.cell_opt();
-        if abi_version >= &ABI_VERSION_2_2 {
-            let param_max_bits = Self::max_bit_size(param_type, abi_version);
-            let param_max_refs = Self::max_refs_count(param_type, abi_version);
+        if abi_version.at_least(2, 2) {
+            let param_max_bits = param_type.max_bit_size(abi_version);
+            let param_max_refs = param_type.max_refs_count(abi_version);
+            let layout_error = |rule, cursor: &Cursor| AbiError::WrongDataLayout {
+                param: String::new(),
+                rule,
+                version: *abi_version,
+                used_bits: cursor.used_bits,
+                used_refs: cursor.used_refs,
+                value_bits: param_max_bits,
+                value_refs: param_max_refs,
+            };
             if new_cell != orig_cell {
                 if  cursor.used_bits + param_max_bits <= BuilderData::bits_capacity() && 
                     (last && cursor.used_refs + param_max_refs <= BuilderData::references_capacity() ||
                     !last && cursor.used_refs + param_max_refs <= BuilderData::references_capacity() - 1)
                 {
-                    fail!(AbiError::WrongDataLayout);
+                    fail!(layout_error(NEXT_CELL_RULE, &cursor));
                 }
                 cursor.used_bits = param_max_bits;
                 cursor.used_refs = param_max_refs;
             } else {
-                cursor.used_bits += param_max_bits;
-                cursor.used_refs += param_max_refs;
-                if  cursor.used_bits > BuilderData::bits_capacity() ||
-                    cursor.used_refs > BuilderData::references_capacity()
+                if  cursor.used_bits + param_max_bits > BuilderData::bits_capacity() ||
+                    cursor.used_refs + param_max_refs > BuilderData::references_capacity()
                 {
-                    fail!(AbiError::WrongDataLayout);
+                    fail!(layout_error(
+                        "value which may not fit into the current cell must start the next one",
+                        &cursor
+                    ));
                 }
+                cursor.used_bits += param_max_bits;
+                cursor.used_refs += param_max_refs;
             }
         } else {
             if new_cell != orig_cell {
@@ -158,7 +226,15 @@ impl TokenValue {
                     (last && param_refs + orig_cell.references_count() <= BuilderData::references_capacity() ||
                     (!last || abi_version == &ABI_VERSION_1_0) && param_refs + orig_cell.references_count() <= BuilderData::references_capacity() - 1)
                 {
-                    fail!(AbiError::WrongDataLayout);
+                    fail!(AbiError::WrongDataLayout {
+                        param: String::new(),
+                        rule: NEXT_CELL_RULE,
+                        version: *abi_version,
+                        used_bits: orig_cell.bit_length(),
+                        used_refs: orig_cell.references_count(),
+                        value_bits: param_bits,
+                        value_refs: param_refs,
+                    });
                 }
             }
         }
@@ -188,9 +264,25 @@ impl TokenValue {
         Ok((TokenValue::Int(Int { number, size }), cursor))
     }
 
-    fn read_varuint(size: usize, cursor: SliceData) -> Result<(Self, SliceData)> {
+    // length prefix can encode values larger than `size - 1` bytes allowed for the type
+    fn read_varint_len(size: usize, cursor: SliceData) -> Result<(usize, SliceData)> {
         let (len, cursor) = Self::read_uint_from_chain(TokenValue::varint_size_len(size), cursor)?;
         let len = len.to_usize().unwrap();
+        if len >= size {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Length prefix {} exceeds maximum length {} of varint{} value",
+                    len,
+                    size - 1,
+                    size
+                )
+            });
+        }
+        Ok((len, cursor))
+    }
+
+    fn read_varuint(size: usize, cursor: SliceData) -> Result<(Self, SliceData)> {
+        let (len, cursor) = Self::read_varint_len(size, cursor)?;
         if len == 0 {
             Ok((TokenValue::VarUint(size, 0u32.into()), cursor))
         } else {
@@ -200,8 +292,7 @@ impl TokenValue {
     }
 
     fn read_varint(size: usize, cursor: SliceData) -> Result<(Self, SliceData)> {
-        let (len, cursor) = Self::read_uint_from_chain(TokenValue::varint_size_len(size), cursor)?;
-        let len = len.to_usize().unwrap();
+        let (len, cursor) = Self::read_varint_len(size, cursor)?;
         if len == 0 {
             Ok((TokenValue::VarInt(size, 0.into()), cursor))
         } else {
@@ -216,9 +307,10 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, Cursor)> {
-        let (tokens, cursor) = Self::decode_params_with_cursor(
-            tuple_params, cursor, abi_version, allow_partial, last
+        let (tokens, cursor) = Self::decode_params_with_options(
+            tuple_params, cursor, abi_version, allow_partial, options, last
         )?;
         Ok((TokenValue::Tuple(tokens), cursor))
     }
@@ -239,6 +331,7 @@ impl TokenValue {
         size: usize,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Vec<Self>, SliceData)> {
         let original = cursor.clone();
         cursor = find_next_bits(cursor, 1)?;
@@ -257,16 +350,17 @@ impl TokenValue {
                 Ok(Some(mut item_slice)) => {
                     let do_load_ref = 
                         if abi_version == &ABI_VERSION_1_0 || abi_version == &ABI_VERSION_2_0 {
-                            item_slice.remaining_bits() == 0 && Self::max_bit_size(item_type, abi_version) != 0
+                            item_slice.remaining_bits() == 0 && item_type.max_bit_size(abi_version) != 0
                         } else {
-                            let value_len = Self::max_bit_size(item_type, abi_version);
+                            let value_len = item_type.max_bit_size(abi_version);
                             Self::map_value_in_ref(32, value_len)
                         };
                     if do_load_ref  {
                         item_slice = SliceData::load_cell(item_slice.checked_drain_reference()?)?;
                     }
-                    let (token, _) =
-                        Self::read_from(item_type, item_slice.into(), true, abi_version, allow_partial)?;
+                    let (token, _) = Self::read_value(
+                        item_type, item_slice.into(), true, abi_version, allow_partial, options
+                    )?;
                     result.push(token);
                 }
                 _ => fail!(AbiError::DeserializationError {
@@ -284,6 +378,7 @@ impl TokenValue {
         mut cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
         cursor = find_next_bits(cursor, 32)?;
         let size = cursor.get_next_u32()?;
@@ -293,6 +388,7 @@ impl TokenValue {
             size as usize,
             abi_version,
             allow_partial,
+            options,
         )?;
 
         Ok((TokenValue::Array(item_type.clone(), result), cursor))
@@ -304,9 +400,12 @@ impl TokenValue {
         cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
         let (result, cursor) =
-            Self::read_array_from_map(item_type, cursor, size, abi_version, allow_partial)?;
+            Self::read_array_from_map(
+                item_type, cursor, size, abi_version, allow_partial, options
+            )?;
 
         Ok((TokenValue::FixedArray(item_type.clone(), result), cursor))
     }
@@ -332,29 +431,28 @@ impl TokenValue {
     fn read_hashmap(
         key_type: &ParamType,
         value_type: &ParamType,
-        mut cursor: SliceData,
+        cursor: SliceData,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
-        let bit_len = TokenValue::get_map_key_size(key_type)?;
-        let value_len = Self::max_bit_size(value_type, abi_version);
-        let value_in_ref = Self::map_value_in_ref(bit_len, value_len);
+        let (hashmap, cursor) = Self::read_dictionary(key_type, cursor)?;
+        let value_in_ref = Self::map_value_in_ref(
+            hashmap.bit_len(),
+            value_type.max_bit_size(abi_version),
+        );
 
-        cursor = find_next_bits(cursor, 1)?;
         let mut new_map = BTreeMap::new();
-        let hashmap = HashmapE::with_hashmap(bit_len, cursor.get_dictionary()?.reference_opt(0));
-        hashmap.iterate_slices(|key, mut value| {
-            let key = Self::read_from(key_type, key.into(), true, abi_version, allow_partial)?.0;
-            let key = serde_json::to_value(&key)?
-                .as_str()
-                .ok_or(AbiError::InvalidData {
-                    msg: "Non-ordinary key".to_owned(),
-                })?
-                .to_owned();
-            if value_in_ref {
-                value = SliceData::load_cell(value.checked_drain_reference()?)?;
-            }
-            let value = Self::read_from(value_type, value.into(), true, abi_version, allow_partial)?.0;
+        hashmap.iterate_slices(|key, value| {
+            let (key, value) = Self::read_map_entry(
+                key_type,
+                value_type,
+                value_in_ref,
+                (key, value),
+                abi_version,
+                allow_partial,
+                options,
+            )?;
             new_map.insert(key, value);
             Ok(true)
         })?;
@@ -364,6 +462,77 @@ impl TokenValue {
         ))
     }
 
+    fn read_dictionary(key_type: &ParamType, cursor: SliceData) -> Result<(HashmapE, SliceData)> {
+        let bit_len = TokenValue::get_map_key_size(key_type)?;
+        let mut cursor = find_next_bits(cursor, 1)?;
+        let hashmap = HashmapE::with_hashmap(bit_len, cursor.get_dictionary()?.reference_opt(0));
+        Ok((hashmap, cursor))
+    }
+
+    /// Reads dictionary of `map` parameter without decoding its entries. Entries are decoded
+    /// separately with `read_map_entry`.
+    pub(crate) fn read_map_dictionary(
+        param_type: &ParamType,
+        mut cursor: Cursor,
+        last: bool,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        options: ReadOptions,
+    ) -> Result<(HashmapE, Cursor)> {
+        let key_type = match param_type {
+            ParamType::Map(key_type, _) => key_type,
+            _ => fail!(AbiError::WrongParameterType),
+        };
+        let (hashmap, slice) = Self::read_dictionary(key_type, cursor.slice.clone())?;
+        if last {
+            Self::check_full_decode(allow_partial, &slice)?;
+        }
+        if options.check_layout {
+            cursor = Self::check_layout(param_type, cursor, &slice, abi_version, last)?;
+        }
+        cursor.slice = slice;
+        Ok((hashmap, cursor))
+    }
+
+    pub(crate) fn read_map_entry(
+        key_type: &ParamType,
+        value_type: &ParamType,
+        value_in_ref: bool,
+        (key, mut value): (SliceData, SliceData),
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        options: ReadOptions,
+    ) -> Result<(String, TokenValue)> {
+        let key =
+            Self::read_value(key_type, key.into(), true, abi_version, allow_partial, options)?.0;
+        let key = serde_json::to_value(&key)?
+            .as_str()
+            .ok_or(AbiError::InvalidData {
+                msg: "Non-ordinary key".to_owned(),
+            })?
+            .to_owned();
+        if value_in_ref {
+            value = SliceData::load_cell(value.checked_drain_reference()?)?;
+        }
+        let value = Self::read_value(
+            value_type, value.into(), true, abi_version, allow_partial, options
+        )?.0;
+        Ok((key, value))
+    }
+
+    fn read_slice(cursor: SliceData) -> Result<(Self, SliceData)> {
+        let mut cursor = find_next_bits(cursor, super::SLICE_LENGTH_BITS)?;
+        let len = cursor.get_next_int(super::SLICE_LENGTH_BITS)? as usize;
+        if len > super::MAX_SLICE_BITS {
+            fail!(AbiError::DeserializationError {
+                msg: "Slice value length exceeds maximum",
+                cursor
+            });
+        }
+        let value = cursor.get_next_slice(len)?;
+        Ok((TokenValue::Slice(value), cursor))
+    }
+
     fn read_bytes_from_chain(
         cursor: SliceData,
         last: bool,
@@ -396,7 +565,7 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
     ) -> Result<(Self, SliceData)> {
-        if abi_version >= &ABI_VERSION_2_4 {
+        if abi_version.at_least(2, 4) {
             let (data, cursor) = get_next_bits_from_chain(cursor, size * 8)?;
             Ok((TokenValue::FixedBytes(data), cursor))
         } else {
@@ -429,13 +598,23 @@ impl TokenValue {
         cursor: SliceData,
         last: bool,
         abi_version: &AbiVersion,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
         let (data, cursor) = Self::read_bytes_from_chain(cursor, last, abi_version)?;
 
-        let string = String::from_utf8(data).map_err(|err| AbiError::InvalidData {
-            msg: format!("Can not deserialize string: {}", err),
-        })?;
-        Ok((TokenValue::String(string), cursor))
+        let value = match String::from_utf8(data) {
+            Ok(string) => TokenValue::String(string),
+            Err(err) => match options.utf8 {
+                Utf8Handling::Strict => fail!(AbiError::InvalidData {
+                    msg: format!("Can not deserialize string: {}", err),
+                }),
+                Utf8Handling::Lossy => {
+                    TokenValue::String(String::from_utf8_lossy(err.as_bytes()).into_owned())
+                }
+                Utf8Handling::AsBytes => TokenValue::Bytes(err.into_bytes()),
+            },
+        };
+        Ok((value, cursor))
     }
 
     fn read_time(mut cursor: SliceData) -> Result<(Self, SliceData)> {
@@ -467,25 +646,28 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
         let mut cursor = find_next_bits(cursor, 1)?;
         if cursor.get_next_bit()? {
-            if Self::is_large_optional(inner_type, abi_version) {
+            if inner_type.is_large_optional_value(abi_version) {
                 let cell = cursor.checked_drain_reference()?;
-                let (result, _) = Self::read_from(
+                let (result, _) = Self::read_value(
                     inner_type,
                     SliceData::load_cell(cell)?.into(),
                     true,
                     abi_version,
                     allow_partial,
+                    options,
                 )?;
                 Ok((
                     TokenValue::Optional(inner_type.clone(), Some(Box::new(result))),
                     cursor,
                 ))
             } else {
-                let (result, cursor) =
-                    Self::read_from(inner_type, cursor.into(), last, abi_version, allow_partial)?;
+                let (result, cursor) = Self::read_value(
+                    inner_type, cursor.into(), last, abi_version, allow_partial, options
+                )?;
                 Ok((
                     TokenValue::Optional(inner_type.clone(), Some(Box::new(result))),
                     cursor.slice,
@@ -502,14 +684,16 @@ impl TokenValue {
         last: bool,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
     ) -> Result<(Self, SliceData)> {
         let (cell, cursor) = Self::read_cell(cursor, last, abi_version)?;
-        let (result, _) = Self::read_from(
+        let (result, _) = Self::read_value(
             inner_type,
             SliceData::load_cell(cell)?.into(),
             true,
             abi_version,
             allow_partial,
+            options,
         )?;
         Ok((TokenValue::Ref(Box::new(result)), cursor))
     }
@@ -525,11 +709,44 @@ impl TokenValue {
             .map(|(tokens, _)| tokens)
     }
 
+    /// Decodes provided params from SliceData handling invalid UTF-8 strings as specified
+    pub fn decode_params_with_utf8_handling(
+        params: &[Param],
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        utf8: Utf8Handling,
+    ) -> Result<Vec<Token>> {
+        let options = ReadOptions { utf8, ..Default::default() };
+        Self::decode_params_with_options(
+            params, cursor.into(), abi_version, allow_partial, options, true
+        )
+        .map(|(tokens, _)| tokens)
+    }
+
     pub fn decode_params_with_cursor(
+        params: &[Param],
+        cursor: Cursor,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        last: bool,
+    ) -> Result<(Vec<Token>, Cursor)> {
+        Self::decode_params_with_options(
+            params,
+            cursor,
+            abi_version,
+            allow_partial,
+            ReadOptions::default(),
+            last,
+        )
+    }
+
+    pub(crate) fn decode_params_with_options(
         params: &[Param],
         mut cursor: Cursor,
         abi_version: &AbiVersion,
         allow_partial: bool,
+        options: ReadOptions,
         last: bool,
     ) -> Result<(Vec<Token>, Cursor)> {
         let mut tokens = vec![];
@@ -538,7 +755,8 @@ impl TokenValue {
             // println!("{:?}", param);
             let last = Some(param) == params.last() && last;
             let (token_value, new_cursor) =
-                Self::read_from(&param.kind, cursor, last, abi_version, allow_partial)?;
+                Self::read_value(&param.kind, cursor, last, abi_version, allow_partial, options)
+                    .map_err(|err| AbiError::prepend_layout_param(err, &param.name))?;
 
             cursor = new_cursor;
             tokens.push(Token {
@@ -556,7 +774,7 @@ fn get_next_bits_from_chain(mut cursor: SliceData, bits: usize) -> Result<(Vec<u
     Ok((cursor.get_next_bits(bits)?, cursor))
 }
 
-fn find_next_bits(mut cursor: SliceData, bits: usize) -> Result<SliceData> {
+pub(crate) fn find_next_bits(mut cursor: SliceData, bits: usize) -> Result<SliceData> {
     debug_assert!(bits != 0);
     let original = cursor.clone();
     if cursor.remaining_bits() == 0 {
@@ -13,26 +13,147 @@
 
 use crate::{
     param_type::ParamType,
-    token::{Token, TokenValue},
-    PublicKeyData,
+    token::{AmountFormat, Token, TokenValue},
+    Int, PublicKeyData,
 };
 
-use num_bigint::{BigInt, BigUint};
-use serde::ser::{Serialize, SerializeMap, Serializer};
+use chrono::{SecondsFormat, TimeZone, Utc};
+use num_bigint::{BigInt, BigUint, Sign};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
 use std::collections::{BTreeMap, HashMap};
-use ever_block::{base64_encode, write_boc, Cell, Result};
+use std::fmt;
+use std::sync::Arc;
+use ever_block::{
+    base64_encode, sha256_digest, write_boc, Cell, MsgAddress, Result, SliceData,
+};
+
+/// Radix of integer values in JSON output
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IntFormat {
+    /// `uint256` values are encoded as `0x`-prefixed hex, all other integers - as decimal
+    #[default]
+    Auto,
+    /// All integers are encoded as decimal
+    Dec,
+    /// All integers are encoded as `0x`-prefixed hex. Fixed size unsigned integers are padded
+    /// with zeros to their full size
+    Hex,
+}
+
+/// Renders decoded addresses, e.g. in user-friendly form or as resolved labels
+pub trait AddressFormatter: Send + Sync {
+    fn format(&self, address: &MsgAddress) -> String;
+}
+
+impl<F> AddressFormatter for F
+where
+    F: Fn(&MsgAddress) -> String + Send + Sync,
+{
+    fn format(&self, address: &MsgAddress) -> String {
+        self(address)
+    }
+}
+
+/// Shared `AddressFormatter` used in `DetokenizeOptions`. Formats are equal only if they share
+/// the same formatter instance.
+#[derive(Clone)]
+pub struct AddressFormat(Arc<dyn AddressFormatter>);
+
+impl AddressFormat {
+    pub fn new<F: AddressFormatter + 'static>(formatter: F) -> Self {
+        Self(Arc::new(formatter))
+    }
+
+    pub fn format(&self, address: &MsgAddress) -> String {
+        self.0.format(address)
+    }
+}
+
+impl fmt::Debug for AddressFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AddressFormat")
+    }
+}
+
+impl PartialEq for AddressFormat {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const u8 == Arc::as_ptr(&other.0) as *const u8
+    }
+}
+
+/// Replacement of redacted values
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RedactionMode {
+    /// Value is replaced with `"<redacted>"` string
+    #[default]
+    Redact,
+    /// Value is replaced with `"sha256:"`-prefixed hex hash of its JSON representation, so equal
+    /// values can still be matched in logs
+    Hash,
+}
+
+/// Parameters hidden from JSON output, e.g. to log decoded calls without sensitive payloads
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Redaction {
+    /// Names of redacted parameters and tuple components
+    pub names: Vec<String>,
+    /// Types of redacted values, including array items, map values and tuple components
+    pub types: Vec<ParamType>,
+    pub mode: RedactionMode,
+}
+
+impl Redaction {
+    /// Checks if value of parameter `name` should be redacted
+    pub fn applies_to(&self, name: &str, value: &TokenValue) -> bool {
+        self.names.iter().any(|item| item == name)
+            || (!self.types.is_empty() && self.types.contains(&value.get_param_type()))
+    }
+}
+
+/// Options controlling JSON representation of decoded values
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DetokenizeOptions {
+    /// Encode public keys as `0x`-prefixed hex strings
+    pub pubkey_hex_prefix: bool,
+    /// Encode absent public key as `null` instead of empty string
+    pub pubkey_none_as_null: bool,
+    /// Encode `varuint16`/`varint16` values as decimal coin amounts
+    pub amount: Option<AmountFormat>,
+    /// Flatten nested tuples into dotted keys (`"limit.value"`) and encode maps as arrays of
+    /// `{"key": ..., "value": ...}` objects
+    pub flatten: bool,
+    /// Radix of `int`/`uint`/`varint`/`varuint` values
+    pub int_format: IntFormat,
+    /// Encode `time` and `expire` values as RFC3339 UTC datetime strings
+    pub rfc3339_time: bool,
+    /// Custom rendering of `address` values. Raw `wc:hex` form is used if not set
+    pub address_format: Option<AddressFormat>,
+    /// Parameters replaced with placeholders or hashes
+    pub redaction: Option<Redaction>,
+}
 
 pub struct Detokenizer;
 
 impl Detokenizer {
     pub fn detokenize(tokens: &[Token]) -> Result<String> {
-        Ok(serde_json::to_string(&Self::detokenize_to_json_value(
-            tokens,
+        Self::detokenize_with_options(tokens, &DetokenizeOptions::default())
+    }
+
+    pub fn detokenize_with_options(tokens: &[Token], options: &DetokenizeOptions) -> Result<String> {
+        Ok(serde_json::to_string(&Self::detokenize_to_json_value_with_options(
+            tokens, options,
         )?)?)
     }
 
     pub fn detokenize_to_json_value(tokens: &[Token]) -> Result<serde_json::Value> {
-        Ok(serde_json::to_value(&FunctionParams { params: tokens })?)
+        Self::detokenize_to_json_value_with_options(tokens, &DetokenizeOptions::default())
+    }
+
+    pub fn detokenize_to_json_value_with_options(
+        tokens: &[Token],
+        options: &DetokenizeOptions,
+    ) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(&FunctionParams { params: tokens, options })?)
     }
 
     pub fn detokenize_optional(tokens: &HashMap<String, TokenValue>) -> Result<String> {
@@ -50,6 +171,7 @@ impl Detokenizer {
 
 pub struct FunctionParams<'a> {
     params: &'a [Token],
+    options: &'a DetokenizeOptions,
 }
 
 impl<'a> Serialize for FunctionParams<'a> {
@@ -57,16 +179,88 @@ impl<'a> Serialize for FunctionParams<'a> {
     where
         S: Serializer,
     {
+        if self.options.flatten {
+            let mut map = serializer.serialize_map(None)?;
+            self.serialize_flat_entries(&mut map, "", self.params)?;
+            return map.end();
+        }
+
         let mut map = serializer.serialize_map(Some(self.params.len()))?;
 
         for token in self.params {
-            map.serialize_entry(&token.name, &token.value)?;
+            map.serialize_entry(
+                &token.name,
+                &ValueSerializer { name: &token.name, value: &token.value, options: self.options },
+            )?;
         }
 
         map.end()
     }
 }
 
+impl<'a> FunctionParams<'a> {
+    fn serialize_flat_entries<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        prefix: &str,
+        tokens: &[Token],
+    ) -> std::result::Result<(), M::Error> {
+        for token in tokens {
+            let name = format!("{}{}", prefix, token.name);
+            let mut value = &token.value;
+            while let TokenValue::Ref(inner) = value {
+                value = inner;
+            }
+            let redacted = self
+                .options
+                .redaction
+                .as_ref()
+                .map_or(false, |redaction| redaction.applies_to(&token.name, value));
+            match value {
+                TokenValue::Tuple(tokens) if !redacted => {
+                    self.serialize_flat_entries(map, &format!("{}.", name), tokens)?
+                }
+                _ => map.serialize_entry(
+                    &name,
+                    &ValueSerializer { name: &token.name, value, options: self.options },
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes `TokenValue` according to provided detokenize options
+struct ValueSerializer<'a> {
+    name: &'a str,
+    value: &'a TokenValue,
+    options: &'a DetokenizeOptions,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn wrap(&self, value: &'a TokenValue) -> Self {
+        Self { name: self.name, value, options: self.options }
+    }
+}
+
+/// Map item serialized as `{"key": ..., "value": ...}` object in flattened mode
+struct MapEntry<'a> {
+    key: &'a str,
+    value: ValueSerializer<'a>,
+}
+
+impl<'a> Serialize for MapEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("key", self.key)?;
+        map.serialize_entry("value", &self.value)?;
+        map.end()
+    }
+}
+
 impl Token {
     pub fn detokenize_big_int<S>(
         number: &BigInt,
@@ -119,6 +313,23 @@ impl Token {
         map.end()
     }
 
+    /// Serializes UTC datetime as RFC3339 string with milliseconds if they are not zero
+    pub fn detokenize_datetime<S>(
+        seconds: u64,
+        nanos: u32,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let datetime = i64::try_from(seconds)
+            .ok()
+            .and_then(|seconds| Utc.timestamp_opt(seconds, nanos).single())
+            .ok_or_else(|| serde::ser::Error::custom("datetime is out of range"))?;
+        let format = if nanos == 0 { SecondsFormat::Secs } else { SecondsFormat::Millis };
+        serializer.serialize_str(&datetime.to_rfc3339_opts(format, true))
+    }
+
     pub fn detokenize_cell<S>(cell: &Cell, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -129,6 +340,16 @@ impl Token {
         serializer.serialize_str(&data)
     }
 
+    pub fn detokenize_slice<S>(slice: &SliceData, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("bits", &slice.remaining_bits())?;
+        map.serialize_entry("base64", &base64_encode(&slice.get_bytestring(0)))?;
+        map.end()
+    }
+
     pub fn detokenize_bytes<S>(arr: &[u8], serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -144,10 +365,24 @@ impl Token {
     where
         S: Serializer,
     {
-        if let Some(key) = value {
-            Self::detokenize_bytes(key, serializer)
-        } else {
-            serializer.serialize_str("")
+        Self::detokenize_public_key_with_options(value, &DetokenizeOptions::default(), serializer)
+    }
+
+    pub fn detokenize_public_key_with_options<S>(
+        value: &Option<PublicKeyData>,
+        options: &DetokenizeOptions,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(key) if options.pubkey_hex_prefix => {
+                serializer.serialize_str(&format!("0x{}", hex::encode(key)))
+            }
+            Some(key) => Self::detokenize_bytes(key, serializer),
+            None if options.pubkey_none_as_null => serializer.serialize_none(),
+            None => serializer.serialize_str(""),
         }
     }
 }
@@ -157,7 +392,81 @@ impl Serialize for TokenValue {
     where
         S: Serializer,
     {
-        match self {
+        ValueSerializer { name: "", value: self, options: &DetokenizeOptions::default() }
+            .serialize(serializer)
+    }
+}
+
+impl<'a> Serialize for ValueSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if let Some(redaction) = &self.options.redaction {
+            if redaction.applies_to(self.name, self.value) {
+                return match redaction.mode {
+                    RedactionMode::Redact => serializer.serialize_str("<redacted>"),
+                    RedactionMode::Hash => {
+                        let options = DetokenizeOptions { redaction: None, ..self.options.clone() };
+                        let json = serde_json::to_string(&ValueSerializer {
+                            name: self.name,
+                            value: self.value,
+                            options: &options,
+                        })
+                        .map_err(|err| serde::ser::Error::custom(err.to_string()))?;
+                        let hash = hex::encode(sha256_digest(json.as_bytes()));
+                        serializer.serialize_str(&format!("sha256:{}", hash))
+                    }
+                };
+            }
+        }
+
+        if let Some(format) = &self.options.amount {
+            match self.value {
+                TokenValue::VarUint(size, uint)
+                    if format.applies_to(&ParamType::VarUint(*size), self.name) =>
+                {
+                    return serializer.serialize_str(&format.format(&BigInt::from(uint.clone())));
+                }
+                TokenValue::VarInt(size, int)
+                    if format.applies_to(&ParamType::VarInt(*size), self.name) =>
+                {
+                    return serializer.serialize_str(&format.format(int));
+                }
+                _ => {}
+            }
+        }
+        match (self.value, self.options.int_format) {
+            (TokenValue::Uint(uint), IntFormat::Dec) => {
+                serializer.serialize_str(&uint.number.to_str_radix(10))
+            }
+            (TokenValue::Uint(uint), IntFormat::Hex) => serializer.serialize_str(&format!(
+                "0x{:0>width$}",
+                uint.number.to_str_radix(16),
+                width = (uint.size + 3) / 4
+            )),
+            (TokenValue::VarUint(_, uint), IntFormat::Dec) => {
+                serializer.serialize_str(&uint.to_str_radix(10))
+            }
+            (TokenValue::VarUint(_, uint), IntFormat::Hex) => {
+                serializer.serialize_str(&format!("0x{}", uint.to_str_radix(16)))
+            }
+            (TokenValue::Int(Int { number, .. }), IntFormat::Hex)
+            | (TokenValue::VarInt(_, number), IntFormat::Hex) => {
+                let sign = if number.sign() == Sign::Minus { "-" } else { "" };
+                serializer.serialize_str(&format!("{}0x{}", sign, number.magnitude().to_str_radix(16)))
+            }
+            _ => self.serialize_value(serializer),
+        }
+    }
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn serialize_value<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.value {
             TokenValue::Uint(uint) => {
                 Token::detokenize_big_uint(&uint.number, uint.size, serializer)
             }
@@ -167,27 +476,60 @@ impl Serialize for TokenValue {
             }
             TokenValue::VarInt(_, int) => Token::detokenize_big_int(&int, serializer),
             TokenValue::Bool(b) => serializer.serialize_bool(b.clone()),
-            TokenValue::Tuple(tokens) => FunctionParams { params: tokens }.serialize(serializer),
-            TokenValue::Array(_, ref tokens) => tokens.serialize(serializer),
-            TokenValue::FixedArray(_, ref tokens) => tokens.serialize(serializer),
+            TokenValue::Tuple(tokens) => {
+                FunctionParams { params: tokens, options: self.options }.serialize(serializer)
+            }
+            TokenValue::Array(_, ref tokens) | TokenValue::FixedArray(_, ref tokens) => {
+                let mut seq = serializer.serialize_seq(Some(tokens.len()))?;
+                for token in tokens {
+                    seq.serialize_element(&self.wrap(token))?;
+                }
+                seq.end()
+            }
             TokenValue::Cell(ref cell) => Token::detokenize_cell(cell, serializer),
-            TokenValue::Map(key_type, _, ref map) => {
-                Token::detokenize_hashmap(key_type, map, serializer)
+            TokenValue::Slice(ref slice) => Token::detokenize_slice(slice, serializer),
+            TokenValue::Map(_, _, ref map) if self.options.flatten => {
+                let mut seq = serializer.serialize_seq(Some(map.len()))?;
+                for (k, v) in map {
+                    seq.serialize_element(&MapEntry { key: k, value: self.wrap(v) })?;
+                }
+                seq.end()
+            }
+            TokenValue::Map(_, _, ref map) => {
+                let mut ser_map = serializer.serialize_map(Some(map.len()))?;
+                for (k, v) in map {
+                    ser_map.serialize_entry(k, &self.wrap(v))?;
+                }
+                ser_map.end()
             }
-            TokenValue::Address(ref address) => serializer.serialize_str(&address.to_string()),
+            TokenValue::Address(ref address) => match &self.options.address_format {
+                Some(format) => serializer.serialize_str(&format.format(address)),
+                None => serializer.serialize_str(&address.to_string()),
+            },
             TokenValue::Bytes(ref arr) => Token::detokenize_bytes(arr, serializer),
             TokenValue::FixedBytes(ref arr) => Token::detokenize_bytes(arr, serializer),
             TokenValue::String(string) => serializer.serialize_str(string),
             TokenValue::Token(gram) => Token::detokenize_grams(gram, serializer),
+            TokenValue::Time(time) if self.options.rfc3339_time => {
+                Token::detokenize_datetime(*time / 1000, (*time % 1000) as u32 * 1_000_000, serializer)
+            }
             TokenValue::Time(time) => {
                 Token::detokenize_big_uint(&BigUint::from(*time), 64, serializer)
             }
+            TokenValue::Expire(expire) if self.options.rfc3339_time => {
+                Token::detokenize_datetime(*expire as u64, 0, serializer)
+            }
             TokenValue::Expire(expire) => {
                 Token::detokenize_big_uint(&BigUint::from(*expire), 32, serializer)
             }
-            TokenValue::PublicKey(key) => Token::detokenize_public_key(&key, serializer),
-            TokenValue::Optional(_, value) => value.serialize(serializer),
-            TokenValue::Ref(value) => value.serialize(serializer),
+            TokenValue::PublicKey(key) => {
+                Token::detokenize_public_key_with_options(&key, self.options, serializer)
+            }
+            TokenValue::Optional(_, value) => match value {
+                Some(value) => serializer.serialize_some(&self.wrap(value)),
+                None => serializer.serialize_none(),
+            },
+            TokenValue::Ref(value) => self.wrap(value).serialize(serializer),
         }
     }
 }
@@ -0,0 +1,366 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Lazy decoding of large collections.
+
+use crate::{
+    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0},
+    error::AbiError,
+    param_type::ParamType,
+    token::{deserialize::find_next_bits, TokenValue},
+};
+
+use ever_block::{fail, BuilderData, Cell, HashmapE, HashmapType, IBitstring, Result, SliceData};
+
+/// Map decoded on demand. Entries are read from the underlying dictionary only when accessed,
+/// so large on-chain maps can be inspected without materializing them.
+#[derive(Clone, Debug)]
+pub struct LazyMap {
+    key_type: ParamType,
+    value_type: ParamType,
+    abi_version: AbiVersion,
+    map: HashmapE,
+}
+
+impl LazyMap {
+    /// Creates lazy map over the dictionary with ABI `map(key_type,value_type)` layout
+    pub fn new(
+        key_type: ParamType,
+        value_type: ParamType,
+        map: HashmapE,
+        abi_version: AbiVersion,
+    ) -> Result<Self> {
+        let bit_len = TokenValue::get_map_key_size(&key_type)?;
+        if map.bit_len() != bit_len {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Dictionary key length {} does not match map key type {}",
+                    map.bit_len(),
+                    key_type
+                )
+            });
+        }
+        Ok(Self {
+            key_type,
+            value_type,
+            abi_version,
+            map,
+        })
+    }
+
+    pub fn key_type(&self) -> &ParamType {
+        &self.key_type
+    }
+
+    pub fn value_type(&self) -> &ParamType {
+        &self.value_type
+    }
+
+    /// Returns underlying dictionary
+    pub fn hashmap(&self) -> &HashmapE {
+        &self.map
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Reads value with provided key
+    pub fn get(&self, key: &TokenValue) -> Result<Option<TokenValue>> {
+        let key = self.encode_key(key)?;
+        match self.map.get(key)? {
+            Some(value) => Ok(Some(self.decode_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns iterator over map entries in keys order. Entries are decoded while iterating.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(TokenValue, TokenValue)>> + '_ {
+        self.map.iter().map(move |item| {
+            let (key, value) = item?;
+            Ok((
+                self.decode_key(SliceData::load_builder(key)?)?,
+                self.decode_value(value)?,
+            ))
+        })
+    }
+
+    /// Reads up to `limit` entries starting from `start_key` (inclusive) or from the first entry.
+    /// Entries are ordered by key value, `int` keys are compared as signed numbers. Each entry
+    /// is found by dictionary seek, so entries before `start_key` are not visited.
+    pub fn range(&self, start_key: Option<&TokenValue>, limit: usize) -> Result<MapPage> {
+        if limit == 0 {
+            fail!(AbiError::InvalidData {
+                msg: "Map page limit must be greater than zero".to_owned()
+            });
+        }
+        let signed = matches!(self.key_type, ParamType::Int(_));
+        let mut leaf = match start_key {
+            Some(key) => self.map.find_leaf(self.encode_key(key)?, true, true, signed, &mut 0)?,
+            None => self.map.get_min(signed, &mut 0)?,
+        };
+        let mut entries = vec![];
+        while let Some((key, value)) = leaf {
+            let key = SliceData::load_builder(key)?;
+            if entries.len() == limit {
+                return Ok(MapPage {
+                    entries,
+                    next_key: Some(self.decode_key(key)?),
+                });
+            }
+            leaf = self.map.find_leaf(key.clone(), true, false, signed, &mut 0)?;
+            entries.push((self.decode_key(key)?, self.decode_value(value)?));
+        }
+        Ok(MapPage {
+            entries,
+            next_key: None,
+        })
+    }
+
+    /// Sets value for the key. Only the dictionary branch leading to the key is rebuilt.
+    pub fn insert(&mut self, key: &TokenValue, value: &TokenValue) -> Result<()> {
+        if !value.type_check(&self.value_type) {
+            fail!(AbiError::WrongParameterType);
+        }
+        let key = self.encode_key(key)?;
+        let data = value.pack_into_chain(&self.abi_version)?;
+        if self.value_in_ref() {
+            self.map.setref(key, &data.into_cell()?)?;
+        } else {
+            self.map.set_builder(key, &data)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the key from the map. Returns `true` if the key was present.
+    pub fn remove(&mut self, key: &TokenValue) -> Result<bool> {
+        let key = self.encode_key(key)?;
+        Ok(self.map.remove(key)?.is_some())
+    }
+
+    pub(crate) fn encode_key(&self, key: &TokenValue) -> Result<SliceData> {
+        if !key.type_check(&self.key_type) {
+            fail!(AbiError::WrongParameterType);
+        }
+        let mut cells = key.write_to_cells(&self.abi_version)?;
+        match cells.pop() {
+            Some(value)
+                if self.key_type == ParamType::Address
+                    && value.data.length_in_bits() != super::STD_ADDRESS_BIT_LENGTH =>
+            {
+                fail!(AbiError::InvalidData {
+                    msg: "Only std non-anycast address can be used as map key".to_owned()
+                })
+            }
+            Some(value) if cells.is_empty() => SliceData::load_builder(value.data),
+            _ => fail!(AbiError::InvalidData {
+                msg: format!("Key type {} can not be used in map", self.key_type)
+            }),
+        }
+    }
+
+    pub(crate) fn decode_key(&self, key: SliceData) -> Result<TokenValue> {
+        Ok(TokenValue::read_from(&self.key_type, key.into(), true, &self.abi_version, false)?.0)
+    }
+
+    pub(crate) fn decode_value(&self, mut value: SliceData) -> Result<TokenValue> {
+        if self.value_in_ref() {
+            value = SliceData::load_cell(value.checked_drain_reference()?)?;
+        }
+        Ok(TokenValue::read_from(
+            &self.value_type,
+            value.into(),
+            true,
+            &self.abi_version,
+            false,
+        )?
+        .0)
+    }
+
+    // values are serialized with `value_type` as is, so its size defines their placement the
+    // same way as for maps written by `TokenValue::pack_into_chain`
+    fn value_in_ref(&self) -> bool {
+        let value_len = self.value_type.max_bit_size(&self.abi_version);
+        TokenValue::map_value_in_ref(self.map.bit_len(), value_len)
+    }
+}
+
+/// Page of map entries returned by `LazyMap::range`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapPage {
+    pub entries: Vec<(TokenValue, TokenValue)>,
+    /// Key of the first entry of the next page if any
+    pub next_key: Option<TokenValue>,
+}
+
+/// Array decoded on demand. Items are read from the underlying 32-bit key dictionary only when
+/// accessed, so large arrays can be inspected without materializing them.
+#[derive(Clone, Debug)]
+pub struct LazyArray {
+    item_type: ParamType,
+    len: usize,
+    abi_version: AbiVersion,
+    map: HashmapE,
+}
+
+impl LazyArray {
+    /// Creates lazy array of `len` items over the dictionary with ABI array layout
+    pub fn new(
+        item_type: ParamType,
+        len: usize,
+        map: HashmapE,
+        abi_version: AbiVersion,
+    ) -> Result<Self> {
+        if map.bit_len() != 32 {
+            fail!(AbiError::InvalidData {
+                msg: format!("Dictionary key length {} is not valid for array", map.bit_len())
+            });
+        }
+        Ok(Self {
+            item_type,
+            len,
+            abi_version,
+            map,
+        })
+    }
+
+    pub fn item_type(&self) -> &ParamType {
+        &self.item_type
+    }
+
+    /// Returns underlying dictionary
+    pub fn hashmap(&self) -> &HashmapE {
+        &self.map
+    }
+
+    /// Returns declared number of items
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads item with provided index. Returns `None` if index is out of array bounds.
+    pub fn get(&self, index: usize) -> Result<Option<TokenValue>> {
+        if index >= self.len {
+            return Ok(None);
+        }
+        self.read_item(index).map(Some)
+    }
+
+    /// Returns iterator over array items. Items are decoded while iterating.
+    pub fn iter(&self) -> impl Iterator<Item = Result<TokenValue>> + '_ {
+        (0..self.len).map(move |index| self.read_item(index))
+    }
+
+    fn read_item(&self, index: usize) -> Result<TokenValue> {
+        let mut key = BuilderData::new();
+        key.append_u32(index as u32)?;
+        match self.map.get(SliceData::load_builder(key)?)? {
+            Some(item) => self.decode_item(item),
+            None => fail!(AbiError::InvalidData {
+                msg: format!("Array doesn't contain item with index {}", index)
+            }),
+        }
+    }
+
+    fn decode_item(&self, mut item: SliceData) -> Result<TokenValue> {
+        let value_len = self.item_type.max_bit_size(&self.abi_version);
+        let in_ref = if self.abi_version == ABI_VERSION_1_0 || self.abi_version == ABI_VERSION_2_0 {
+            item.remaining_bits() == 0 && value_len != 0
+        } else {
+            TokenValue::map_value_in_ref(32, value_len)
+        };
+        if in_ref {
+            item = SliceData::load_cell(item.checked_drain_reference()?)?;
+        }
+        Ok(TokenValue::read_from(&self.item_type, item.into(), true, &self.abi_version, false)?.0)
+    }
+}
+
+impl TokenValue {
+    /// Reads map from the cursor without decoding its entries. Returns lazy map and cursor
+    /// positioned after the map.
+    pub fn read_lazy_map(
+        key_type: &ParamType,
+        value_type: &ParamType,
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+    ) -> Result<(LazyMap, SliceData)> {
+        let bit_len = TokenValue::get_map_key_size(key_type)?;
+        let mut cursor = find_next_bits(cursor, 1)?;
+        let map = HashmapE::with_hashmap(bit_len, cursor.get_dictionary()?.reference_opt(0));
+        let map = LazyMap::new(key_type.clone(), value_type.clone(), map, *abi_version)?;
+        Ok((map, cursor))
+    }
+
+    /// Reads array or fixed array of type `param_type` from the cursor without decoding its items.
+    /// Returns lazy array and cursor positioned after the array.
+    pub fn read_lazy_array(
+        param_type: &ParamType,
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+    ) -> Result<(LazyArray, SliceData)> {
+        let (item_type, len, mut cursor) = match param_type {
+            ParamType::Array(item_type) => {
+                let mut cursor = find_next_bits(cursor, 32)?;
+                let len = cursor.get_next_u32()? as usize;
+                (item_type, len, cursor)
+            }
+            ParamType::FixedArray(item_type, len) => (item_type, *len, cursor),
+            _ => fail!(AbiError::WrongParameterType),
+        };
+        cursor = find_next_bits(cursor, 1)?;
+        let map = HashmapE::with_hashmap(32, cursor.get_dictionary()?.reference_opt(0));
+        let array = LazyArray::new(item_type.as_ref().clone(), len, map, *abi_version)?;
+        Ok((array, cursor))
+    }
+
+    /// Reads up to `limit` entries of the map starting from `start_key`. See `LazyMap::range`.
+    pub fn decode_map_range(
+        cursor: SliceData,
+        key_type: &ParamType,
+        value_type: &ParamType,
+        start_key: Option<&TokenValue>,
+        limit: usize,
+        abi_version: &AbiVersion,
+    ) -> Result<MapPage> {
+        let (map, _) = Self::read_lazy_map(key_type, value_type, cursor, abi_version)?;
+        map.range(start_key, limit)
+    }
+
+    /// Updates serialized map dictionary without decoding its entries. `map_cell` is the root
+    /// cell of the dictionary (`None` for empty map). Deletions are applied after insertions.
+    /// Returns root cell of the updated dictionary.
+    pub fn apply_map_diff(
+        map_cell: Option<Cell>,
+        inserts: &[(TokenValue, TokenValue)],
+        deletes: &[TokenValue],
+        key_type: &ParamType,
+        value_type: &ParamType,
+        abi_version: &AbiVersion,
+    ) -> Result<Option<Cell>> {
+        let bit_len = TokenValue::get_map_key_size(key_type)?;
+        let map = HashmapE::with_hashmap(bit_len, map_cell);
+        let mut map = LazyMap::new(key_type.clone(), value_type.clone(), map, *abi_version)?;
+        for (key, value) in inserts {
+            map.insert(key, value)?;
+        }
+        for key in deletes {
+            map.remove(key)?;
+        }
+        Ok(map.map.data().cloned())
+    }
+}
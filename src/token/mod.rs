@@ -17,24 +17,39 @@ use crate::{
     int::{Int, Uint},
     param::Param,
     param_type::ParamType,
-    PublicKeyData, contract::{AbiVersion, ABI_VERSION_2_4},
+    PublicKeyData, contract::AbiVersion,
 };
 
 use chrono::prelude::Utc;
 use num_bigint::{BigInt, BigUint};
 use std::collections::BTreeMap;
 use std::fmt;
-use ever_block::{fail, BuilderData, Cell, Grams, MsgAddress, Result};
+use ever_block::{fail, Cell, Grams, MsgAddress, Result, SliceData};
 
+mod amount;
 mod deserialize;
 mod detokenizer;
+mod lazy;
+mod pfx_map;
+mod random;
+mod report;
 mod serialize;
+mod stream;
 mod tokenizer;
+mod tokens;
+mod validate;
 
+pub use self::amount::*;
 pub use self::deserialize::*;
 pub use self::detokenizer::*;
+pub use self::lazy::*;
+pub use self::random::*;
+pub use self::report::*;
 pub use self::serialize::*;
+pub use self::stream::*;
 pub use self::tokenizer::*;
+pub use self::tokens::*;
+pub use self::validate::*;
 
 #[cfg(test)]
 mod test_encoding;
@@ -43,6 +58,10 @@ mod tests;
 
 pub const STD_ADDRESS_BIT_LENGTH: usize = 267;
 pub const MAX_HASH_MAP_INFO_ABOUT_KEY: usize = 12;
+/// Bit length of `slice` value length prefix
+pub const SLICE_LENGTH_BITS: usize = 10;
+/// Maximum bit length of `slice` value so that value with its prefix fits into one cell
+pub const MAX_SLICE_BITS: usize = 1013;
 
 /// EVERX ABI params.
 #[derive(Debug, PartialEq, Clone)]
@@ -135,6 +154,10 @@ pub enum TokenValue {
     Optional(ParamType, Option<Box<TokenValue>>),
     /// Parameter stored in reference
     Ref(Box<TokenValue>),
+    /// Raw bitstring
+    ///
+    /// Encoded as 10-bit length followed by the bits
+    Slice(SliceData),
 }
 
 impl fmt::Display for TokenValue {
@@ -164,6 +187,7 @@ impl fmt::Display for TokenValue {
                 write!(f, "[{}]", s)
             }
             TokenValue::Cell(c) => write!(f, "{:?}", c),
+            TokenValue::Slice(slice) => write!(f, "{}", slice.to_hex_string()),
             TokenValue::Map(_key_type, _value_type, map) => {
                 let s = map
                     .iter()
@@ -235,6 +259,7 @@ impl TokenValue {
                 }
             }
             TokenValue::Cell(_) => *param_type == ParamType::Cell,
+            TokenValue::Slice(_) => *param_type == ParamType::Slice,
             TokenValue::Map(map_key_type, map_value_type, ref values) => {
                 if let ParamType::Map(ref key_type, ref value_type) = *param_type {
                     map_key_type == key_type.as_ref()
@@ -289,6 +314,7 @@ impl TokenValue {
                 ParamType::FixedArray(Box::new(param_type.clone()), tokens.len())
             }
             TokenValue::Cell(_) => ParamType::Cell,
+            TokenValue::Slice(_) => ParamType::Slice,
             TokenValue::Map(key_type, value_type, _) => {
                 ParamType::Map(Box::new(key_type.clone()), Box::new(value_type.clone()))
             }
@@ -335,79 +361,170 @@ impl TokenValue {
         8 - ((size - 1) as u8).leading_zeros() as usize
     }
 
-    pub(crate) fn is_large_optional(param_type: &ParamType, abi_version: &AbiVersion) -> bool {
-        Self::max_bit_size(param_type, abi_version) >= BuilderData::bits_capacity()
-            || Self::max_refs_count(param_type, abi_version) >= BuilderData::references_capacity()
+    /// Wraps value into `ref(T)`
+    pub fn into_ref(self) -> TokenValue {
+        TokenValue::Ref(Box::new(self))
     }
 
-    pub(crate) fn max_refs_count(param_type: &ParamType, abi_version: &AbiVersion) -> usize {
-        match param_type {
-            // in-cell serialized types
-            ParamType::Uint(_)
-            | ParamType::Int(_)
-            | ParamType::VarUint(_)
-            | ParamType::VarInt(_)
-            | ParamType::Bool
-            | ParamType::Address
-            | ParamType::Token
-            | ParamType::Time
-            | ParamType::Expire
-            | ParamType::PublicKey => 0,
-            ParamType::FixedBytes(_) if &ABI_VERSION_2_4 <= abi_version => 0,
-            // reference serialized types
-            ParamType::Array(_)
-            | ParamType::FixedArray(_, _)
-            | ParamType::Cell
-            | ParamType::String
-            | ParamType::Map(_, _)
-            | ParamType::Bytes
-            | ParamType::FixedBytes(_)
-            | ParamType::Ref(_) => 1,
-            // tuple refs is sum of inner types refs
-            ParamType::Tuple(params) => params
-                .iter()
-                .fold(0, |acc, param| acc + Self::max_refs_count(&param.kind, abi_version)),
-            // large optional is serialized into reference
-            ParamType::Optional(param_type) => {
-                if Self::is_large_optional(param_type, abi_version) {
-                    1
+    /// Returns value with tuples which can not fit into one cell wrapped into `ref(T)`.
+    /// Resulting value corresponds to `ParamType::promote_large_tuples` of the value type.
+    pub fn promote_large_tuples(self, abi_version: &AbiVersion) -> TokenValue {
+        if !abi_version.at_least(2, 4) {
+            return self;
+        }
+        match self {
+            TokenValue::Tuple(_) => {
+                let promoted = self.promote_tuple_components(abi_version);
+                if promoted.get_param_type().exceeds_cell(abi_version) {
+                    promoted.into_ref()
                 } else {
-                    Self::max_refs_count(param_type, abi_version)
+                    promoted
                 }
             }
+            TokenValue::Array(item_type, values) => TokenValue::Array(
+                item_type.promote_large_tuples(abi_version),
+                values
+                    .into_iter()
+                    .map(|value| value.promote_large_tuples(abi_version))
+                    .collect(),
+            ),
+            TokenValue::FixedArray(item_type, values) => TokenValue::FixedArray(
+                item_type.promote_large_tuples(abi_version),
+                values
+                    .into_iter()
+                    .map(|value| value.promote_large_tuples(abi_version))
+                    .collect(),
+            ),
+            TokenValue::Map(key_type, value_type, values) => TokenValue::Map(
+                key_type,
+                value_type.promote_large_tuples(abi_version),
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, value.promote_large_tuples(abi_version)))
+                    .collect(),
+            ),
+            TokenValue::Optional(inner_type, value) => TokenValue::Optional(
+                inner_type.promote_large_tuples(abi_version),
+                value.map(|value| Box::new(value.promote_large_tuples(abi_version))),
+            ),
+            TokenValue::Ref(value) => match *value {
+                TokenValue::Tuple(_) => (*value).promote_tuple_components(abi_version).into_ref(),
+                value => value.promote_large_tuples(abi_version).into_ref(),
+            },
+            value => value,
         }
     }
 
-    pub(crate) fn max_bit_size(param_type: &ParamType, abi_version: &AbiVersion) -> usize {
+    /// Reverts `promote_large_tuples`: unwraps tuples promoted into `ref(T)`, so value
+    /// corresponds to `param_type` it was promoted from
+    pub fn restore_promoted_tuples(self, param_type: &ParamType) -> TokenValue {
+        match (param_type, self) {
+            (ParamType::Tuple(_), TokenValue::Ref(value)) => {
+                value.restore_promoted_tuples(param_type)
+            }
+            (ParamType::Tuple(params), TokenValue::Tuple(tokens)) => TokenValue::Tuple(
+                tokens
+                    .into_iter()
+                    .zip(params)
+                    .map(|(token, param)| Token {
+                        name: token.name,
+                        value: token.value.restore_promoted_tuples(&param.kind),
+                    })
+                    .collect(),
+            ),
+            (ParamType::Array(item_type), TokenValue::Array(_, values)) => TokenValue::Array(
+                item_type.as_ref().clone(),
+                values
+                    .into_iter()
+                    .map(|value| value.restore_promoted_tuples(item_type))
+                    .collect(),
+            ),
+            (ParamType::FixedArray(item_type, _), TokenValue::FixedArray(_, values)) => {
+                TokenValue::FixedArray(
+                    item_type.as_ref().clone(),
+                    values
+                        .into_iter()
+                        .map(|value| value.restore_promoted_tuples(item_type))
+                        .collect(),
+                )
+            }
+            (ParamType::Map(_, value_type), TokenValue::Map(key_type, _, values)) => {
+                TokenValue::Map(
+                    key_type,
+                    value_type.as_ref().clone(),
+                    values
+                        .into_iter()
+                        .map(|(key, value)| (key, value.restore_promoted_tuples(value_type)))
+                        .collect(),
+                )
+            }
+            (ParamType::Optional(inner_type), TokenValue::Optional(_, value)) => {
+                TokenValue::Optional(
+                    inner_type.as_ref().clone(),
+                    value.map(|value| Box::new(value.restore_promoted_tuples(inner_type))),
+                )
+            }
+            (ParamType::Ref(inner_type), TokenValue::Ref(value)) => {
+                value.restore_promoted_tuples(inner_type).into_ref()
+            }
+            (_, value) => value,
+        }
+    }
+
+    fn promote_tuple_components(self, abi_version: &AbiVersion) -> TokenValue {
+        match self {
+            TokenValue::Tuple(tokens) => TokenValue::Tuple(
+                tokens
+                    .into_iter()
+                    .map(|token| Token {
+                        name: token.name,
+                        value: token.value.promote_large_tuples(abi_version),
+                    })
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+
+    /// Returns sample value of given type. Sample value is the default value except dynamic
+    /// arrays, maps and optionals which contain one sample item to show the value structure.
+    pub fn sample_value(param_type: &ParamType) -> TokenValue {
         match param_type {
-            ParamType::Uint(size) => *size,
-            ParamType::Int(size) => *size,
-            ParamType::VarUint(size) => Self::varint_size_len(*size) + (size - 1) * 8,
-            ParamType::VarInt(size) => Self::varint_size_len(*size) + (size - 1) * 8,
-            ParamType::Bool => 1,
-            ParamType::Array(_) => 33,
-            ParamType::FixedArray(_, _) => 1,
-            ParamType::Cell => 0,
-            ParamType::Map(_, _) => 1,
-            ParamType::Address => 591,
-            ParamType::FixedBytes(size) if &ABI_VERSION_2_4 <= abi_version => size * 8,
-            ParamType::Bytes | ParamType::FixedBytes(_) => 0,
-            ParamType::String => 0,
-            ParamType::Token => 124,
-            ParamType::Time => 64,
-            ParamType::Expire => 32,
-            ParamType::PublicKey => 257,
-            ParamType::Ref(_) => 0,
-            ParamType::Tuple(params) => params
-                .iter()
-                .fold(0, |acc, param| acc + Self::max_bit_size(&param.kind, abi_version)),
-            ParamType::Optional(param_type) => {
-                if Self::is_large_optional(&param_type, abi_version) {
-                    1
-                } else {
-                    1 + Self::max_bit_size(&param_type, abi_version)
-                }
+            ParamType::Array(inner) => {
+                TokenValue::Array(inner.as_ref().clone(), vec![Self::sample_value(inner)])
+            }
+            ParamType::FixedArray(inner, size) => TokenValue::FixedArray(
+                inner.as_ref().clone(),
+                std::iter::repeat(Self::sample_value(inner))
+                    .take(*size)
+                    .collect(),
+            ),
+            ParamType::Map(key, value) => {
+                let sample_key = match key.as_ref() {
+                    ParamType::Address => format!("0:{:064}", 0),
+                    _ => "0".to_owned(),
+                };
+                TokenValue::Map(
+                    key.as_ref().clone(),
+                    value.as_ref().clone(),
+                    BTreeMap::from([(sample_key, Self::sample_value(value))]),
+                )
             }
+            ParamType::Tuple(params) => TokenValue::Tuple(
+                params
+                    .iter()
+                    .map(|inner| Token {
+                        name: inner.name.clone(),
+                        value: Self::sample_value(&inner.kind),
+                    })
+                    .collect(),
+            ),
+            ParamType::Optional(inner) => TokenValue::Optional(
+                inner.as_ref().clone(),
+                Some(Box::new(Self::sample_value(inner))),
+            ),
+            ParamType::Ref(inner) => Self::sample_value(inner).into_ref(),
+            _ => Self::default_value(param_type),
         }
     }
 
@@ -426,6 +543,7 @@ impl TokenValue {
                     .collect(),
             ),
             ParamType::Cell => TokenValue::Cell(Default::default()),
+            ParamType::Slice => TokenValue::Slice(SliceData::new_empty()),
             ParamType::Map(key, value) => TokenValue::Map(
                 key.as_ref().clone(),
                 value.as_ref().clone(),
@@ -0,0 +1,109 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Maps stored in prefix dictionaries (`PfxHashmapE`).
+//!
+//! Prefix dictionaries are not a part of ABI specification, but some contracts keep data in
+//! them. Keys and values use the same encoding as ordinary ABI `map(K,V)`.
+
+use crate::{
+    contract::AbiVersion,
+    error::AbiError,
+    param_type::ParamType,
+    token::{deserialize::find_next_bits, Tokenizer, TokenValue},
+};
+
+use std::collections::BTreeMap;
+use ever_block::{fail, BuilderData, HashmapType, PfxHashmapE, Result, Serializable, SliceData};
+
+impl TokenValue {
+    /// Reads `map(key_type,value_type)` stored as prefix dictionary from the cursor.
+    /// Returns decoded map and cursor positioned after the dictionary.
+    pub fn read_pfx_map(
+        key_type: &ParamType,
+        value_type: &ParamType,
+        cursor: SliceData,
+        abi_version: &AbiVersion,
+    ) -> Result<(Self, SliceData)> {
+        let bit_len = TokenValue::get_map_key_size(key_type)?;
+        let value_len = value_type.max_bit_size(abi_version);
+        let value_in_ref = Self::map_value_in_ref(bit_len, value_len);
+
+        let mut cursor = find_next_bits(cursor, 1)?;
+        let mut map = BTreeMap::new();
+        let hashmap = PfxHashmapE::with_hashmap(bit_len, cursor.get_dictionary()?.reference_opt(0));
+        hashmap.iterate_slices(|key, mut value| {
+            if key.remaining_bits() != bit_len {
+                fail!(AbiError::InvalidData {
+                    msg: format!(
+                        "Prefix dictionary key of {} bits can not be decoded as {}",
+                        key.remaining_bits(),
+                        key_type
+                    )
+                });
+            }
+            let key = Self::read_from(key_type, key.into(), true, abi_version, false)?.0;
+            let key = serde_json::to_value(&key)?
+                .as_str()
+                .ok_or(AbiError::InvalidData {
+                    msg: "Non-ordinary key".to_owned(),
+                })?
+                .to_owned();
+            if value_in_ref {
+                value = SliceData::load_cell(value.checked_drain_reference()?)?;
+            }
+            let value = Self::read_from(value_type, value.into(), true, abi_version, false)?.0;
+            map.insert(key, value);
+            Ok(true)
+        })?;
+        Ok((
+            TokenValue::Map(key_type.clone(), value_type.clone(), map),
+            cursor,
+        ))
+    }
+
+    /// Serializes map token as prefix dictionary. All keys have full key type length.
+    pub fn write_pfx_map(&self, abi_version: &AbiVersion) -> Result<BuilderData> {
+        let (key_type, value_type, map) = match self {
+            TokenValue::Map(key_type, value_type, map) => (key_type, value_type, map),
+            _ => fail!(AbiError::WrongParameterType),
+        };
+        let key_len = Self::get_map_key_size(key_type)?;
+        let value_len = value_type.max_bit_size(abi_version);
+        let value_in_ref = Self::map_value_in_ref(key_len, value_len);
+
+        let mut hashmap = PfxHashmapE::with_bit_len(key_len);
+        for (key, value) in map.iter() {
+            let key = Tokenizer::tokenize_parameter(key_type, &key.as_str().into(), "map key")?;
+            let mut key_vec = key.write_to_cells(abi_version)?;
+            if key_vec.len() != 1 || key_vec[0].data.length_in_bits() != key_len {
+                fail!(AbiError::InvalidData {
+                    msg: format!("Key type {} can not be used in prefix map", key_type)
+                })
+            }
+            let key = SliceData::load_builder(key_vec.pop().unwrap().data)?;
+
+            let data =
+                Self::pack_cells_into_chain(value.write_to_cells(abi_version)?, abi_version)?;
+            if value_in_ref {
+                hashmap.setref(key, &data.into_cell()?)?;
+            } else {
+                hashmap.set(key, &SliceData::load_builder(data)?)?;
+            }
+        }
+
+        let mut builder = BuilderData::new();
+        hashmap.write_to(&mut builder)?;
+        Ok(builder)
+    }
+}
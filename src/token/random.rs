@@ -0,0 +1,265 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Random values generation for fuzz and integration testing.
+
+use crate::{
+    error::AbiError,
+    int::{Int, Uint},
+    param_type::{ParamType, ValueRange},
+    token::{Token, TokenValue},
+};
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::ToPrimitive;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use ever_block::{AccountId, BuilderData, Grams, MsgAddress, Result, SliceData};
+
+/// Source of random numbers. Implemented for closures so any generator can be adapted,
+/// e.g. `|| rng.next_u64()`
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> RandomSource for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Size limits for generated values
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomConfig {
+    /// Maximum length of dynamic arrays
+    pub max_array_len: usize,
+    /// Maximum length of `bytes` values and data length of `cell` values
+    pub max_bytes_len: usize,
+    /// Maximum length of strings
+    pub max_string_len: usize,
+    /// Maximum number of map entries
+    pub max_map_len: usize,
+    /// Prefer boundary values: zero and maximum numbers and lengths
+    pub extreme_values: bool,
+}
+
+impl Default for RandomConfig {
+    fn default() -> Self {
+        Self {
+            max_array_len: 4,
+            max_bytes_len: 32,
+            max_string_len: 32,
+            max_map_len: 4,
+            extreme_values: false,
+        }
+    }
+}
+
+impl TokenValue {
+    /// Generates random value of given type which can be encoded with any ABI version
+    /// supporting the type
+    pub fn random<R: RandomSource>(
+        param_type: &ParamType,
+        rng: &mut R,
+        config: &RandomConfig,
+    ) -> Result<TokenValue> {
+        let value = match param_type {
+            ParamType::Uint(size) => TokenValue::Uint(Uint {
+                number: random_uint(rng, param_type, config),
+                size: *size,
+            }),
+            ParamType::Int(size) => TokenValue::Int(Int {
+                number: random_int(rng, param_type, config),
+                size: *size,
+            }),
+            ParamType::VarUint(size) => {
+                TokenValue::VarUint(*size, random_uint(rng, param_type, config))
+            }
+            ParamType::VarInt(size) => {
+                TokenValue::VarInt(*size, random_int(rng, param_type, config))
+            }
+            ParamType::Bool => TokenValue::Bool(rng.next_u64() % 2 == 0),
+            ParamType::Tuple(params) => {
+                let mut tokens = vec![];
+                for param in params {
+                    tokens.push(Token {
+                        name: param.name.clone(),
+                        value: Self::random(&param.kind, rng, config)?,
+                    });
+                }
+                TokenValue::Tuple(tokens)
+            }
+            ParamType::Array(inner) => {
+                let len = random_len(rng, config.max_array_len, config);
+                let mut values = vec![];
+                for _ in 0..len {
+                    values.push(Self::random(inner, rng, config)?);
+                }
+                TokenValue::Array(inner.as_ref().clone(), values)
+            }
+            ParamType::FixedArray(inner, size) => {
+                let mut values = vec![];
+                for _ in 0..*size {
+                    values.push(Self::random(inner, rng, config)?);
+                }
+                TokenValue::FixedArray(inner.as_ref().clone(), values)
+            }
+            ParamType::Cell => {
+                let max_bits = std::cmp::min(config.max_bytes_len * 8, BuilderData::bits_capacity());
+                let bits = random_len(rng, max_bits, config);
+                let data = random_bytes(rng, (bits + 7) / 8);
+                TokenValue::Cell(BuilderData::with_raw(data, bits)?.into_cell()?)
+            }
+            ParamType::Slice => {
+                let max_bits = std::cmp::min(config.max_bytes_len * 8, super::MAX_SLICE_BITS);
+                let bits = random_len(rng, max_bits, config);
+                let data = random_bytes(rng, (bits + 7) / 8);
+                TokenValue::Slice(SliceData::from_raw(data, bits))
+            }
+            ParamType::Map(key_type, value_type) => {
+                let len = random_len(rng, config.max_map_len, config);
+                let mut map = BTreeMap::new();
+                for _ in 0..len {
+                    let key = match key_type.as_ref() {
+                        ParamType::Address => random_std_address(rng)?,
+                        key_type => Self::random(key_type, rng, config)?,
+                    };
+                    let key = serde_json::to_value(&key)?
+                        .as_str()
+                        .ok_or(AbiError::InvalidData {
+                            msg: "Non-ordinary key".to_owned(),
+                        })?
+                        .to_owned();
+                    map.insert(key, Self::random(value_type, rng, config)?);
+                }
+                TokenValue::Map(key_type.as_ref().clone(), value_type.as_ref().clone(), map)
+            }
+            ParamType::Address => {
+                if rng.next_u64() % 4 == 0 {
+                    TokenValue::Address(MsgAddress::AddrNone)
+                } else {
+                    random_std_address(rng)?
+                }
+            }
+            ParamType::Bytes => {
+                let len = random_len(rng, config.max_bytes_len, config);
+                TokenValue::Bytes(random_bytes(rng, len))
+            }
+            ParamType::FixedBytes(size) => TokenValue::FixedBytes(random_bytes(rng, *size)),
+            ParamType::String => {
+                let len = random_len(rng, config.max_string_len, config);
+                TokenValue::String(
+                    (0..len)
+                        .map(|_| (b' ' + (rng.next_u64() % 95) as u8) as char)
+                        .collect(),
+                )
+            }
+            ParamType::Token => {
+                let number = random_uint(rng, param_type, config);
+                TokenValue::Token(Grams::from_str(&number.to_string()).map_err(|_| {
+                    AbiError::InvalidData {
+                        msg: format!("Can not create grams value from {}", number),
+                    }
+                })?)
+            }
+            ParamType::Time => {
+                let time = random_uint(rng, param_type, config);
+                TokenValue::Time(time.to_u64().unwrap_or_default())
+            }
+            ParamType::Expire => {
+                let expire = random_uint(rng, param_type, config);
+                TokenValue::Expire(expire.to_u32().unwrap_or_default())
+            }
+            ParamType::PublicKey => {
+                if rng.next_u64() % 4 == 0 {
+                    TokenValue::PublicKey(None)
+                } else {
+                    let mut key = [0u8; 32];
+                    key.copy_from_slice(&random_bytes(rng, 32));
+                    TokenValue::PublicKey(Some(key))
+                }
+            }
+            ParamType::Optional(inner) => {
+                if rng.next_u64() % 2 == 0 {
+                    TokenValue::Optional(inner.as_ref().clone(), None)
+                } else {
+                    TokenValue::Optional(
+                        inner.as_ref().clone(),
+                        Some(Box::new(Self::random(inner, rng, config)?)),
+                    )
+                }
+            }
+            ParamType::Ref(inner) => Self::random(inner, rng, config)?.into_ref(),
+        };
+        Ok(value)
+    }
+}
+
+fn random_bytes<R: RandomSource>(rng: &mut R, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.next_u64() as u8).collect()
+}
+
+fn random_len<R: RandomSource>(rng: &mut R, max: usize, config: &RandomConfig) -> usize {
+    if config.extreme_values && rng.next_u64() % 2 == 0 {
+        if rng.next_u64() % 2 == 0 {
+            0
+        } else {
+            max
+        }
+    } else {
+        (rng.next_u64() % (max as u64 + 1)) as usize
+    }
+}
+
+// generates number within the range of integer-like type
+fn random_int<R: RandomSource>(
+    rng: &mut R,
+    param_type: &ParamType,
+    config: &RandomConfig,
+) -> BigInt {
+    let bounds = match param_type.value_range() {
+        ValueRange::Int(bounds) => bounds,
+        _ => return BigInt::default(),
+    };
+    if config.extreme_values {
+        match rng.next_u64() % 4 {
+            0 => return bounds.min,
+            1 => return bounds.max,
+            _ => {}
+        }
+    }
+    let width = (&bounds.max - &bounds.min).to_biguint().unwrap_or_default();
+    let bits = width.bits() as usize;
+    let bytes = random_bytes(rng, (bits + 7) / 8);
+    let number = BigUint::from_bytes_be(&bytes) >> (bytes.len() * 8 - bits);
+    bounds.min + BigInt::from(number % (width + 1u8))
+}
+
+fn random_uint<R: RandomSource>(
+    rng: &mut R,
+    param_type: &ParamType,
+    config: &RandomConfig,
+) -> BigUint {
+    random_int(rng, param_type, config).to_biguint().unwrap_or_default()
+}
+
+fn random_std_address<R: RandomSource>(rng: &mut R) -> Result<TokenValue> {
+    let mut account_id = [0u8; 32];
+    account_id.copy_from_slice(&random_bytes(rng, 32));
+    let workchain_id = if rng.next_u64() % 2 == 0 { 0 } else { -1 };
+    Ok(TokenValue::Address(MsgAddress::with_standart(
+        None,
+        workchain_id,
+        AccountId::from(account_id),
+    )?))
+}
@@ -0,0 +1,149 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Encoding layout report.
+
+use crate::{
+    contract::AbiVersion,
+    token::{Token, TokenValue},
+};
+
+/// Reason of placing value into separate cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefReason {
+    /// `optional(T)` value is too large to be stored in the current cell
+    LargeOptional,
+    /// Value of `ref(T)` type
+    Ref,
+}
+
+/// Reason of continuing cells chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainReason {
+    /// Not enough free bits in the current cell
+    NotEnoughBits,
+    /// Not enough free references in the current cell
+    NotEnoughRefs,
+    /// Value references fit into the current cell but the last reference is needed for chaining
+    ChainRefReserved,
+}
+
+/// Value placed into separate cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefPlacement {
+    /// Value path: parameter name with tuple components and collection items separated by `.`
+    pub path: String,
+    pub reason: RefReason,
+}
+
+/// Value started new cell in the chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainBreak {
+    /// Value path: parameter name with tuple components separated by `.`
+    pub path: String,
+    pub reason: ChainReason,
+}
+
+/// Report about encoded values layout
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodeReport {
+    /// Values placed into separate cells
+    pub refs: Vec<RefPlacement>,
+    /// Values which started new cells in the root chain
+    pub chain: Vec<ChainBreak>,
+}
+
+impl EncodeReport {
+    /// Returns number of cells in the root chain
+    pub fn cells_count(&self) -> usize {
+        self.chain.len() + 1
+    }
+
+    /// Creates report. `labels` contains path for every serialized value packed into chain
+    /// and `breaks` contains indexes of values which started new cells.
+    pub(crate) fn new(
+        tokens: &[Token],
+        labels: &[String],
+        breaks: &[(usize, ChainReason)],
+        abi_version: &AbiVersion,
+    ) -> Self {
+        let mut refs = vec![];
+        for token in tokens {
+            Self::collect_refs(&token.name, &token.value, abi_version, &mut refs);
+        }
+        let chain = breaks
+            .iter()
+            .map(|(index, reason)| ChainBreak {
+                path: labels.get(*index).cloned().unwrap_or_default(),
+                reason: *reason,
+            })
+            .collect();
+        Self { refs, chain }
+    }
+
+    /// Adds paths of serialized values produced by `TokenValue::write_to_cells`. Tuples are
+    /// expanded into components while tuples promoted into `ref(T)` are single values.
+    pub(crate) fn collect_labels(path: &str, value: &TokenValue, labels: &mut Vec<String>) {
+        match value {
+            TokenValue::Tuple(tokens) => {
+                for token in tokens {
+                    Self::collect_labels(&format!("{}.{}", path, token.name), &token.value, labels);
+                }
+            }
+            _ => labels.push(path.to_owned()),
+        }
+    }
+
+    fn collect_refs(
+        path: &str,
+        value: &TokenValue,
+        abi_version: &AbiVersion,
+        refs: &mut Vec<RefPlacement>,
+    ) {
+        match value {
+            TokenValue::Tuple(tokens) => {
+                for token in tokens {
+                    let path = format!("{}.{}", path, token.name);
+                    Self::collect_refs(&path, &token.value, abi_version, refs);
+                }
+            }
+            TokenValue::Array(_, values) | TokenValue::FixedArray(_, values) => {
+                for (i, value) in values.iter().enumerate() {
+                    Self::collect_refs(&format!("{}.{}", path, i), value, abi_version, refs);
+                }
+            }
+            TokenValue::Map(_, _, values) => {
+                for (key, value) in values {
+                    Self::collect_refs(&format!("{}.{}", path, key), value, abi_version, refs);
+                }
+            }
+            TokenValue::Optional(param_type, Some(value)) => {
+                if param_type.is_large_optional_value(abi_version) {
+                    refs.push(RefPlacement {
+                        path: path.to_owned(),
+                        reason: RefReason::LargeOptional,
+                    });
+                }
+                Self::collect_refs(path, value, abi_version, refs);
+            }
+            TokenValue::Ref(value) => {
+                refs.push(RefPlacement {
+                    path: path.to_owned(),
+                    reason: RefReason::Ref,
+                });
+                Self::collect_refs(path, value, abi_version, refs);
+            }
+            _ => {}
+        }
+    }
+}
@@ -12,25 +12,159 @@
 */
 
 use crate::{
-    contract::{AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_2, ABI_VERSION_2_4},
+    contract::{AbiVersion, ABI_VERSION_1_0},
     error::AbiError,
     int::{Int, Uint},
     param_type::ParamType,
-    token::{Token, TokenValue, Tokenizer},
+    token::{ChainReason, Token, TokenValue, Tokenizer},
     PublicKeyData,
 };
 
 use num_bigint::{BigInt, BigUint, Sign};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use ever_block::Serializable;
 use ever_block::{fail, BuilderData, Cell, HashmapE, IBitstring, Result, SliceData};
 
+/// Minimal array length serialized in parallel
+#[cfg(feature = "parallel")]
+pub const PARALLEL_ARRAY_THRESHOLD: usize = 1024;
+
+/// Serialized value with its layout size. Since ABI 2.2 values are placed into cells chain
+/// according to their maximum possible size, not actual one, so layout doesn't depend on values.
+#[derive(Clone, Debug)]
 pub struct SerializedValue {
+    /// Serialized data
     pub data: BuilderData,
+    /// Maximum bits count value can take
     pub max_bits: usize,
+    /// Maximum references count value can take
     pub max_refs: usize,
 }
 
+impl SerializedValue {
+    /// Creates value with explicit layout size. Fails if data exceeds provided size.
+    pub fn with_layout(data: BuilderData, max_bits: usize, max_refs: usize) -> Result<Self> {
+        if data.bits_used() > max_bits || data.references_used() > max_refs {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Serialized value ({} bits, {} refs) exceeds layout size ({} bits, {} refs)",
+                    data.bits_used(),
+                    data.references_used(),
+                    max_bits,
+                    max_refs
+                )
+            });
+        }
+        Ok(Self {
+            data,
+            max_bits,
+            max_refs,
+        })
+    }
+}
+
+/// Cache of cells built during encoding. Equal `bytes` and `string` values and repeated
+/// consecutive array items are serialized once and share the same cells. Cells are cached per
+/// ABI version, so the cache can be reused for encoding with different versions.
+#[derive(Clone, Debug)]
+pub struct CellCache {
+    enabled: bool,
+    bytes: HashMap<AbiVersion, HashMap<Vec<u8>, Cell>>,
+}
+
+impl Default for CellCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CellCache {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            bytes: HashMap::new(),
+        }
+    }
+
+    // cache which never stores cells
+    pub(crate) fn disabled() -> Self {
+        Self {
+            enabled: false,
+            bytes: HashMap::new(),
+        }
+    }
+
+    /// Returns number of cached `bytes` and `string` values
+    pub fn len(&self) -> usize {
+        self.bytes.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.values().all(HashMap::is_empty)
+    }
+}
+
+/// Packs serialized values into cells chain using the same rules as function call encoding.
+/// Allows building custom layouts (e.g. payload prefixes) compatible with ABI decoding.
+#[derive(Clone, Debug)]
+pub struct ChainPacker {
+    abi_version: AbiVersion,
+    values: Vec<SerializedValue>,
+    cache: CellCache,
+}
+
+impl ChainPacker {
+    pub fn new(abi_version: AbiVersion) -> Self {
+        Self {
+            abi_version,
+            values: vec![],
+            cache: CellCache::disabled(),
+        }
+    }
+
+    /// Enables sharing of cells between equal values pushed with `push_token`
+    pub fn with_cell_cache(mut self) -> Self {
+        self.cache.enabled = true;
+        self
+    }
+
+    /// Appends raw serialized value
+    pub fn push_value(&mut self, value: SerializedValue) -> &mut Self {
+        self.values.push(value);
+        self
+    }
+
+    /// Appends raw data. Layout size of the data equals to its actual size
+    pub fn push_builder(&mut self, data: BuilderData) -> &mut Self {
+        self.push_value(data.into())
+    }
+
+    /// Appends serialized token value
+    pub fn push_token(&mut self, value: &TokenValue) -> Result<&mut Self> {
+        self.values
+            .append(&mut value.write_to_cells_with_cache(&self.abi_version, &mut self.cache)?);
+        Ok(self)
+    }
+
+    /// Appends serialized tokens values
+    pub fn push_tokens(&mut self, tokens: &[Token]) -> Result<&mut Self> {
+        for token in tokens {
+            self.push_token(&token.value)?;
+        }
+        Ok(self)
+    }
+
+    /// Returns values added so far
+    pub fn values(&self) -> &[SerializedValue] {
+        &self.values
+    }
+
+    /// Packs values into cells chain. Fails if no values were added
+    pub fn pack(self) -> Result<BuilderData> {
+        TokenValue::pack_cells_into_chain(self.values, &self.abi_version)
+    }
+}
+
 impl From<BuilderData> for SerializedValue {
     fn from(data: BuilderData) -> Self {
         SerializedValue {
@@ -43,25 +177,54 @@ impl From<BuilderData> for SerializedValue {
 
 impl TokenValue {
     pub fn pack_values_into_chain(
+        tokens: &[Token],
+        cells: Vec<SerializedValue>,
+        abi_version: &AbiVersion,
+    ) -> Result<BuilderData> {
+        Self::pack_values_into_chain_with_breaks(tokens, cells, abi_version, None)
+    }
+
+    pub(crate) fn pack_values_into_chain_with_breaks(
         tokens: &[Token],
         mut cells: Vec<SerializedValue>,
         abi_version: &AbiVersion,
+        breaks: Option<&mut Vec<(usize, ChainReason)>>,
     ) -> Result<BuilderData> {
         for token in tokens {
             cells.append(&mut token.value.write_to_cells(abi_version)?);
         }
-        Self::pack_cells_into_chain(cells, abi_version)
+        Self::pack_cells_into_chain_with_breaks(cells, abi_version, breaks)
     }
 
     pub fn pack_into_chain(&self, abi_version: &AbiVersion) -> Result<BuilderData> {
-        Self::pack_cells_into_chain(self.write_to_cells(abi_version)?, abi_version)
+        self.pack_into_chain_with_cache(abi_version, &mut CellCache::disabled())
+    }
+
+    /// Packs value into cells chain sharing cells of equal values through the cache
+    pub fn pack_into_chain_with_cache(
+        &self,
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<BuilderData> {
+        let cells = self.write_to_cells_with_cache(abi_version, cache)?;
+        Self::pack_cells_into_chain(cells, abi_version)
+    }
+
+    /// Packs serialized values into cells chain. First value is placed into the root cell.
+    pub fn pack_cells_into_chain(
+        values: Vec<SerializedValue>,
+        abi_version: &AbiVersion,
+    ) -> Result<BuilderData> {
+        Self::pack_cells_into_chain_with_breaks(values, abi_version, None)
     }
 
     // first cell is resulting builder
     // every next cell: put data to root
-    fn pack_cells_into_chain(
+    // `breaks` receives indexes of values which started new cells in chain
+    fn pack_cells_into_chain_with_breaks(
         mut values: Vec<SerializedValue>,
         abi_version: &AbiVersion,
+        mut breaks: Option<&mut Vec<(usize, ChainReason)>>,
     ) -> Result<BuilderData> {
         values.reverse();
         let mut packed_cells = match values.pop() {
@@ -70,10 +233,12 @@ impl TokenValue {
                 msg: "No cells".to_owned()
             }),
         };
+        let mut index = 0;
         while let Some(value) = values.pop() {
+            index += 1;
             let builder = packed_cells.last_mut().unwrap();
 
-            let (remaining_bits, remaining_refs) = if abi_version >= &ABI_VERSION_2_2 {
+            let (remaining_bits, remaining_refs) = if abi_version.at_least(2, 2) {
                 (
                     BuilderData::bits_capacity() - builder.max_bits,
                     BuilderData::references_capacity() - builder.max_refs,
@@ -81,7 +246,7 @@ impl TokenValue {
             } else {
                 (builder.data.bits_free(), builder.data.references_free())
             };
-            let (value_bits, value_refs) = if abi_version >= &ABI_VERSION_2_2 {
+            let (value_bits, value_refs) = if abi_version.at_least(2, 2) {
                 (value.max_bits, value.max_refs)
             } else {
                 (value.data.bits_used(), value.data.references_used())
@@ -89,6 +254,14 @@ impl TokenValue {
 
             if remaining_bits < value_bits || remaining_refs < value_refs {
                 // if not enough bits or refs - continue chain
+                if let Some(breaks) = breaks.as_mut() {
+                    let reason = if remaining_bits < value_bits {
+                        ChainReason::NotEnoughBits
+                    } else {
+                        ChainReason::NotEnoughRefs
+                    };
+                    breaks.push((index, reason));
+                }
                 packed_cells.push(value);
             } else if value_refs > 0 && remaining_refs == value_refs {
                 // if refs strictly fit into cell we should decide if we can put them into current
@@ -103,6 +276,9 @@ impl TokenValue {
                     builder.max_bits += value.max_bits;
                     builder.max_refs += value.max_refs;
                 } else {
+                    if let Some(breaks) = breaks.as_mut() {
+                        breaks.push((index, ChainReason::ChainRefReserved));
+                    }
                     packed_cells.push(value);
                 }
             } else {
@@ -126,7 +302,7 @@ impl TokenValue {
 
     fn get_remaining(values: &[SerializedValue], abi_version: &AbiVersion) -> (usize, usize) {
         values.iter().fold((0, 0), |(refs, bits), value| {
-            if abi_version >= &ABI_VERSION_2_2 {
+            if abi_version.at_least(2, 2) {
                 (refs + value.max_refs, bits + value.max_bits)
             } else {
                 (
@@ -138,59 +314,111 @@ impl TokenValue {
     }
 
     pub fn write_to_cells(&self, abi_version: &AbiVersion) -> Result<Vec<SerializedValue>> {
-        let data = match self {
-            TokenValue::Uint(uint) => Self::write_uint(uint),
-            TokenValue::Int(int) => Self::write_int(int),
-            TokenValue::VarUint(size, uint) => Self::write_varuint(uint, *size),
-            TokenValue::VarInt(size, int) => Self::write_varint(int, *size),
-            TokenValue::Bool(b) => Self::write_bool(b),
-            TokenValue::Tuple(ref tokens) => {
-                let mut vec = vec![];
-                for token in tokens.iter() {
-                    vec.append(&mut token.value.write_to_cells(abi_version)?);
-                }
-                return Ok(vec);
+        self.write_to_cells_with_cache(abi_version, &mut CellCache::disabled())
+    }
+
+    /// Serializes value sharing cells of equal values through the cache
+    pub fn write_to_cells_with_cache(
+        &self,
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<Vec<SerializedValue>> {
+        if let TokenValue::Tuple(ref tokens) = self {
+            let mut vec = vec![];
+            for token in tokens.iter() {
+                vec.append(&mut token.value.write_to_cells_with_cache(abi_version, cache)?);
+            }
+            return Ok(vec);
+        }
+
+        let param_type = self.get_param_type();
+        Ok(vec![SerializedValue {
+            data: self.write_single_value(abi_version, cache)?,
+            max_bits: param_type.max_bit_size(abi_version),
+            max_refs: param_type.max_refs_count(abi_version),
+        }])
+    }
+
+    // serializes value which is not a tuple into single builder
+    pub(crate) fn write_single_value(
+        &self,
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<BuilderData> {
+        match self {
+            TokenValue::Uint(_)
+            | TokenValue::Int(_)
+            | TokenValue::VarUint(..)
+            | TokenValue::VarInt(..)
+            | TokenValue::Bool(_)
+            | TokenValue::Address(_)
+            | TokenValue::Token(_)
+            | TokenValue::Time(_)
+            | TokenValue::Expire(_)
+            | TokenValue::PublicKey(_) => {
+                let mut builder = BuilderData::new();
+                self.write_single_value_into(&mut builder, abi_version, cache)?;
+                Ok(builder)
             }
+            TokenValue::Tuple(_) => fail!(AbiError::InvalidData {
+                msg: "Tuple is serialized as a sequence of its components".to_owned()
+            }),
             TokenValue::Array(param_type, ref tokens) => {
-                Self::write_array(param_type, tokens, abi_version)
+                Self::write_array(param_type, tokens, abi_version, cache)
             }
             TokenValue::FixedArray(param_type, ref tokens) => {
-                Self::write_fixed_array(param_type, tokens, abi_version)
+                Self::write_fixed_array(param_type, tokens, abi_version, cache)
             }
             TokenValue::Cell(cell) => Self::write_cell(cell),
+            TokenValue::Slice(slice) => Self::write_slice(slice),
             TokenValue::Map(key_type, value_type, value) => {
-                Self::write_map(key_type, value_type, value, abi_version)
+                Self::write_map(key_type, value_type, value, abi_version, cache)
+            }
+            TokenValue::Bytes(ref arr) => Self::write_bytes(arr, abi_version, cache),
+            TokenValue::FixedBytes(ref arr) => Self::write_fixed_bytes(arr, abi_version, cache),
+            TokenValue::String(ref string) => {
+                Self::write_bytes(string.as_bytes(), abi_version, cache)
             }
-            TokenValue::Address(address) => Ok(address.write_to_new_cell()?),
-            TokenValue::Bytes(ref arr) => Self::write_bytes(arr, abi_version),
-            TokenValue::FixedBytes(ref arr) => Self::write_fixed_bytes(arr, abi_version),
-            TokenValue::String(ref string) => Self::write_bytes(string.as_bytes(), abi_version),
-            TokenValue::Token(gram) => Ok(gram.write_to_new_cell()?),
-            TokenValue::Time(time) => Ok(time.write_to_new_cell()?),
-            TokenValue::Expire(expire) => Ok(expire.write_to_new_cell()?),
-            TokenValue::PublicKey(key) => Self::write_public_key(key),
             TokenValue::Optional(param_type, value) => Self::write_optional(
                 param_type,
                 value.as_ref().map(|val| val.as_ref()),
                 abi_version,
+                cache,
             ),
-            TokenValue::Ref(value) => Self::write_ref(value, abi_version),
-        }?;
+            TokenValue::Ref(value) => Self::write_ref(value, abi_version, cache),
+        }
+    }
 
-        let param_type = self.get_param_type();
-        Ok(vec![SerializedValue {
-            data,
-            max_bits: Self::max_bit_size(&param_type, abi_version),
-            max_refs: Self::max_refs_count(&param_type, abi_version),
-        }])
+    /// Appends value to `builder`. Fixed size values are written in place, other values are
+    /// serialized into separate builder first.
+    pub(crate) fn write_single_value_into(
+        &self,
+        builder: &mut BuilderData,
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<()> {
+        match self {
+            TokenValue::Uint(uint) => Self::write_uint(uint, builder),
+            TokenValue::Int(int) => Self::write_int(int, builder),
+            TokenValue::VarUint(size, uint) => Self::write_varuint(uint, *size, builder),
+            TokenValue::VarInt(size, int) => Self::write_varint(int, *size, builder),
+            TokenValue::Bool(b) => Self::write_bool(b, builder),
+            TokenValue::Address(address) => address.write_to(builder),
+            TokenValue::Token(gram) => gram.write_to(builder),
+            TokenValue::Time(time) => time.write_to(builder),
+            TokenValue::Expire(expire) => expire.write_to(builder),
+            TokenValue::PublicKey(key) => Self::write_public_key(key, builder),
+            _ => {
+                builder.append_builder(&self.write_single_value(abi_version, cache)?)?;
+                Ok(())
+            }
+        }
     }
 
-    fn write_int(value: &Int) -> Result<BuilderData> {
+    fn write_int(value: &Int, builder: &mut BuilderData) -> Result<()> {
         let vec = value.number.to_signed_bytes_be();
         let vec_bits_length = vec.len() * 8;
 
-        let mut builder = BuilderData::new();
-
         if value.size > vec_bits_length {
             let padding = if value.number.sign() == num_bigint::Sign::Minus {
                 0xFFu8
@@ -213,20 +441,19 @@ impl TokenValue {
             builder.append_raw(&vec[offset / 8 + 1..], vec[offset / 8 + 1..].len() * 8)?;
         };
 
-        Ok(builder)
+        Ok(())
     }
 
-    fn write_uint(value: &Uint) -> Result<BuilderData> {
+    fn write_uint(value: &Uint, builder: &mut BuilderData) -> Result<()> {
         let int = Int {
             number: BigInt::from_biguint(Sign::Plus, value.number.clone()),
             size: value.size,
         };
 
-        Self::write_int(&int)
+        Self::write_int(&int, builder)
     }
 
-    fn write_varnumber(vec: &Vec<u8>, size: usize) -> Result<BuilderData> {
-        let mut builder = BuilderData::new();
+    fn write_varnumber(vec: &Vec<u8>, size: usize, builder: &mut BuilderData) -> Result<()> {
         let bits = Self::varint_size_len(size);
         if vec != &[0] {
             builder.append_bits(vec.len(), bits as usize)?;
@@ -235,10 +462,10 @@ impl TokenValue {
             builder.append_bits(0, bits as usize)?;
         }
 
-        Ok(builder)
+        Ok(())
     }
 
-    fn write_varint(value: &BigInt, size: usize) -> Result<BuilderData> {
+    fn write_varint(value: &BigInt, size: usize, builder: &mut BuilderData) -> Result<()> {
         let vec = value.to_signed_bytes_be();
 
         if vec.len() > size - 1 {
@@ -246,10 +473,10 @@ impl TokenValue {
                 msg: format!("Too long value for varint{}: {}", size, value)
             });
         }
-        Self::write_varnumber(&vec, size)
+        Self::write_varnumber(&vec, size, builder)
     }
 
-    fn write_varuint(value: &BigUint, size: usize) -> Result<BuilderData> {
+    fn write_varuint(value: &BigUint, size: usize, builder: &mut BuilderData) -> Result<()> {
         let vec = value.to_bytes_be();
 
         if vec.len() > size - 1 {
@@ -257,13 +484,12 @@ impl TokenValue {
                 msg: format!("Too long value for varuint{}: {}", size, value)
             });
         }
-        Self::write_varnumber(&vec, size)
+        Self::write_varnumber(&vec, size, builder)
     }
 
-    fn write_bool(value: &bool) -> Result<BuilderData> {
-        let mut builder = BuilderData::new();
+    fn write_bool(value: &bool, builder: &mut BuilderData) -> Result<()> {
         builder.append_bit_bool(value.clone())?;
-        Ok(builder)
+        Ok(())
     }
 
     fn write_cell(cell: &Cell) -> Result<BuilderData> {
@@ -272,25 +498,56 @@ impl TokenValue {
         Ok(builder)
     }
 
+    fn write_slice(slice: &SliceData) -> Result<BuilderData> {
+        if slice.remaining_bits() > super::MAX_SLICE_BITS || slice.remaining_references() != 0 {
+            fail!(AbiError::InvalidData {
+                msg: format!(
+                    "Slice value must contain up to {} bits and no references",
+                    super::MAX_SLICE_BITS
+                )
+            });
+        }
+        let mut builder = BuilderData::new();
+        builder.append_bits(slice.remaining_bits(), super::SLICE_LENGTH_BITS)?;
+        builder.append_bytestring(slice)?;
+        Ok(builder)
+    }
+
     // creates dictionary with indexes of an array items as keys and items as values
     // and prepends dictionary to cell
     fn put_array_into_dictionary(
         param_type: &ParamType,
         array: &[TokenValue],
         abi_version: &AbiVersion,
+        cache: &mut CellCache,
     ) -> Result<HashmapE> {
+        #[cfg(feature = "parallel")]
+        if !cache.enabled && array.len() >= PARALLEL_ARRAY_THRESHOLD {
+            return Self::put_array_into_dictionary_parallel(param_type, array, abi_version);
+        }
+
         let mut map = HashmapE::with_bit_len(32);
 
-        let value_in_ref = Self::map_value_in_ref(32, Self::max_bit_size(param_type, abi_version));
+        let value_in_ref = Self::map_value_in_ref(32, param_type.max_bit_size(abi_version));
 
+        // serialized previous item is reused if the current item is equal to it
+        let mut data = BuilderData::new();
+        let mut cell = Cell::default();
         for i in 0..array.len() {
             let index = SliceData::load_builder((i as u32).write_to_new_cell()?)?;
 
-            let data =
-                Self::pack_cells_into_chain(array[i].write_to_cells(abi_version)?, abi_version)?;
+            let repeated = cache.enabled && i > 0 && array[i - 1] == array[i];
+            if !repeated {
+                let item = array[i].pack_into_chain_with_cache(abi_version, cache)?;
+                if value_in_ref {
+                    cell = item.into_cell()?;
+                } else {
+                    data = item;
+                }
+            }
 
             if value_in_ref {
-                map.setref(index, &data.into_cell()?)?;
+                map.setref(index, &cell)?;
             } else {
                 map.set_builder(index, &data)?;
             }
@@ -299,12 +556,48 @@ impl TokenValue {
         Ok(map)
     }
 
+    // serializes array items in parallel and then builds dictionary from serialized items
+    #[cfg(feature = "parallel")]
+    fn put_array_into_dictionary_parallel(
+        param_type: &ParamType,
+        array: &[TokenValue],
+        abi_version: &AbiVersion,
+    ) -> Result<HashmapE> {
+        use rayon::prelude::*;
+
+        let mut map = HashmapE::with_bit_len(32);
+
+        let value_in_ref = Self::map_value_in_ref(32, param_type.max_bit_size(abi_version));
+
+        let index = |i: usize| SliceData::load_builder((i as u32).write_to_new_cell()?);
+        if value_in_ref {
+            let cells = array
+                .par_iter()
+                .map(|item| item.pack_into_chain(abi_version)?.into_cell())
+                .collect::<Result<Vec<_>>>()?;
+            for (i, cell) in cells.iter().enumerate() {
+                map.setref(index(i)?, cell)?;
+            }
+        } else {
+            let items = array
+                .par_iter()
+                .map(|item| item.pack_into_chain(abi_version))
+                .collect::<Result<Vec<_>>>()?;
+            for (i, data) in items.iter().enumerate() {
+                map.set_builder(index(i)?, data)?;
+            }
+        }
+
+        Ok(map)
+    }
+
     fn write_array(
         param_type: &ParamType,
         value: &Vec<TokenValue>,
         abi_version: &AbiVersion,
+        cache: &mut CellCache,
     ) -> Result<BuilderData> {
-        let map = Self::put_array_into_dictionary(param_type, value, abi_version)?;
+        let map = Self::put_array_into_dictionary(param_type, value, abi_version, cache)?;
 
         let mut builder = BuilderData::new();
         builder.append_u32(value.len() as u32)?;
@@ -318,14 +611,19 @@ impl TokenValue {
         param_type: &ParamType,
         value: &Vec<TokenValue>,
         abi_version: &AbiVersion,
+        cache: &mut CellCache,
     ) -> Result<BuilderData> {
-        let map = Self::put_array_into_dictionary(param_type, value, abi_version)?;
+        let map = Self::put_array_into_dictionary(param_type, value, abi_version, cache)?;
 
         Ok(map.write_to_new_cell()?)
     }
 
-    fn write_fixed_bytes(data: &[u8], abi_version: &AbiVersion) -> Result<BuilderData> {
-        if abi_version >= &ABI_VERSION_2_4 {
+    fn write_fixed_bytes(
+        data: &[u8],
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<BuilderData> {
+        if abi_version.at_least(2, 4) {
             if data.len() * 8 > BuilderData::bits_capacity() {
                 fail!(AbiError::InvalidData {
                     msg: "FixedBytes value size is limited to 127 bytes".to_owned()
@@ -335,11 +633,20 @@ impl TokenValue {
             builder.append_raw(data, data.len() * 8)?;
             Ok(builder)
         } else {
-            Self::write_bytes(data, abi_version)
+            Self::write_bytes(data, abi_version, cache)
         }
     }
 
-    fn write_bytes(data: &[u8], abi_version: &AbiVersion) -> Result<BuilderData> {
+    fn write_bytes(
+        data: &[u8],
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<BuilderData> {
+        if cache.enabled {
+            if let Some(cell) = cache.bytes.get(abi_version).and_then(|bytes| bytes.get(data)) {
+                return Self::write_cell(cell);
+            }
+        }
         let cell_len = BuilderData::bits_capacity() / 8;
         let mut len = data.len();
         let mut cell_capacity = if abi_version == &ABI_VERSION_1_0 {
@@ -363,6 +670,13 @@ impl TokenValue {
         if builder.references_used() == 0 {
             builder.checked_append_reference(Cell::default())?;
         }
+        if cache.enabled {
+            cache
+                .bytes
+                .entry(*abi_version)
+                .or_default()
+                .insert(data.to_vec(), builder.references()[0].clone());
+        }
         Ok(builder)
     }
 
@@ -375,9 +689,10 @@ impl TokenValue {
         value_type: &ParamType,
         value: &BTreeMap<String, TokenValue>,
         abi_version: &AbiVersion,
+        cache: &mut CellCache,
     ) -> Result<BuilderData> {
         let key_len = Self::get_map_key_size(key_type)?;
-        let value_len = Self::max_bit_size(value_type, abi_version);
+        let value_len = value_type.max_bit_size(abi_version);
         let value_in_ref = Self::map_value_in_ref(key_len, value_len);
 
         let mut hashmap = HashmapE::with_bit_len(key_len);
@@ -399,8 +714,7 @@ impl TokenValue {
                 })
             }
 
-            let data =
-                Self::pack_cells_into_chain(value.write_to_cells(abi_version)?, abi_version)?;
+            let data = value.pack_into_chain_with_cache(abi_version, cache)?;
 
             let slice_key = SliceData::load_builder(key_vec.pop().unwrap().data)?;
             if value_in_ref {
@@ -416,31 +730,31 @@ impl TokenValue {
         Ok(builder)
     }
 
-    fn write_public_key(data: &Option<PublicKeyData>) -> Result<BuilderData> {
-        let mut builder = BuilderData::new();
+    fn write_public_key(data: &Option<PublicKeyData>, builder: &mut BuilderData) -> Result<()> {
         if let Some(key) = data {
             builder.append_bit_one()?;
             builder.append_raw(key, key.len() * 8)?;
         } else {
             builder.append_bit_zero()?;
         }
-        Ok(builder)
+        Ok(())
     }
 
     fn write_optional(
         param_type: &ParamType,
         value: Option<&TokenValue>,
         abi_version: &AbiVersion,
+        cache: &mut CellCache,
     ) -> Result<BuilderData> {
         if let Some(value) = value {
-            if Self::is_large_optional(param_type, abi_version) {
-                let value = value.pack_into_chain(abi_version)?;
+            if param_type.is_large_optional_value(abi_version) {
+                let value = value.pack_into_chain_with_cache(abi_version, cache)?;
                 let mut builder = BuilderData::new();
                 builder.append_bit_one()?;
                 builder.checked_append_reference(value.into_cell()?)?;
                 Ok(builder)
             } else {
-                let mut builder = value.pack_into_chain(abi_version)?;
+                let mut builder = value.pack_into_chain_with_cache(abi_version, cache)?;
                 builder.prepend_raw(&[0x80], 1)?;
                 Ok(builder)
             }
@@ -449,8 +763,12 @@ impl TokenValue {
         }
     }
 
-    fn write_ref(value: &TokenValue, abi_version: &AbiVersion) -> Result<BuilderData> {
-        let value = value.pack_into_chain(abi_version)?;
+    fn write_ref(
+        value: &TokenValue,
+        abi_version: &AbiVersion,
+        cache: &mut CellCache,
+    ) -> Result<BuilderData> {
+        let value = value.pack_into_chain_with_cache(abi_version, cache)?;
         let mut builder = BuilderData::new();
         builder.checked_append_reference(value.into_cell()?)?;
         Ok(builder)
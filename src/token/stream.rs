@@ -0,0 +1,374 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Incremental encoding and decoding of parameters.
+//!
+//! `ChainWriter` and `ChainReader` process parameters one by one on caller-provided builders and
+//! slices. Writer places every value into the cells chain as soon as it is written, without
+//! collecting serialized values first, so it fits constrained environments handling small bodies.
+//! Fixed size values are written right into the current cell, values with variable layout
+//! (arrays, maps, `bytes` etc.) are serialized separately and then appended.
+//! Since ABI 2.2 cells chain layout depends only on parameter types, so writer needs declared
+//! parameters list instead of serialized values to look ahead.
+//!
+//! `ChunkedDecoder` decodes parameters in steps of limited size, so async runtimes can interleave
+//! decoding of very large bodies with other work and enforce timeouts between steps.
+
+use crate::{
+    contract::AbiVersion,
+    error::AbiError,
+    param::Param,
+    param_type::ParamType,
+    token::{CellCache, Cursor, ReadOptions, Token, TokenValue, Utf8Handling},
+};
+
+use ever_block::{fail, BuilderData, HashmapE, HashmapType, Result, SliceData};
+use std::collections::BTreeMap;
+
+/// Writes values into cells chain one by one
+#[derive(Clone, Debug)]
+pub struct ChainWriter {
+    abi_version: AbiVersion,
+    /// Cells of the chain, the last one is filled now
+    cells: Vec<BuilderData>,
+    /// Layout size taken in the last cell
+    used_bits: usize,
+    used_refs: usize,
+    /// Layout size of values not written yet
+    remaining_bits: usize,
+    remaining_refs: usize,
+}
+
+impl ChainWriter {
+    /// Creates writer of `params` values. First written value is placed into the root cell.
+    /// Supported since ABI 2.2.
+    pub fn new(abi_version: AbiVersion, params: &[Param]) -> Result<Self> {
+        if !abi_version.at_least(2, 2) {
+            fail!(AbiError::NotSupported {
+                subject: "Incremental encoding".to_owned(),
+                version: abi_version,
+            });
+        }
+        let (remaining_bits, remaining_refs) =
+            params.iter().fold((0, 0), |(bits, refs), param| {
+                (
+                    bits + param.kind.max_bit_size(&abi_version),
+                    refs + param.kind.max_refs_count(&abi_version),
+                )
+            });
+        Ok(Self {
+            abi_version,
+            cells: vec![],
+            used_bits: 0,
+            used_refs: 0,
+            remaining_bits,
+            remaining_refs,
+        })
+    }
+
+    /// Creates writer of `params` values placed after `prefix` (e.g. function ID) in the root cell
+    pub fn with_prefix(
+        abi_version: AbiVersion,
+        prefix: BuilderData,
+        params: &[Param],
+    ) -> Result<Self> {
+        let mut writer = Self::new(abi_version, params)?;
+        writer.used_bits = prefix.bits_used();
+        writer.used_refs = prefix.references_used();
+        writer.cells.push(prefix);
+        Ok(writer)
+    }
+
+    /// Writes next value. Tuples are written component by component.
+    pub fn write(&mut self, value: &TokenValue) -> Result<&mut Self> {
+        if let TokenValue::Tuple(tokens) = value {
+            for token in tokens {
+                self.write(&token.value)?;
+            }
+            return Ok(self);
+        }
+
+        let kind = value.get_param_type();
+        let bits = kind.max_bit_size(&self.abi_version);
+        let refs = kind.max_refs_count(&self.abi_version);
+        if bits > self.remaining_bits || refs > self.remaining_refs {
+            fail!(AbiError::InvalidData {
+                msg: format!("Value of type {} exceeds declared parameters", kind)
+            });
+        }
+        self.remaining_bits -= bits;
+        self.remaining_refs -= refs;
+
+        if !self.fits(bits, refs) {
+            self.cells.push(BuilderData::new());
+            self.used_bits = 0;
+            self.used_refs = 0;
+        }
+        if let Some(current) = self.cells.last_mut() {
+            value.write_single_value_into(current, &self.abi_version, &mut CellCache::disabled())?;
+        }
+        self.used_bits += bits;
+        self.used_refs += refs;
+        Ok(self)
+    }
+
+    // checks if value fits into the last cell by the same rules as
+    // `TokenValue::pack_cells_into_chain` uses for ABI 2.2 and later
+    fn fits(&self, bits: usize, refs: usize) -> bool {
+        if self.cells.is_empty() {
+            return false;
+        }
+        let free_bits = BuilderData::bits_capacity() - self.used_bits;
+        let free_refs = BuilderData::references_capacity() - self.used_refs;
+
+        if free_bits < bits || free_refs < refs {
+            false
+        } else if refs > 0 && free_refs == refs {
+            // refs strictly fit, so the current cell is used only if all remaining values fit
+            // into it too, otherwise the last reference is needed for chaining
+            self.remaining_refs == 0 && self.remaining_bits + bits <= free_bits
+        } else {
+            true
+        }
+    }
+
+    /// Links cells into chain and returns the root cell. Fails if not all declared values were
+    /// written.
+    pub fn finish(mut self) -> Result<BuilderData> {
+        if self.remaining_bits != 0 || self.remaining_refs != 0 {
+            fail!(AbiError::InvalidData {
+                msg: "Not all declared parameters were written".to_owned()
+            });
+        }
+        let mut result = match self.cells.pop() {
+            Some(cell) => cell,
+            None => fail!(AbiError::InvalidData {
+                msg: "No cells".to_owned()
+            }),
+        };
+        while let Some(mut cell) = self.cells.pop() {
+            cell.checked_append_reference(result.into_cell()?)?;
+            result = cell;
+        }
+        Ok(result)
+    }
+}
+
+/// Reads values from cells chain one by one
+#[derive(Clone, Debug)]
+pub struct ChainReader {
+    abi_version: AbiVersion,
+    cursor: Cursor,
+    allow_partial: bool,
+}
+
+impl ChainReader {
+    pub fn new(abi_version: AbiVersion, data: SliceData, allow_partial: bool) -> Self {
+        Self {
+            abi_version,
+            cursor: data.into(),
+            allow_partial,
+        }
+    }
+
+    /// Reads next value. `last` should be set for the last value of the chain.
+    pub fn read(&mut self, kind: &ParamType, last: bool) -> Result<TokenValue> {
+        let (value, cursor) = TokenValue::read_from(
+            kind,
+            self.cursor.clone(),
+            last,
+            &self.abi_version,
+            self.allow_partial,
+        )?;
+        self.cursor = cursor;
+        Ok(value)
+    }
+
+    /// Returns cursor pointing to the data after read values
+    pub fn into_cursor(self) -> Cursor {
+        self.cursor
+    }
+}
+
+impl TokenValue {
+    /// Creates decoder of provided params yielding control after every `chunk_size` decoded values.
+    /// Entries of `map` parameters are counted as separate values.
+    pub fn decode_params_chunked(
+        params: &[Param],
+        data: SliceData,
+        abi_version: &AbiVersion,
+        allow_partial: bool,
+        chunk_size: usize,
+    ) -> ChunkedDecoder<'_> {
+        ChunkedDecoder {
+            params,
+            index: 0,
+            abi_version: *abi_version,
+            cursor: data.into(),
+            allow_partial,
+            chunk_size: chunk_size.max(1),
+            options: ReadOptions::default(),
+            map: None,
+            failed: false,
+        }
+    }
+}
+
+/// Iterator over chunks of decoded params created by `TokenValue::decode_params_chunked`.
+///
+/// Every step decodes at most `chunk_size` values and yields params completed during the step.
+/// Chunk is empty if the step was spent on entries of a large map. Concatenated chunks are equal
+/// to the result of `TokenValue::decode_params`. Iteration stops after the first error.
+#[derive(Debug)]
+pub struct ChunkedDecoder<'a> {
+    params: &'a [Param],
+    index: usize,
+    abi_version: AbiVersion,
+    cursor: Cursor,
+    allow_partial: bool,
+    chunk_size: usize,
+    options: ReadOptions,
+    /// Map parameter decoded now
+    map: Option<PendingMap>,
+    failed: bool,
+}
+
+#[derive(Debug)]
+struct PendingMap {
+    hashmap: HashmapE,
+    /// Next entry to decode. Entries are visited in keys order by dictionary seek, so the
+    /// dictionary is not traversed in advance.
+    next: Option<(BuilderData, SliceData)>,
+    value_in_ref: bool,
+    values: BTreeMap<String, TokenValue>,
+}
+
+impl ChunkedDecoder<'_> {
+    /// Sets handling of `string` values which are not valid UTF-8
+    pub fn utf8(mut self, utf8: Utf8Handling) -> Self {
+        self.options.utf8 = utf8;
+        self
+    }
+
+    /// Disables verification of ABI 2.2+ deterministic layout. See `DecodeOptions`.
+    pub fn skip_layout_check(mut self, skip_layout_check: bool) -> Self {
+        self.options.check_layout = !skip_layout_check;
+        self
+    }
+
+    /// Returns number of params completely decoded so far
+    pub fn decoded_count(&self) -> usize {
+        self.index
+    }
+
+    fn next_chunk(&mut self) -> Result<Vec<Token>> {
+        let params = self.params;
+        let mut tokens = vec![];
+        let mut budget = self.chunk_size;
+        while budget > 0 && self.index < params.len() {
+            let param = &params[self.index];
+            let last = self.index + 1 == params.len();
+            let (key_type, value_type) = match &param.kind {
+                ParamType::Map(key_type, value_type) => (key_type.as_ref(), value_type.as_ref()),
+                _ => {
+                    let (value, cursor) = TokenValue::read_value(
+                        &param.kind,
+                        self.cursor.clone(),
+                        last,
+                        &self.abi_version,
+                        self.allow_partial,
+                        self.options,
+                    )?;
+                    self.cursor = cursor;
+                    tokens.push(Token::new(&param.name, value));
+                    self.index += 1;
+                    budget -= 1;
+                    continue;
+                }
+            };
+
+            if self.map.is_none() {
+                self.map = Some(self.read_map(&param.kind, value_type, last)?);
+            }
+            let map = self.map.as_mut().unwrap();
+            while budget > 0 {
+                let (key, value) = match map.next.take() {
+                    Some(entry) => entry,
+                    None => break,
+                };
+                let key = SliceData::load_builder(key)?;
+                map.next = map.hashmap.find_leaf(key.clone(), true, false, false, &mut 0)?;
+                let (key, value) = TokenValue::read_map_entry(
+                    key_type,
+                    value_type,
+                    map.value_in_ref,
+                    (key, value),
+                    &self.abi_version,
+                    self.allow_partial,
+                    self.options,
+                )?;
+                map.values.insert(key, value);
+                budget -= 1;
+            }
+            if map.next.is_none() {
+                let values = std::mem::take(&mut map.values);
+                self.map = None;
+                tokens.push(Token::new(
+                    &param.name,
+                    TokenValue::Map(key_type.clone(), value_type.clone(), values),
+                ));
+                self.index += 1;
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn read_map(
+        &mut self,
+        param_type: &ParamType,
+        value_type: &ParamType,
+        last: bool,
+    ) -> Result<PendingMap> {
+        let (hashmap, cursor) = TokenValue::read_map_dictionary(
+            param_type,
+            self.cursor.clone(),
+            last,
+            &self.abi_version,
+            self.allow_partial,
+            self.options,
+        )?;
+        self.cursor = cursor;
+        Ok(PendingMap {
+            next: hashmap.get_min(false, &mut 0)?,
+            value_in_ref: TokenValue::map_value_in_ref(
+                hashmap.bit_len(),
+                value_type.max_bit_size(&self.abi_version),
+            ),
+            hashmap,
+            values: BTreeMap::new(),
+        })
+    }
+}
+
+impl Iterator for ChunkedDecoder<'_> {
+    type Item = Result<Vec<Token>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.index == self.params.len() {
+            return None;
+        }
+        let result = self.next_chunk();
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
@@ -26,7 +26,9 @@ use crate::contract::{
     AbiVersion, ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_1, ABI_VERSION_2_2,
     MAX_SUPPORTED_VERSION, ABI_VERSION_2_4, ABI_VERSION_2_3,
 };
-use crate::token::Cursor;
+use crate::token::{
+    CellCache, ChainPacker, ChainReader, ChainWriter, Cursor, MapPage, Utf8Handling,
+};
 use crate::{Int, Param, ParamType, Token, TokenValue, Uint, AbiError};
 
 fn put_array_into_map<T: Serializable>(array: &[T]) -> HashmapE {
@@ -1533,7 +1535,7 @@ fn test_wrong_layout() {
                 .unwrap_err()
                 .downcast::<AbiError>()
                 .unwrap(),
-            AbiError::WrongDataLayout,
+            AbiError::WrongDataLayout { .. },
         )
     );
     assert!(
@@ -1542,7 +1544,7 @@ fn test_wrong_layout() {
                 .unwrap_err()
                 .downcast::<AbiError>()
                 .unwrap(),
-            AbiError::WrongDataLayout,
+            AbiError::WrongDataLayout { .. },
         )
     );
     assert!( 
@@ -1551,7 +1553,7 @@ fn test_wrong_layout() {
                 .unwrap_err()
                 .downcast::<AbiError>()
                 .unwrap(),
-            AbiError::WrongDataLayout,
+            AbiError::WrongDataLayout { .. },
         )
     );
 
@@ -1574,9 +1576,24 @@ fn test_wrong_layout() {
                 .unwrap_err()
                 .downcast::<AbiError>()
                 .unwrap(),
-            AbiError::WrongDataLayout,
+            AbiError::WrongDataLayout { .. },
         )
     );
+
+    // addresses may take up to 591 bits, so the second one must start the next cell
+    let err = TokenValue::decode_params(&params, slice, &ABI_VERSION_2_2, false)
+        .unwrap_err()
+        .downcast::<AbiError>()
+        .unwrap();
+    match &err {
+        AbiError::WrongDataLayout { param, version, used_bits, used_refs, value_bits, .. } => {
+            assert_eq!(param, "b");
+            assert_eq!(version, &ABI_VERSION_2_2);
+            assert_eq!((*used_bits, *used_refs, *value_bits), (591, 0, 591));
+        }
+        err => panic!("unexpected error {}", err),
+    }
+    assert!(err.to_string().starts_with("Wrong data layout of `b` parameter: value which may not"));
 }
 
 #[test]
@@ -1609,3 +1626,750 @@ fn test_fixed_bytes() {
         &[ABI_VERSION_2_4],
     );
 }
+
+#[test]
+fn test_random_values() {
+    let types = vec![
+        ParamType::Uint(256),
+        ParamType::Int(17),
+        ParamType::VarUint(16),
+        ParamType::VarInt(32),
+        ParamType::Bool,
+        ParamType::Array(Box::new(ParamType::Tuple(vec![
+            Param::new("x", ParamType::Address),
+            Param::new("y", ParamType::String),
+        ]))),
+        ParamType::FixedArray(Box::new(ParamType::Bytes), 2),
+        ParamType::Map(Box::new(ParamType::Address), Box::new(ParamType::Token)),
+        ParamType::Map(Box::new(ParamType::Int(8)), Box::new(ParamType::Cell)),
+        ParamType::Optional(Box::new(ParamType::PublicKey)),
+        ParamType::Ref(Box::new(ParamType::FixedBytes(16))),
+        ParamType::Time,
+        ParamType::Expire,
+    ];
+    let params = params_from_types(types);
+
+    let mut state = 0x2545F4914F6CDD1Du64;
+    let mut rng = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for extreme_values in [false, true] {
+        let config = crate::token::RandomConfig {
+            extreme_values,
+            ..Default::default()
+        };
+        for _ in 0..16 {
+            let tokens: Vec<Token> = params
+                .iter()
+                .map(|param| Token {
+                    name: param.name.clone(),
+                    value: TokenValue::random(&param.kind, &mut rng, &config).unwrap(),
+                })
+                .collect();
+            assert!(Token::types_check(&tokens, &params));
+
+            let builder =
+                TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_4).unwrap();
+            let decoded = TokenValue::decode_params(
+                &params,
+                SliceData::load_builder(builder).unwrap(),
+                &ABI_VERSION_2_4,
+                false,
+            )
+            .unwrap();
+            assert_eq!(decoded, tokens);
+        }
+    }
+}
+
+#[test]
+fn test_chain_packer() {
+    let tokens = tokens_from_values(vec![TokenValue::Uint(Uint::new(1, 256)); 5]);
+
+    for version in [ABI_VERSION_1_0, ABI_VERSION_2_2, ABI_VERSION_2_4] {
+        let mut prefix = BuilderData::new();
+        prefix.append_u32(0xABCD).unwrap();
+
+        let expected =
+            TokenValue::pack_values_into_chain(&tokens, vec![prefix.clone().into()], &version)
+                .unwrap();
+
+        let mut packer = crate::token::ChainPacker::new(version);
+        packer.push_builder(prefix).push_tokens(&tokens).unwrap();
+        assert_eq!(packer.values().len(), 6);
+        assert_eq!(packer.pack().unwrap(), expected);
+    }
+
+    // reserved layout size moves values into next cell since ABI 2.2
+    let mut prefix = BuilderData::new();
+    prefix.append_u32(0xABCD).unwrap();
+    let prefix = crate::token::SerializedValue::with_layout(prefix, 1023, 0).unwrap();
+    let mut packer = crate::token::ChainPacker::new(ABI_VERSION_2_4);
+    packer.push_value(prefix);
+    packer.push_token(&TokenValue::Uint(Uint::new(1, 8))).unwrap();
+    let packed = packer.pack().unwrap();
+    assert_eq!(packed.bits_used(), 32);
+    assert_eq!(packed.references_used(), 1);
+
+    let mut too_long = BuilderData::new();
+    too_long.append_u32(0).unwrap();
+    assert!(crate::token::SerializedValue::with_layout(too_long, 31, 0).is_err());
+
+    assert!(crate::token::ChainPacker::new(ABI_VERSION_2_4).pack().is_err());
+}
+
+#[test]
+fn test_lazy_map() {
+    let value_type = ParamType::Tuple(vec![
+        Param::new("a", ParamType::Uint(256)),
+        Param::new("b", ParamType::Uint(256)),
+        Param::new("c", ParamType::Uint(256)),
+        Param::new("d", ParamType::Uint(256)),
+    ]);
+    let mut map = BTreeMap::new();
+    for i in 0..10u32 {
+        map.insert(
+            i.to_string(),
+            TokenValue::Tuple(tokens_from_values(vec![TokenValue::Uint(Uint::new(i as u128, 256)); 4])),
+        );
+    }
+    let expected = TokenValue::Map(ParamType::Uint(32), value_type.clone(), map.clone());
+
+    for version in [ABI_VERSION_2_0, ABI_VERSION_2_4] {
+        let mut builder = expected.pack_into_chain(&version).unwrap();
+        builder.append_u8(0xAB).unwrap();
+        let slice = SliceData::load_builder(builder).unwrap();
+
+        let (lazy, mut cursor) =
+            TokenValue::read_lazy_map(&ParamType::Uint(32), &value_type, slice, &version).unwrap();
+        assert_eq!(cursor.get_next_byte().unwrap(), 0xAB);
+        assert!(!lazy.is_empty());
+
+        let key = TokenValue::Uint(Uint::new(7, 32));
+        assert_eq!(lazy.get(&key).unwrap().as_ref(), map.get("7"));
+        assert_eq!(lazy.get(&TokenValue::Uint(Uint::new(70, 32))).unwrap(), None);
+        assert!(lazy.get(&TokenValue::Uint(Uint::new(7, 64))).is_err());
+
+        let first: Vec<(TokenValue, TokenValue)> =
+            lazy.iter().take(2).collect::<Result<_>>().unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].0, TokenValue::Uint(Uint::new(0, 32)));
+        assert_eq!(Some(&first[1].1), map.get("1"));
+    }
+}
+
+#[test]
+fn test_decode_map_range() {
+    let mut map = BTreeMap::new();
+    for i in 0..20u32 {
+        map.insert(
+            i.to_string(),
+            TokenValue::Uint(Uint::new(i as u128 * 10, 64)),
+        );
+    }
+    let value = TokenValue::Map(ParamType::Uint(16), ParamType::Uint(64), map);
+    let slice = SliceData::load_builder(value.pack_into_chain(&ABI_VERSION_2_4).unwrap()).unwrap();
+
+    let range = |start: Option<u32>, limit| {
+        let start = start.map(|key| TokenValue::Uint(Uint::new(key as u128, 16)));
+        TokenValue::decode_map_range(
+            slice.clone(),
+            &ParamType::Uint(16),
+            &ParamType::Uint(64),
+            start.as_ref(),
+            limit,
+            &ABI_VERSION_2_4,
+        )
+        .unwrap()
+    };
+
+    let page = range(None, 5);
+    assert_eq!(page.entries.len(), 5);
+    assert_eq!(page.entries[0].0, TokenValue::Uint(Uint::new(0, 16)));
+    assert_eq!(page.next_key, Some(TokenValue::Uint(Uint::new(5, 16))));
+
+    let page = range(Some(17), 5);
+    assert_eq!(page.entries.len(), 3);
+    assert_eq!(
+        page.entries[0],
+        (
+            TokenValue::Uint(Uint::new(17, 16)),
+            TokenValue::Uint(Uint::new(170, 64))
+        )
+    );
+    assert_eq!(page.next_key, None);
+
+    assert!(range(Some(100), 5).entries.is_empty());
+
+    let mut map = BTreeMap::new();
+    for i in [-300i128, -2, 0, 5, 300] {
+        map.insert(i.to_string(), TokenValue::Bool(i > 0));
+    }
+    let value = TokenValue::Map(ParamType::Int(16), ParamType::Bool, map);
+    let slice = SliceData::load_builder(value.pack_into_chain(&ABI_VERSION_2_4).unwrap()).unwrap();
+    let range = |start: Option<i128>, limit| {
+        let start = start.map(|key| TokenValue::Int(Int::new(key, 16)));
+        TokenValue::decode_map_range(
+            slice.clone(),
+            &ParamType::Int(16),
+            &ParamType::Bool,
+            start.as_ref(),
+            limit,
+            &ABI_VERSION_2_4,
+        )
+    };
+    let keys = |page: &MapPage| -> Vec<TokenValue> {
+        page.entries.iter().map(|(key, _)| key.clone()).collect()
+    };
+
+    let page = range(None, 3).unwrap();
+    assert_eq!(
+        keys(&page),
+        vec![
+            TokenValue::Int(Int::new(-300, 16)),
+            TokenValue::Int(Int::new(-2, 16)),
+            TokenValue::Int(Int::new(0, 16)),
+        ]
+    );
+    assert_eq!(page.next_key, Some(TokenValue::Int(Int::new(5, 16))));
+
+    let page = range(Some(-100), 2).unwrap();
+    assert_eq!(
+        keys(&page),
+        vec![TokenValue::Int(Int::new(-2, 16)), TokenValue::Int(Int::new(0, 16))]
+    );
+    assert_eq!(page.next_key, Some(TokenValue::Int(Int::new(5, 16))));
+
+    let page = range(Some(1), 5).unwrap();
+    assert_eq!(
+        keys(&page),
+        vec![TokenValue::Int(Int::new(5, 16)), TokenValue::Int(Int::new(300, 16))]
+    );
+    assert_eq!(page.next_key, None);
+
+    assert!(range(None, 0).is_err());
+}
+
+#[test]
+fn test_lazy_array() {
+    let items: Vec<TokenValue> = (0..50u32)
+        .map(|i| TokenValue::Uint(Uint::new(i as u128, 256)))
+        .collect();
+
+    for version in [ABI_VERSION_1_0, ABI_VERSION_2_0, ABI_VERSION_2_4] {
+        for param_type in [
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+            ParamType::FixedArray(Box::new(ParamType::Uint(256)), 50),
+        ] {
+            let value = match param_type {
+                ParamType::Array(_) => TokenValue::Array(ParamType::Uint(256), items.clone()),
+                _ => TokenValue::FixedArray(ParamType::Uint(256), items.clone()),
+            };
+            let mut builder = value.pack_into_chain(&version).unwrap();
+            builder.append_u8(0xAB).unwrap();
+            let slice = SliceData::load_builder(builder).unwrap();
+
+            let (lazy, mut cursor) =
+                TokenValue::read_lazy_array(&param_type, slice, &version).unwrap();
+            assert_eq!(cursor.get_next_byte().unwrap(), 0xAB);
+            assert_eq!(lazy.len(), 50);
+            assert_eq!(lazy.get(42).unwrap().as_ref(), Some(&items[42]));
+            assert_eq!(lazy.get(50).unwrap(), None);
+
+            let decoded: Vec<TokenValue> = lazy.iter().collect::<Result<_>>().unwrap();
+            assert_eq!(decoded, items);
+        }
+    }
+
+    assert!(TokenValue::read_lazy_array(
+        &ParamType::Uint(32),
+        SliceData::new_empty(),
+        &ABI_VERSION_2_4
+    )
+    .is_err());
+}
+
+#[test]
+fn test_apply_map_diff() {
+    let key = |i: u32| TokenValue::Uint(Uint::new(i as u128, 32));
+    let value = |s: &str| TokenValue::String(s.to_owned());
+    let mut map = BTreeMap::new();
+    for i in 0..5u32 {
+        map.insert(i.to_string(), value(&i.to_string()));
+    }
+    let original = TokenValue::Map(ParamType::Uint(32), ParamType::String, map.clone());
+    let mut slice =
+        SliceData::load_builder(original.pack_into_chain(&ABI_VERSION_2_4).unwrap()).unwrap();
+    let map_cell = slice.get_dictionary().unwrap().reference_opt(0);
+
+    let updated = TokenValue::apply_map_diff(
+        map_cell,
+        &[(key(1), value("one")), (key(10), value("ten"))],
+        &[key(3), key(100)],
+        &ParamType::Uint(32),
+        &ParamType::String,
+        &ABI_VERSION_2_4,
+    )
+    .unwrap();
+
+    map.insert("1".to_owned(), value("one"));
+    map.insert("10".to_owned(), value("ten"));
+    map.remove("3");
+    let expected = TokenValue::Map(ParamType::Uint(32), ParamType::String, map);
+    let mut builder = BuilderData::new();
+    HashmapE::with_hashmap(32, updated).write_to(&mut builder).unwrap();
+    assert_eq!(builder, expected.pack_into_chain(&ABI_VERSION_2_4).unwrap());
+
+    let updated = TokenValue::apply_map_diff(
+        None,
+        &[],
+        &[key(1)],
+        &ParamType::Uint(32),
+        &ParamType::String,
+        &ABI_VERSION_2_4,
+    )
+    .unwrap();
+    assert!(updated.is_none());
+
+    assert!(TokenValue::apply_map_diff(
+        None,
+        &[(key(1), TokenValue::Bool(true))],
+        &[],
+        &ParamType::Uint(32),
+        &ParamType::String,
+        &ABI_VERSION_2_4,
+    )
+    .is_err());
+
+    // large tuple values are placed the same way as by eager encoding, as is or promoted
+    let tuple_type = ParamType::Tuple(params_from_types(vec![ParamType::Uint(256); 4]));
+    let tuple = |i: u32| {
+        TokenValue::Tuple(tokens_from_values(vec![
+            TokenValue::Uint(Uint::new(i as u128, 256));
+            4
+        ]))
+    };
+    for promote in [false, true] {
+        let promoted = |value: TokenValue| match promote {
+            true => value.promote_large_tuples(&ABI_VERSION_2_4),
+            false => value,
+        };
+        let value_type = match promote {
+            true => tuple_type.promote_large_tuples(&ABI_VERSION_2_4),
+            false => tuple_type.clone(),
+        };
+        let (one, two) = (promoted(tuple(1)), promoted(tuple(2)));
+        let updated = TokenValue::apply_map_diff(
+            None,
+            &[(key(1), one.clone()), (key(2), two.clone())],
+            &[],
+            &ParamType::Uint(32),
+            &value_type,
+            &ABI_VERSION_2_4,
+        )
+        .unwrap();
+        let mut builder = BuilderData::new();
+        HashmapE::with_hashmap(32, updated).write_to(&mut builder).unwrap();
+        let params = params_from_types(vec![ParamType::Map(
+            Box::new(ParamType::Uint(32)),
+            Box::new(value_type.clone()),
+        )]);
+        let decoded = TokenValue::decode_params(
+            &params,
+            SliceData::load_builder(builder).unwrap(),
+            &ABI_VERSION_2_4,
+            false,
+        )
+        .unwrap();
+        let expected = BTreeMap::from_iter([("1".to_owned(), one), ("2".to_owned(), two)]);
+        assert_eq!(
+            decoded[0].value,
+            TokenValue::Map(ParamType::Uint(32), value_type, expected)
+        );
+    }
+}
+
+#[test]
+fn test_pfx_map() {
+    let mut map = BTreeMap::new();
+    for i in 0..10u32 {
+        map.insert(
+            (i * 1000).to_string(),
+            TokenValue::Tuple(tokens_from_values(vec![
+                TokenValue::Uint(Uint::new(i as u128, 256)),
+                TokenValue::Uint(Uint::new(i as u128, 256)),
+                TokenValue::Uint(Uint::new(i as u128, 256)),
+                TokenValue::Uint(Uint::new(i as u128, 256)),
+            ])),
+        );
+    }
+    let value_type = ParamType::Tuple(params_from_types(vec![ParamType::Uint(256); 4]));
+    let value = TokenValue::Map(ParamType::Uint(32), value_type.clone(), map);
+
+    let mut builder = value.write_pfx_map(&ABI_VERSION_2_4).unwrap();
+    builder.append_u8(0xAB).unwrap();
+    let slice = SliceData::load_builder(builder).unwrap();
+
+    let (decoded, mut cursor) =
+        TokenValue::read_pfx_map(&ParamType::Uint(32), &value_type, slice, &ABI_VERSION_2_4)
+            .unwrap();
+    assert_eq!(cursor.get_next_byte().unwrap(), 0xAB);
+    assert_eq!(decoded, value);
+
+    assert!(TokenValue::Bool(true).write_pfx_map(&ABI_VERSION_2_4).is_err());
+}
+
+#[test]
+fn test_slice() {
+    let slice = SliceData::from_raw(vec![0xAB, 0xC0], 12);
+    let mut expected = BuilderData::new();
+    expected.append_bits(12, 10).unwrap();
+    expected.append_raw(&[0xAB, 0xC0], 12).unwrap();
+    assert_eq!(
+        TokenValue::Slice(slice.clone()).pack_into_chain(&ABI_VERSION_2_4).unwrap(),
+        expected
+    );
+
+    let values = vec![
+        TokenValue::Slice(slice),
+        TokenValue::Slice(SliceData::new_empty()),
+        TokenValue::Uint(Uint::new(0xFF, 8)),
+    ];
+    let tokens = tokens_from_values(values);
+    let params = params_from_types(vec![ParamType::Slice, ParamType::Slice, ParamType::Uint(8)]);
+    let data = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_4).unwrap();
+    let decoded = TokenValue::decode_params(
+        &params,
+        SliceData::load_builder(data).unwrap(),
+        &ABI_VERSION_2_4,
+        false,
+    )
+    .unwrap();
+    assert_eq!(decoded, tokens);
+
+    let too_long = TokenValue::Slice(SliceData::from_raw(vec![0; 127], 1014));
+    assert!(too_long.write_to_cells(&ABI_VERSION_2_4).is_err());
+    assert!(!ParamType::Slice.is_supported(&ABI_VERSION_2_3));
+}
+
+#[test]
+fn test_varint_oversized_len() {
+    let decode = |param_type: ParamType, len_bits: usize, len: usize| {
+        let mut data = BuilderData::new();
+        data.append_bits(len, len_bits).unwrap();
+        data.append_raw(&vec![0x11; len], len * 8).unwrap();
+        TokenValue::decode_params(
+            &params_from_types(vec![param_type]),
+            SliceData::load_builder(data).unwrap(),
+            &ABI_VERSION_2_4,
+            false,
+        )
+    };
+
+    // 4 bits length prefix of varuint10 can encode lengths up to 15 bytes while only 9 are allowed
+    assert_eq!(
+        decode(ParamType::VarUint(10), 4, 9).unwrap()[0].value,
+        TokenValue::VarUint(10, BigUint::from_bytes_be(&[0x11; 9]))
+    );
+    assert!(decode(ParamType::VarUint(10), 4, 10).is_err());
+    assert!(decode(ParamType::VarUint(10), 4, 15).is_err());
+
+    assert_eq!(
+        decode(ParamType::VarInt(24), 5, 23).unwrap()[0].value,
+        TokenValue::VarInt(24, BigInt::from_signed_bytes_be(&[0x11; 23]))
+    );
+    assert!(decode(ParamType::VarInt(24), 5, 24).is_err());
+    assert!(decode(ParamType::VarInt(24), 5, 31).is_err());
+}
+
+#[test]
+fn test_string_utf8_handling() {
+    let data = vec![b'a', 0xff, b'b'];
+    let body = TokenValue::Bytes(data.clone()).pack_into_chain(&ABI_VERSION_2_4).unwrap();
+    let decode = |utf8| {
+        TokenValue::decode_params_with_utf8_handling(
+            &params_from_types(vec![ParamType::String]),
+            SliceData::load_builder(body.clone()).unwrap(),
+            &ABI_VERSION_2_4,
+            false,
+            utf8,
+        )
+    };
+
+    assert!(decode(Utf8Handling::Strict).is_err());
+    assert!(TokenValue::decode_params(
+        &params_from_types(vec![ParamType::String]),
+        SliceData::load_builder(body.clone()).unwrap(),
+        &ABI_VERSION_2_4,
+        false,
+    )
+    .is_err());
+    assert_eq!(
+        decode(Utf8Handling::Lossy).unwrap()[0].value,
+        TokenValue::String("a\u{FFFD}b".to_owned())
+    );
+    assert_eq!(decode(Utf8Handling::AsBytes).unwrap()[0].value, TokenValue::Bytes(data));
+}
+
+#[test]
+fn test_cell_cache() {
+    let payload = TokenValue::Bytes(vec![0x55; 300]);
+    let item = TokenValue::Tuple(vec![
+        Token::new("id", TokenValue::Uint(Uint::new(1, 32))),
+        Token::new("payload", payload.clone()),
+    ]);
+    let mut items = vec![item.clone(); 5];
+    items.push(TokenValue::Tuple(vec![
+        Token::new("id", TokenValue::Uint(Uint::new(2, 32))),
+        Token::new("payload", payload.clone()),
+    ]));
+    let value = TokenValue::Array(item.get_param_type(), items);
+    let map = TokenValue::Map(
+        ParamType::Uint(8),
+        ParamType::Bytes,
+        BTreeMap::from_iter(vec![("1".to_owned(), payload.clone()), ("2".to_owned(), payload)]),
+    );
+
+    for value in [value, map] {
+        let mut cache = CellCache::new();
+        let cached = value.pack_into_chain_with_cache(&ABI_VERSION_2_4, &mut cache).unwrap();
+        assert_eq!(cached, value.pack_into_chain(&ABI_VERSION_2_4).unwrap());
+        assert_eq!(cache.len(), 1);
+    }
+
+    let tokens = [
+        Token::new("a", TokenValue::String("hello".to_owned())),
+        Token::new("b", TokenValue::Bytes(b"hello".to_vec())),
+    ];
+    let mut packer = ChainPacker::new(ABI_VERSION_2_4).with_cell_cache();
+    packer.push_tokens(&tokens).unwrap();
+    let mut expected = ChainPacker::new(ABI_VERSION_2_4);
+    expected.push_tokens(&tokens).unwrap();
+    assert_eq!(packer.pack().unwrap(), expected.pack().unwrap());
+
+    // `bytes` layout differs between versions, so cells are not shared across them
+    let value = TokenValue::Bytes(vec![7; 200]);
+    let mut cache = CellCache::new();
+    for version in [ABI_VERSION_2_4, ABI_VERSION_1_0] {
+        assert_eq!(
+            value.pack_into_chain_with_cache(&version, &mut cache).unwrap(),
+            value.pack_into_chain(&version).unwrap()
+        );
+    }
+    assert_eq!(cache.len(), 2);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_array() {
+    use crate::token::PARALLEL_ARRAY_THRESHOLD;
+
+    for item_type in [ParamType::Uint(32), ParamType::Bytes, ParamType::Uint(1000)] {
+        let values: Vec<TokenValue> = (0..PARALLEL_ARRAY_THRESHOLD + 10)
+            .map(|i| match item_type {
+                ParamType::Uint(size) => TokenValue::Uint(Uint::new(i as u128, size)),
+                _ => TokenValue::Bytes((i as u32).to_be_bytes().to_vec()),
+            })
+            .collect();
+        let value = TokenValue::Array(item_type.clone(), values);
+
+        // cell cache disables parallel serialization
+        let sequential = value
+            .pack_into_chain_with_cache(&ABI_VERSION_2_4, &mut CellCache::new())
+            .unwrap();
+        assert_eq!(value.pack_into_chain(&ABI_VERSION_2_4).unwrap(), sequential);
+    }
+}
+
+#[test]
+fn test_chain_writer() {
+    let cell = TokenValue::Cell(BuilderData::with_raw(vec![0x55], 8).unwrap().into_cell().unwrap());
+    let tokens = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(1, 256))),
+        Token::new("b", TokenValue::Bytes(vec![1; 10])),
+        Token::new("c", cell.clone()),
+        Token::new("d", cell.clone()),
+        Token::new(
+            "e",
+            TokenValue::Tuple(vec![
+                Token::new("x", cell),
+                Token::new("y", TokenValue::Int(Int::new(-5, 200))),
+            ]),
+        ),
+        Token::new("f", TokenValue::String("hello".to_owned())),
+        Token::new("g", TokenValue::Bool(true)),
+    ];
+    let params: Vec<Param> = tokens
+        .iter()
+        .map(|token| Param::new(&token.name, token.value.get_param_type()))
+        .collect();
+
+    let mut prefix = BuilderData::new();
+    prefix.append_u32(0x12345678).unwrap();
+    for prefix in [None, Some(prefix)] {
+        let (mut writer, cells) = match prefix {
+            Some(prefix) => (
+                ChainWriter::with_prefix(ABI_VERSION_2_4, prefix.clone(), &params).unwrap(),
+                vec![prefix.into()],
+            ),
+            None => (ChainWriter::new(ABI_VERSION_2_4, &params).unwrap(), vec![]),
+        };
+        for token in &tokens {
+            writer.write(&token.value).unwrap();
+        }
+        let expected =
+            TokenValue::pack_values_into_chain(&tokens, cells.clone(), &ABI_VERSION_2_4).unwrap();
+        let body = writer.finish().unwrap();
+        assert_eq!(body, expected);
+
+        let mut slice = SliceData::load_builder(body).unwrap();
+        if !cells.is_empty() {
+            assert_eq!(slice.get_next_u32().unwrap(), 0x12345678);
+        }
+        let mut reader = ChainReader::new(ABI_VERSION_2_4, slice, false);
+        for (i, param) in params.iter().enumerate() {
+            let value = reader.read(&param.kind, i + 1 == params.len()).unwrap();
+            assert_eq!(value, tokens[i].value);
+        }
+    }
+
+    let mut writer = ChainWriter::new(ABI_VERSION_2_4, &params[..1]).unwrap();
+    assert!(writer.write(&TokenValue::Bool(true)).unwrap().write(&tokens[0].value).is_err());
+    assert!(ChainWriter::new(ABI_VERSION_2_4, &params).unwrap().finish().is_err());
+    assert!(ChainWriter::new(ABI_VERSION_2_1, &params).is_err());
+}
+
+#[test]
+fn test_decode_params_chunked() {
+    let entries = (0..10u64).map(|i| (i.to_string(), TokenValue::Uint(Uint::new(i.into(), 64))));
+    let tokens = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(1, 256))),
+        Token::new(
+            "b",
+            TokenValue::Map(ParamType::Uint(32), ParamType::Uint(64), BTreeMap::from_iter(entries)),
+        ),
+        Token::new("c", TokenValue::Map(ParamType::Int(8), ParamType::Bool, BTreeMap::new())),
+        Token::new("d", TokenValue::Bool(true)),
+    ];
+    let params: Vec<Param> = tokens
+        .iter()
+        .map(|token| Param::new(&token.name, token.value.get_param_type()))
+        .collect();
+    let body = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_4).unwrap();
+    let slice = SliceData::load_builder(body).unwrap();
+
+    let chunks: Vec<Vec<Token>> =
+        TokenValue::decode_params_chunked(&params, slice.clone(), &ABI_VERSION_2_4, false, 3)
+            .collect::<Result<_>>()
+            .unwrap();
+    let lens: Vec<usize> = chunks.iter().map(Vec::len).collect();
+    assert_eq!(lens, [1, 0, 0, 3]);
+    assert_eq!(chunks.concat(), tokens);
+    assert_eq!(
+        chunks.concat(),
+        TokenValue::decode_params(&params, slice.clone(), &ABI_VERSION_2_4, false).unwrap()
+    );
+
+    let mut decoder =
+        TokenValue::decode_params_chunked(&params, slice.clone(), &ABI_VERSION_2_4, false, 0);
+    assert_eq!(decoder.next().unwrap().unwrap(), tokens[..1]);
+    assert_eq!(decoder.decoded_count(), 1);
+    assert_eq!(decoder.count(), 11);
+
+    let mut decoder =
+        TokenValue::decode_params_chunked(&params[..2], slice, &ABI_VERSION_2_4, false, 100);
+    assert!(decoder.next().unwrap().is_err());
+    assert!(decoder.next().is_none());
+
+    // decoding options are applied to map entries
+    let entries = [("1".to_owned(), TokenValue::Bytes(vec![0xff]))];
+    let tokens = [Token::new(
+        "m",
+        TokenValue::Map(ParamType::Uint(8), ParamType::Bytes, BTreeMap::from_iter(entries)),
+    )];
+    let params = [Param::new(
+        "m",
+        ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::String)),
+    )];
+    let body = TokenValue::pack_values_into_chain(&tokens, vec![], &ABI_VERSION_2_4).unwrap();
+    let slice = SliceData::load_builder(body).unwrap();
+    let decode = |utf8| {
+        TokenValue::decode_params_chunked(&params, slice.clone(), &ABI_VERSION_2_4, false, 1)
+            .utf8(utf8)
+            .collect::<Result<Vec<Vec<Token>>>>()
+    };
+    assert!(decode(Utf8Handling::Strict).is_err());
+    assert_eq!(
+        decode(Utf8Handling::Lossy).unwrap().concat(),
+        TokenValue::decode_params_with_utf8_handling(
+            &params,
+            slice.clone(),
+            &ABI_VERSION_2_4,
+            false,
+            Utf8Handling::Lossy
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_large_tuple_promotion() {
+    let tuple = TokenValue::Tuple(
+        (0..4)
+            .map(|i| Token::new(&format!("x{}", i), TokenValue::Uint(Uint::new(i, 256))))
+            .collect(),
+    );
+    let tokens = tokens_from_values(vec![
+        TokenValue::Uint(Uint::new(1, 8)),
+        tuple.clone(),
+        TokenValue::Array(tuple.get_param_type(), vec![tuple.clone(); 2]),
+    ]);
+    let params = params_from_tokens(&tokens);
+
+    // tuple exceeding cell capacity is spread across cells chain unless promoted explicitly
+    for version in [ABI_VERSION_2_3, ABI_VERSION_2_4] {
+        let body = TokenValue::pack_values_into_chain(&tokens, vec![], &version).unwrap();
+        assert_eq!(body.length_in_bits(), 8 + 256 * 3);
+        let decoded = TokenValue::decode_params(
+            &params,
+            SliceData::load_builder(body).unwrap(),
+            &version,
+            false,
+        )
+        .unwrap();
+        assert_eq!(decoded, tokens);
+    }
+
+    // since ABI 2.4 promoted tuple is stored in reference
+    let promoted: Vec<Token> = tokens
+        .iter()
+        .map(|token| Token {
+            name: token.name.clone(),
+            value: token.value.clone().promote_large_tuples(&ABI_VERSION_2_4),
+        })
+        .collect();
+    assert_eq!(promoted[1].value, tuple.clone().into_ref());
+    let body = TokenValue::pack_values_into_chain(&promoted, vec![], &ABI_VERSION_2_4).unwrap();
+    assert_eq!(body.length_in_bits(), 8 + 33);
+    let decoded = TokenValue::decode_params(
+        &params_from_tokens(&promoted),
+        SliceData::load_builder(body).unwrap(),
+        &ABI_VERSION_2_4,
+        false,
+    )
+    .unwrap();
+    assert_eq!(decoded, promoted);
+    let restored: Vec<Token> = decoded
+        .into_iter()
+        .zip(&params)
+        .map(|(token, param)| Token {
+            name: token.name,
+            value: token.value.restore_promoted_tuples(&param.kind),
+        })
+        .collect();
+    assert_eq!(restored, tokens);
+}
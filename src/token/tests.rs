@@ -12,8 +12,12 @@
 */
 
 mod tokenize_tests {
-    use crate::token::{Detokenizer, Tokenizer};
-    use crate::{Int, Param, ParamType, Token, TokenValue, Uint};
+    use crate::token::{
+        AddressFormat, AmountFormat, DetokenizeOptions, Detokenizer, IntFormat, Redaction,
+        RedactionMode, TokenizeOptions, Tokenizer,
+    };
+    use crate::{AbiError, Int, IntBounds, Param, ParamType, Token, TokenValue, Uint};
+    use num_bigint::BigInt;
     use std::collections::BTreeMap;
     use ever_block::{Grams, MsgAddress};
     use ever_block::{AccountId, BuilderData, Cell, SliceData, ED25519_PUBLIC_KEY_LENGTH};
@@ -542,6 +546,98 @@ mod tokenize_tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_empty_cell() {
+        let params = vec![
+            Param::new("a", ParamType::Cell),
+            Param::new("b", ParamType::Optional(Box::new(ParamType::Cell))),
+        ];
+        let input = serde_json::json!({ "a": "", "b": "" });
+
+        assert_eq!(
+            Tokenizer::tokenize_all_params(&params, &input).unwrap(),
+            vec![
+                Token::new("a", TokenValue::Cell(Cell::default())),
+                Token::new(
+                    "b",
+                    TokenValue::Optional(
+                        ParamType::Cell,
+                        Some(Box::new(TokenValue::Cell(Cell::default())))
+                    )
+                ),
+            ]
+        );
+
+        let options = TokenizeOptions {
+            strict_cell: true,
+            empty_cell_as_none: true,
+            ..Default::default()
+        };
+        assert!(Tokenizer::tokenize_all_params_with_options(&params, &input, &options).is_err());
+
+        let input = serde_json::json!({ "a": "te6ccgEBAQEAAgAAAA==", "b": "" });
+        assert_eq!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &options).unwrap(),
+            vec![
+                Token::new("a", TokenValue::Cell(Cell::default())),
+                Token::new("b", TokenValue::Optional(ParamType::Cell, None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_amounts() {
+        let params = vec![
+            Param::new("balance", ParamType::VarUint(16)),
+            Param::new("delta", ParamType::VarInt(16)),
+            Param::new("other", ParamType::VarUint(16)),
+        ];
+        let format = AmountFormat {
+            decimals: 9,
+            names: vec!["balance".to_owned(), "delta".to_owned()],
+        };
+        let tokenize_options = TokenizeOptions {
+            amount: Some(format.clone()),
+            ..Default::default()
+        };
+        let input = serde_json::json!({
+            "balance": "12.5",
+            "delta": "-0.000000001",
+            "other": "12",
+        });
+        let tokens = vec![
+            Token::new("balance", TokenValue::VarUint(16, 12_500_000_000u64.into())),
+            Token::new("delta", TokenValue::VarInt(16, (-1).into())),
+            Token::new("other", TokenValue::VarUint(16, 12u32.into())),
+        ];
+        assert_eq!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &tokenize_options)
+                .unwrap(),
+            tokens
+        );
+
+        let detokenize_options = DetokenizeOptions {
+            amount: Some(format),
+            ..Default::default()
+        };
+        assert_eq!(
+            Detokenizer::detokenize_to_json_value_with_options(&tokens, &detokenize_options)
+                .unwrap(),
+            input
+        );
+
+        let input = serde_json::json!({ "balance": "1.0000000001", "delta": "0", "other": "0" });
+        assert!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &tokenize_options)
+                .is_err()
+        );
+        let input = serde_json::json!({ "balance": "-1", "delta": "0", "other": "0" });
+        assert!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &tokenize_options)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_tokenize_hashmap() {
         let input = r#"{
@@ -725,7 +821,7 @@ mod tokenize_tests {
     fn test_tokenize_bytes() {
         let input = r#"{
             "a": "ABCDEF",
-            "b": "ABCDEF0102",
+            "b": "ABCDEF",
             "c": "55555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555555"
         }"#;
 
@@ -746,6 +842,18 @@ mod tokenize_tests {
             expected_tokens
         );
 
+        // longer fixedbytes are truncated only on demand
+        let mut input: serde_json::Value = serde_json::from_str(input).unwrap();
+        input["b"] = "ABCDEF0102".into();
+        assert!(Tokenizer::tokenize_all_params(&params, &input).is_err());
+        let options = TokenizeOptions { allow_truncate: true, ..Default::default() };
+        assert_eq!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &options).unwrap(),
+            expected_tokens
+        );
+        input["b"] = "ABCD".into();
+        assert!(Tokenizer::tokenize_all_params_with_options(&params, &input, &options).is_err());
+
         // check that detokenizer gives the same result
         let input = Detokenizer::detokenize(&expected_tokens).unwrap();
         println!("{}", input);
@@ -977,6 +1085,314 @@ mod tokenize_tests {
         );
     }
 
+    #[test]
+    fn test_pubkey_representation() {
+        let params = vec![
+            Param::new("a", ParamType::PublicKey),
+            Param::new("b", ParamType::PublicKey),
+        ];
+        let tokens = vec![
+            Token::new(
+                "a",
+                TokenValue::PublicKey(Some([0xcc; ED25519_PUBLIC_KEY_LENGTH])),
+            ),
+            Token::new("b", TokenValue::PublicKey(None)),
+        ];
+
+        let options = DetokenizeOptions {
+            pubkey_hex_prefix: true,
+            pubkey_none_as_null: true,
+            ..Default::default()
+        };
+        let output = Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap();
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "a": format!("0x{}", "cc".repeat(ED25519_PUBLIC_KEY_LENGTH)),
+                "b": null,
+            })
+        );
+
+        let input = serde_json::json!({
+            "a": format!("0x{}", "cc".repeat(ED25519_PUBLIC_KEY_LENGTH)),
+            "b": "",
+        });
+        assert_eq!(Tokenizer::tokenize_all_params(&params, &input).unwrap(), tokens);
+    }
+
+    #[test]
+    fn test_flatten_detokenize() {
+        let limit = vec![
+            Token::new("value", TokenValue::Uint(Uint::new(12, 64))),
+            Token::new(
+                "period",
+                TokenValue::Tuple(vec![Token::new("days", TokenValue::Uint(Uint::new(30, 8)))]),
+            ),
+        ];
+        let mut map = BTreeMap::new();
+        map.insert("1".to_owned(), TokenValue::Bool(true));
+        map.insert("2".to_owned(), TokenValue::Bool(false));
+        let tokens = vec![
+            Token::new("limit", TokenValue::Ref(Box::new(TokenValue::Tuple(limit.clone())))),
+            Token::new("flags", TokenValue::Map(ParamType::Uint(8), ParamType::Bool, map)),
+            Token::new("list", TokenValue::Array(
+                ParamType::Tuple(vec![Param::new("value", ParamType::Uint(64))]),
+                vec![TokenValue::Tuple(vec![limit[0].clone()])],
+            )),
+        ];
+
+        let options = DetokenizeOptions { flatten: true, ..Default::default() };
+        let output = Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap();
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "limit.value": "12",
+                "limit.period.days": "30",
+                "flags": [
+                    { "key": "1", "value": true },
+                    { "key": "2", "value": false },
+                ],
+                "list": [{ "value": "12" }],
+            })
+        );
+
+        let output = Detokenizer::detokenize_to_json_value(&tokens).unwrap();
+        assert_eq!(output["limit"]["period"]["days"], "30");
+        assert_eq!(output["flags"]["1"], true);
+    }
+
+    #[test]
+    fn test_int_format() {
+        let params = vec![
+            Param::new("a", ParamType::Uint(256)),
+            Param::new("b", ParamType::Uint(16)),
+            Param::new("c", ParamType::Int(32)),
+            Param::new("d", ParamType::VarUint(16)),
+            Param::new("e", ParamType::VarInt(16)),
+        ];
+        let tokens = vec![
+            Token::new("a", TokenValue::Uint(Uint::new(255, 256))),
+            Token::new("b", TokenValue::Uint(Uint::new(255, 16))),
+            Token::new("c", TokenValue::Int(Int::new(-255, 32))),
+            Token::new("d", TokenValue::VarUint(16, 255u32.into())),
+            Token::new("e", TokenValue::VarInt(16, (-255i32).into())),
+        ];
+
+        let detokenize = |int_format| {
+            let options = DetokenizeOptions { int_format, ..Default::default() };
+            Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap()
+        };
+
+        assert_eq!(
+            detokenize(IntFormat::Auto),
+            serde_json::json!({
+                "a": format!("0x{:0>64}", "ff"),
+                "b": "255",
+                "c": "-255",
+                "d": "255",
+                "e": "-255",
+            })
+        );
+        assert_eq!(
+            detokenize(IntFormat::Dec),
+            serde_json::json!({ "a": "255", "b": "255", "c": "-255", "d": "255", "e": "-255" })
+        );
+        let hex = detokenize(IntFormat::Hex);
+        assert_eq!(
+            hex,
+            serde_json::json!({
+                "a": format!("0x{:0>64}", "ff"),
+                "b": "0x00ff",
+                "c": "-0xff",
+                "d": "0xff",
+                "e": "-0xff",
+            })
+        );
+
+        // all formats are accepted by tokenizer
+        for output in [hex, detokenize(IntFormat::Dec)] {
+            assert_eq!(Tokenizer::tokenize_all_params(&params, &output).unwrap(), tokens);
+        }
+    }
+
+    #[test]
+    fn test_rfc3339_time() {
+        let params = vec![
+            Param::new("time", ParamType::Time),
+            Param::new("expire", ParamType::Expire),
+        ];
+        let tokens = vec![
+            Token::new("time", TokenValue::Time(1_700_000_000_123)),
+            Token::new("expire", TokenValue::Expire(1_700_000_060)),
+        ];
+
+        let options = DetokenizeOptions { rfc3339_time: true, ..Default::default() };
+        let output = Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap();
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "time": "2023-11-14T22:13:20.123Z",
+                "expire": "2023-11-14T22:14:20Z",
+            })
+        );
+        assert_eq!(Tokenizer::tokenize_all_params(&params, &output).unwrap(), tokens);
+
+        let input = serde_json::json!({
+            "time": "2023-11-15T00:13:20.123+02:00",
+            "expire": 1_700_000_060,
+        });
+        assert_eq!(Tokenizer::tokenize_all_params(&params, &input).unwrap(), tokens);
+
+        let input = serde_json::json!({ "time": "1969-12-31T23:59:59Z", "expire": 0 });
+        assert!(Tokenizer::tokenize_all_params(&params, &input).is_err());
+    }
+
+    #[test]
+    fn test_address_format() {
+        let owner = MsgAddress::with_standart(None, 0, AccountId::from([0x11; 32])).unwrap();
+        let other = MsgAddress::with_standart(None, -1, AccountId::from([0x22; 32])).unwrap();
+        let tokens = vec![
+            Token::new("owner", TokenValue::Address(owner.clone())),
+            Token::new(
+                "others",
+                TokenValue::Array(ParamType::Address, vec![TokenValue::Address(other.clone())]),
+            ),
+        ];
+
+        let labels = AddressFormat::new(move |address: &MsgAddress| {
+            if address == &owner {
+                "owner.ever".to_owned()
+            } else {
+                format!("raw:{}", address)
+            }
+        });
+        let options = DetokenizeOptions {
+            address_format: Some(labels.clone()),
+            ..Default::default()
+        };
+        assert_eq!(options.clone(), options);
+        let output = Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap();
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "owner": "owner.ever",
+                "others": [format!("raw:{}", other)],
+            })
+        );
+
+        let output = Detokenizer::detokenize_to_json_value(&tokens).unwrap();
+        assert_eq!(output["others"][0], other.to_string());
+    }
+
+    #[test]
+    fn test_redaction() {
+        let tokens = vec![
+            Token::new("payload", TokenValue::Bytes(vec![1, 2, 3])),
+            Token::new("key", TokenValue::PublicKey(Some([0xcc; ED25519_PUBLIC_KEY_LENGTH]))),
+            Token::new(
+                "inner",
+                TokenValue::Tuple(vec![
+                    Token::new("secret", TokenValue::Uint(Uint::new(7, 8))),
+                    Token::new("public", TokenValue::Uint(Uint::new(8, 8))),
+                ]),
+            ),
+            Token::new("value", TokenValue::Uint(Uint::new(9, 8))),
+        ];
+        let mut redaction = Redaction {
+            names: vec!["secret".to_owned()],
+            types: vec![ParamType::Bytes, ParamType::PublicKey],
+            mode: RedactionMode::Redact,
+        };
+
+        let options = DetokenizeOptions {
+            redaction: Some(redaction.clone()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap(),
+            serde_json::json!({
+                "payload": "<redacted>",
+                "key": "<redacted>",
+                "inner": {"secret": "<redacted>", "public": "8"},
+                "value": "9",
+            })
+        );
+
+        redaction.names = vec!["inner".to_owned()];
+        redaction.types = vec![];
+        redaction.mode = RedactionMode::Hash;
+        let options = DetokenizeOptions {
+            redaction: Some(redaction),
+            flatten: true,
+            ..Default::default()
+        };
+        let output = Detokenizer::detokenize_to_json_value_with_options(&tokens, &options).unwrap();
+        let hash = hex::encode(ever_block::sha256_digest(br#"{"secret":"7","public":"8"}"#));
+        assert_eq!(output["inner"], format!("sha256:{}", hash));
+        assert_eq!(output["payload"], "010203");
+        assert!(output.get("inner.secret").is_none());
+    }
+
+    #[test]
+    fn test_int_bounds() {
+        let bounds = IntBounds::int(8);
+        assert_eq!(bounds.min, BigInt::from(-128));
+        assert_eq!(bounds.max, BigInt::from(127));
+        assert!(bounds.contains(&BigInt::from(-128)));
+        assert!(!bounds.contains(&BigInt::from(128)));
+        assert_eq!(IntBounds::uint(8).max, BigInt::from(255));
+        assert_eq!(IntBounds::varuint(16), IntBounds::uint(120));
+        assert_eq!(IntBounds::for_type(&ParamType::Token), Some(IntBounds::uint(120)));
+        assert_eq!(IntBounds::for_type(&ParamType::Bool), None);
+        // zero-sized integers hold only zero
+        assert!(IntBounds::int(0).contains(&BigInt::from(0)));
+        assert!(!IntBounds::int(0).contains(&BigInt::from(-1)));
+
+        let params = vec![
+            Param::new("a", ParamType::Int(8)),
+            Param::new("b", ParamType::Uint(8)),
+            Param::new("c", ParamType::VarInt(1)),
+        ];
+        let input = serde_json::json!({ "a": "-0x80", "b": "0xff", "c": 0 });
+        let expected = vec![
+            Token::new("a", TokenValue::Int(Int::new(-128, 8))),
+            Token::new("b", TokenValue::Uint(Uint::new(255, 8))),
+            Token::new("c", TokenValue::VarInt(1, BigInt::from(0))),
+        ];
+        let strict = TokenizeOptions { strict_int: true, ..Default::default() };
+        assert_eq!(
+            Tokenizer::tokenize_all_params_with_options(&params, &input, &strict).unwrap(),
+            expected
+        );
+
+        for (a, b) in [("-0", "1"), ("-0x0", "1"), ("1", "0x0ff"), ("007", "1"), ("1", "01")] {
+            let input = serde_json::json!({ "a": a, "b": b, "c": 0 });
+            assert!(Tokenizer::tokenize_all_params(&params, &input).is_ok());
+            assert!(Tokenizer::tokenize_all_params_with_options(&params, &input, &strict).is_err());
+        }
+    }
+
+    #[test]
+    fn test_tokenize_slice() {
+        let params = vec![Param::new("a", ParamType::Slice)];
+        let tokens = vec![Token::new(
+            "a",
+            TokenValue::Slice(SliceData::from_raw(vec![0xAB, 0xC0], 12)),
+        )];
+
+        let output = Detokenizer::detokenize_to_json_value(&tokens).unwrap();
+        assert_eq!(output, serde_json::json!({ "a": { "bits": 12, "base64": "q8A=" } }));
+        assert_eq!(Tokenizer::tokenize_all_params(&params, &output).unwrap(), tokens);
+
+        let input = serde_json::json!({ "a": { "bits": 17, "base64": "q8A=" } });
+        assert!(Tokenizer::tokenize_all_params(&params, &input).is_err());
+        let data = ever_block::base64_encode(&[0u8; 127]);
+        let input = serde_json::json!({ "a": { "bits": 1014, "base64": data } });
+        assert!(Tokenizer::tokenize_all_params(&params, &input).is_err());
+        let input = serde_json::json!({ "a": "q8A=" });
+        assert!(Tokenizer::tokenize_all_params(&params, &input).is_err());
+    }
+
     #[test]
     fn test_tokenize_optional() {
         let input = r#"{
@@ -1076,6 +1492,100 @@ mod tokenize_tests {
         )
         .is_err(),);
     }
+
+    #[test]
+    fn test_tokenize_error_path() {
+        let item = ParamType::Tuple(vec![
+            Param::new("a", ParamType::Uint(8)),
+            Param::new(
+                "b",
+                ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Bool)),
+            ),
+        ]);
+        let params = vec![
+            Param::new("t1", ParamType::Bool),
+            Param::new("t2", ParamType::Array(Box::new(item))),
+        ];
+        let error_path = |input: serde_json::Value| {
+            let err = Tokenizer::tokenize_all_params(&params, &input).unwrap_err();
+            match err.downcast::<AbiError>().unwrap() {
+                AbiError::WrongDataFormat { path, .. }
+                | AbiError::InvalidParameterValue { path, .. }
+                | AbiError::InvalidParameterLength { path, .. } => path,
+                err => panic!("unexpected error {}", err),
+            }
+        };
+
+        let input = serde_json::json!({ "t1": 1, "t2": [] });
+        assert_eq!(error_path(input), "/t1");
+        let input = serde_json::json!({ "t1": true, "t2": [{ "a": 1, "b": {} }, { "a": 1 }] });
+        assert_eq!(error_path(input), "/t2/1/b");
+        let input = serde_json::json!({ "t1": true, "t2": [{ "a": 1, "b": { "5": 5 } }] });
+        assert_eq!(error_path(input), "/t2/0/b/5");
+
+        let err = Tokenizer::tokenize_parameter(&ParamType::Bool, &1.into(), "flag").unwrap_err();
+        assert!(!err.to_string().contains(" at `"));
+    }
+
+    #[test]
+    fn test_tokenize_collect_errors() {
+        let item = ParamType::Tuple(vec![
+            Param::new("amount", ParamType::Uint(8)),
+            Param::new("memo", ParamType::String),
+        ]);
+        let params = vec![
+            Param::new("items", ParamType::Array(Box::new(item))),
+            Param::new("pair", ParamType::FixedArray(Box::new(ParamType::Bool), 2)),
+            Param::new(
+                "a/b",
+                ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Int(8))),
+            ),
+            Param::new("dest", ParamType::Optional(Box::new(ParamType::Address))),
+            Param::new("flag", ParamType::Bool),
+        ];
+
+        let input = serde_json::json!({
+            "items": [
+                { "amount": 1, "memo": "ok" },
+                { "amount": 256, "memo": 5 },
+            ],
+            "pair": [true, "no", false],
+            "a/b": { "1": 1, "2": 128 },
+            "dest": "not an address",
+        });
+        let errors = Tokenizer::tokenize_all_params_collect(&params, &input).unwrap_err();
+        let pointers: Vec<_> = errors.iter().map(|error| error.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            vec![
+                "/items/1/amount",
+                "/items/1/memo",
+                "/pair",
+                "/pair/1",
+                "/a~1b/2",
+                "/dest",
+                "/flag",
+            ]
+        );
+        assert!(errors[0].to_string().starts_with("/items/1/amount: "));
+        assert!(errors[1].error.to_string().contains("at `/items/1/memo`"));
+
+        let input = serde_json::json!({
+            "items": [{ "amount": 1, "memo": "ok" }],
+            "pair": [true, false],
+            "a/b": { "1": -1 },
+            "flag": true,
+        });
+        assert_eq!(
+            Tokenizer::tokenize_all_params_collect(&params, &input).unwrap(),
+            Tokenizer::tokenize_all_params(&params, &input).unwrap()
+        );
+
+        let input = serde_json::json!([]);
+        let errors = Tokenizer::tokenize_all_params_collect(&params, &input).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "");
+    }
 }
 
 mod types_check_tests {
@@ -1404,3 +1914,200 @@ mod default_values_tests {
         }
     }
 }
+
+mod validate_tests {
+    use crate::{Int, Param, ParamType, Token, TokenValue, Uint};
+    use crate::token::{validate_tokens, ValidationIssue, ValidationIssueKind::{self, *}};
+    use ever_block::MsgAddress;
+    use num_bigint::BigInt;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    fn issue(path: &str, kind: ValidationIssueKind) -> ValidationIssue {
+        ValidationIssue {
+            path: path.to_owned(),
+            kind,
+        }
+    }
+
+    fn map_type(key: ParamType, value: ParamType) -> ParamType {
+        ParamType::Map(Box::new(key), Box::new(value))
+    }
+
+    #[test]
+    fn test_validate_tokens() {
+        let item = ParamType::Tuple(vec![
+            Param::new("a", ParamType::Int(8)),
+            Param::new("b", ParamType::VarUint(2)),
+        ]);
+        let params = vec![
+            Param::new("items", ParamType::FixedArray(Box::new(item.clone()), 2)),
+            Param::new("balances", map_type(ParamType::Address, ParamType::Uint(8))),
+            Param::new("ids", map_type(ParamType::Uint(8), ParamType::Bool)),
+            Param::new("flag", ParamType::Bool),
+            Param::new("last", ParamType::Uint(32)),
+        ];
+
+        let tuple = |a: i128, b: u32| {
+            TokenValue::Tuple(vec![
+                Token::new("a", TokenValue::Int(Int::new(a, 8))),
+                Token::new("b", TokenValue::VarUint(2, b.into())),
+            ])
+        };
+        let std_address = format!("0:{}", "11".repeat(32));
+        let var_address = format!("0:{}", "22".repeat(31));
+        let var_display = MsgAddress::from_str(&var_address).unwrap().to_string();
+        let mut balances = BTreeMap::new();
+        balances.insert(std_address.clone(), TokenValue::Uint(Uint::new(1, 8)));
+        balances.insert(var_address.clone(), TokenValue::Uint(Uint::new(256, 8)));
+        let mut ids = BTreeMap::new();
+        ids.insert("255".to_owned(), TokenValue::Bool(true));
+        ids.insert("256".to_owned(), TokenValue::Bool(false));
+        let valid = vec![
+            Token::new(
+                "items",
+                TokenValue::FixedArray(item.clone(), vec![tuple(-128, 255), tuple(127, 0)]),
+            ),
+            Token::new(
+                "balances",
+                TokenValue::Map(ParamType::Address, ParamType::Uint(8), BTreeMap::new()),
+            ),
+            Token::new(
+                "ids",
+                TokenValue::Map(ParamType::Uint(8), ParamType::Bool, BTreeMap::new()),
+            ),
+            Token::new("flag", TokenValue::Bool(true)),
+            Token::new("last", TokenValue::Uint(Uint::new(0, 32))),
+        ];
+        assert_eq!(validate_tokens(&params, &valid), vec![]);
+
+        let tokens = vec![
+            Token::new("items", TokenValue::FixedArray(item, vec![tuple(-129, 256)])),
+            Token::new(
+                "balances",
+                TokenValue::Map(ParamType::Address, ParamType::Uint(8), balances),
+            ),
+            Token::new("ids", TokenValue::Map(ParamType::Uint(8), ParamType::Bool, ids)),
+            Token::new("flags", TokenValue::Uint(Uint::new(1, 1))),
+        ];
+        let balance_path = format!("balances.{}", var_address);
+        assert_eq!(
+            validate_tokens(&params, &tokens),
+            vec![
+                issue("items", WrongLength { expected: 2, provided: 1 }),
+                issue("items.0.a", OutOfRange { value: "-129".to_owned() }),
+                issue("items.0.b", OutOfRange { value: "256".to_owned() }),
+                issue(&balance_path, AddressKind { address: var_display }),
+                issue(&balance_path, OutOfRange { value: "256".to_owned() }),
+                issue("ids.256", InvalidMapKey { key: "256".to_owned() }),
+                issue("flags", NameMismatch { expected: "flag".to_owned() }),
+                issue("flag", TypeMismatch { expected: ParamType::Bool }),
+                issue("last", Missing),
+            ]
+        );
+        assert_eq!(
+            issue("last", ValidationIssueKind::Missing).to_string(),
+            "`last`: value is missing"
+        );
+
+        let extra = Token::new("extra", TokenValue::VarInt(16, BigInt::from(1)));
+        let issues = validate_tokens(&params[3..], &[valid[3].clone(), valid[4].clone(), extra]);
+        assert_eq!(issues, vec![issue("extra", Unexpected)]);
+    }
+}
+
+mod tokens_tests {
+    use crate::{AbiError, Int, ParamType, Token, TokenValue, Uint};
+    use crate::token::Tokens;
+    use ever_block::MsgAddress;
+    use num_bigint::{BigInt, BigUint};
+
+    fn tokens() -> Tokens {
+        Tokens::from(vec![
+            Token::new("value", TokenValue::Uint(Uint::new(300, 32))),
+            Token::new("delta", TokenValue::Int(Int::new(-5, 8))),
+            Token::new("flag", TokenValue::Bool(true)),
+            Token::new("dest", TokenValue::Address(MsgAddress::AddrNone)),
+            Token::new("inner", TokenValue::Tuple(vec![
+                Token::new("text", TokenValue::String("hello".to_owned())),
+            ])),
+        ])
+    }
+
+    #[test]
+    fn test_lookup() {
+        let tokens = tokens();
+        assert_eq!(tokens.get("flag"), Some(&TokenValue::Bool(true)));
+        assert_eq!(tokens.get("none"), None);
+        assert_eq!(tokens["delta"], TokenValue::Int(Int::new(-5, 8)));
+        assert_eq!(tokens.len(), 5);
+
+        assert_eq!(tokens.get_as::<u32>("value").unwrap(), 300);
+        assert_eq!(tokens.get_as::<BigUint>("value").unwrap(), BigUint::from(300u32));
+        assert_eq!(tokens.get_as::<i64>("value").unwrap(), 300);
+        assert_eq!(tokens.get_as::<i8>("delta").unwrap(), -5);
+        assert_eq!(tokens.get_as::<BigInt>("delta").unwrap(), BigInt::from(-5));
+        assert!(tokens.get_as::<bool>("flag").unwrap());
+        assert_eq!(tokens.get_as::<MsgAddress>("dest").unwrap(), MsgAddress::AddrNone);
+        let inner = tokens.get_as::<Tokens>("inner").unwrap();
+        assert_eq!(inner.get_as::<String>("text").unwrap(), "hello");
+
+        assert!(matches!(
+            tokens.get_as::<u8>("value").unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::UnexpectedValueType { expected: "u8", .. }
+        ));
+        assert!(matches!(
+            tokens.get_as::<u32>("delta").unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::UnexpectedValueType { expected: "u32", .. }
+        ));
+        assert!(matches!(
+            tokens.get_as::<String>("flag").unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::UnexpectedValueType { expected: "string", .. }
+        ));
+        assert!(matches!(
+            tokens.get_as::<bool>("none").unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::MissingToken { name } if name == "none"
+        ));
+
+        let map = tokens.into_map();
+        assert_eq!(map.len(), 5);
+        assert_eq!(map["flag"], TokenValue::Bool(true));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let uint = TokenValue::Uint(Uint::new(7, 8));
+        assert_eq!(uint.as_uint(), Some(&Uint::new(7, 8)));
+        assert_eq!(uint.as_int(), None);
+        assert_eq!(uint.expect_uint().unwrap().number, BigUint::from(7u8));
+        assert!(matches!(
+            uint.expect_address().unwrap_err().downcast::<AbiError>().unwrap(),
+            AbiError::UnexpectedValueType { expected: "address", value } if value == "7"
+        ));
+
+        let bytes = TokenValue::FixedBytes(vec![1, 2]);
+        assert_eq!(bytes.as_bytes(), Some([1u8, 2].as_slice()));
+        assert_eq!(TokenValue::String("s".to_owned()).expect_str().unwrap(), "s");
+
+        let array = TokenValue::Array(ParamType::Uint(8), vec![uint.clone()]);
+        assert_eq!(array.expect_array().unwrap(), [uint.clone()].as_slice());
+        assert!(array.as_map().is_none());
+
+        let none = TokenValue::Optional(ParamType::Bool, None);
+        assert_eq!(none.as_optional(), Some(None));
+        let some = TokenValue::Optional(ParamType::Uint(8), Some(Box::new(uint.clone())));
+        assert_eq!(some.expect_optional().unwrap(), Some(&uint));
+        assert!(uint.expect_optional().is_err());
+        assert_eq!(uint.clone().into_ref().as_ref_value(), Some(&uint));
+
+        let tuple = tokens().into_inner();
+        let tuple = TokenValue::Tuple(tuple);
+        assert_eq!(tuple.expect_tuple().unwrap()[2].value.expect_bool().unwrap(), &true);
+    }
+
+    #[test]
+    #[should_panic(expected = "no token with name `none`")]
+    fn test_index_missing() {
+        let _ = &tokens()["none"];
+    }
+}
@@ -14,13 +14,14 @@
 //! ABI param and parsing for it.
 use crate::{
     error::AbiError,
-    int::{Int, Uint},
+    int::{Int, IntBounds, Uint},
     param::Param,
     param_type::ParamType,
-    token::{Token, TokenValue},
+    token::{AmountFormat, Token, TokenValue, MAX_SLICE_BITS},
 };
 
-use num_bigint::{BigInt, BigUint, Sign};
+use chrono::DateTime;
+use num_bigint::{BigInt, BigUint};
 use num_traits::cast::ToPrimitive;
 use serde_json::Value;
 use std::{
@@ -29,51 +30,119 @@ use std::{
     str::FromStr,
 };
 use ever_block::{
-    base64_decode, error, fail, read_single_root_boc, Cell, Grams, MsgAddress, Result,
+    base64_decode, error, fail, read_single_root_boc, Cell, Grams, MsgAddress, Result, SliceData,
     ED25519_PUBLIC_KEY_LENGTH,
 };
 
+/// Options controlling parsing of JSON values
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenizeOptions {
+    /// Reject empty strings as `cell` values instead of substituting empty cell
+    pub strict_cell: bool,
+    /// Parse empty string as absent value for `optional(cell)`
+    pub empty_cell_as_none: bool,
+    /// Parse `varuint16`/`varint16` decimal values as coin amounts
+    pub amount: Option<AmountFormat>,
+    /// Truncate `fixedbytes<N>` values longer than N bytes instead of rejecting them
+    pub allow_truncate: bool,
+    /// Reject ambiguous integer strings: negative zero, decimal numbers with leading zeros and
+    /// hex numbers with more digits than the type size needs
+    pub strict_int: bool,
+}
+
+/// Error of parsing a single value in collect-all-errors mode
+#[derive(Debug)]
+pub struct FieldError {
+    /// JSON pointer of the value in input JSON, e.g. `/items/0/amount`
+    pub pointer: String,
+    pub error: ever_block::Error,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.error)
+    }
+}
+
 /// This struct should be used to parse string values as tokens.
 pub struct Tokenizer;
 
 impl Tokenizer {
     /// Tries to parse a JSON value as a token of given type.
     pub fn tokenize_parameter(param: &ParamType, value: &Value, name: &str) -> Result<TokenValue> {
+        Self::tokenize_parameter_with_options(param, value, name, &TokenizeOptions::default())
+    }
+
+    /// Tries to parse a JSON value as a token of given type using provided options.
+    pub fn tokenize_parameter_with_options(
+        param: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
         match &param {
-            ParamType::Uint(size) => Self::tokenize_uint(*size, value, name),
-            ParamType::Int(size) => Self::tokenize_int(*size, value, name),
-            ParamType::VarUint(size) => Self::tokenize_varuint(*size, value, name),
-            ParamType::VarInt(size) => Self::tokenize_varint(*size, value, name),
+            ParamType::Uint(size) => Self::tokenize_uint(*size, value, name, options),
+            ParamType::Int(size) => Self::tokenize_int(*size, value, name, options),
+            ParamType::VarUint(size) => Self::tokenize_varuint(*size, value, name, options),
+            ParamType::VarInt(size) => Self::tokenize_varint(*size, value, name, options),
             ParamType::Bool => Self::tokenize_bool(value, name),
-            ParamType::Tuple(tuple_params) => Self::tokenize_tuple(tuple_params, value, name),
-            ParamType::Array(param_type) => Self::tokenize_array(&param_type, value, name),
+            ParamType::Tuple(tuple_params) => {
+                Self::tokenize_tuple(tuple_params, value, name, options)
+            }
+            ParamType::Array(param_type) => Self::tokenize_array(&param_type, value, name, options),
             ParamType::FixedArray(param_type, size) => {
-                Self::tokenize_fixed_array(&param_type, *size, value, name)
+                Self::tokenize_fixed_array(&param_type, *size, value, name, options)
             }
-            ParamType::Cell => Self::tokenize_cell(value, name),
+            ParamType::Cell => Self::tokenize_cell(value, name, options),
+            ParamType::Slice => Self::tokenize_slice(value, name),
             ParamType::Map(key_type, value_type) => {
-                Self::tokenize_hashmap(key_type, value_type, value, name)
+                Self::tokenize_hashmap(key_type, value_type, value, name, options)
             }
             ParamType::Address => Self::tokenize_address(value, name),
-            ParamType::Bytes => Self::tokenize_bytes(value, None, name),
-            ParamType::FixedBytes(size) => Self::tokenize_bytes(value, Some(*size), name),
+            ParamType::Bytes => Self::tokenize_bytes(value, None, name, options),
+            ParamType::FixedBytes(size) => Self::tokenize_bytes(value, Some(*size), name, options),
             ParamType::String => Self::tokenize_string(value, name),
             ParamType::Token => Self::tokenize_gram(value, name),
             ParamType::Time => Self::tokenize_time(value, name),
             ParamType::Expire => Self::tokenize_expire(value, name),
             ParamType::PublicKey => Self::tokenize_public_key(value, name),
-            ParamType::Optional(param_type) => Self::tokenize_optional(param_type, value, name),
-            ParamType::Ref(param_type) => Self::tokenize_ref(param_type, value, name),
+            ParamType::Optional(param_type) => {
+                Self::tokenize_optional(param_type, value, name, options)
+            }
+            ParamType::Ref(param_type) => Self::tokenize_ref(param_type, value, name, options),
         }
     }
 
+    /// Tries to parse a value nested into JSON object or array. `key` is added to the path of
+    /// value format errors.
+    fn tokenize_nested(
+        param: &ParamType,
+        value: &Value,
+        name: &str,
+        key: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        Self::tokenize_parameter_with_options(param, value, name, options)
+            .map_err(|err| AbiError::prepend_path(err, &Self::pointer_push("", key)))
+    }
+
     /// Tries to parse parameters from JSON values to tokens.
     pub fn tokenize_all_params(params: &[Param], values: &Value) -> Result<Vec<Token>> {
+        Self::tokenize_all_params_with_options(params, values, &TokenizeOptions::default())
+    }
+
+    /// Tries to parse parameters from JSON values to tokens using provided options.
+    pub fn tokenize_all_params_with_options(
+        params: &[Param],
+        values: &Value,
+        options: &TokenizeOptions,
+    ) -> Result<Vec<Token>> {
         if let Value::Object(map) = values {
             let mut tokens = Vec::new();
             for param in params {
                 let value = map.get(&param.name).unwrap_or(&Value::Null);
-                let token_value = Self::tokenize_parameter(&param.kind, value, &param.name)?;
+                let token_value =
+                    Self::tokenize_nested(&param.kind, value, &param.name, &param.name, options)?;
                 tokens.push(Token {
                     name: param.name.clone(),
                     value: token_value,
@@ -88,17 +157,194 @@ impl Tokenizer {
         }
     }
 
+    /// Parses parameters from JSON values to tokens without stopping at the first bad value.
+    /// Returns errors of all values which can not be parsed.
+    pub fn tokenize_all_params_collect(
+        params: &[Param],
+        values: &Value,
+    ) -> std::result::Result<Vec<Token>, Vec<FieldError>> {
+        Self::tokenize_all_params_collect_with_options(params, values, &TokenizeOptions::default())
+    }
+
+    /// Parses parameters from JSON values to tokens using provided options without stopping at
+    /// the first bad value. Returns errors of all values which can not be parsed.
+    pub fn tokenize_all_params_collect_with_options(
+        params: &[Param],
+        values: &Value,
+        options: &TokenizeOptions,
+    ) -> std::result::Result<Vec<Token>, Vec<FieldError>> {
+        if !values.is_object() {
+            return Err(vec![FieldError {
+                pointer: String::new(),
+                error: error!(AbiError::InvalidInputData {
+                    msg: "Contract function parameters should be passed as a JSON object"
+                        .to_string()
+                }),
+            }]);
+        }
+        let mut errors = vec![];
+        let tokens = Self::collect_params(params, values, "", options, &mut errors);
+        match tokens {
+            Some(tokens) if errors.is_empty() => Ok(tokens),
+            _ => Err(errors),
+        }
+    }
+
+    fn collect_params(
+        params: &[Param],
+        values: &Value,
+        pointer: &str,
+        options: &TokenizeOptions,
+        errors: &mut Vec<FieldError>,
+    ) -> Option<Vec<Token>> {
+        let mut tokens = Some(Vec::new());
+        for param in params {
+            let value = values.get(&param.name).unwrap_or(&Value::Null);
+            let pointer = Self::pointer_push(pointer, &param.name);
+            let value =
+                Self::collect_parameter(&param.kind, value, &param.name, &pointer, options, errors);
+            match (tokens.as_mut(), value) {
+                (Some(tokens), Some(value)) => tokens.push(Token::new(&param.name, value)),
+                _ => tokens = None,
+            }
+        }
+        tokens
+    }
+
+    fn collect_items<'a>(
+        item_type: &ParamType,
+        items: impl Iterator<Item = (String, &'a Value)>,
+        name: &str,
+        pointer: &str,
+        options: &TokenizeOptions,
+        errors: &mut Vec<FieldError>,
+    ) -> Option<Vec<(String, TokenValue)>> {
+        let mut values = Some(Vec::new());
+        for (key, value) in items {
+            let pointer = Self::pointer_push(pointer, &key);
+            let value = Self::collect_parameter(item_type, value, name, &pointer, options, errors);
+            match (values.as_mut(), value) {
+                (Some(values), Some(value)) => values.push((key, value)),
+                _ => values = None,
+            }
+        }
+        values
+    }
+
+    // walks into composite values to report errors of all nested values
+    fn collect_parameter(
+        param_type: &ParamType,
+        value: &Value,
+        name: &str,
+        pointer: &str,
+        options: &TokenizeOptions,
+        errors: &mut Vec<FieldError>,
+    ) -> Option<TokenValue> {
+        match (param_type, value) {
+            (ParamType::Tuple(params), Value::Object(_)) => {
+                Self::collect_params(params, value, pointer, options, errors)
+                    .map(TokenValue::Tuple)
+            }
+            (ParamType::Array(item_type), Value::Array(array)) => {
+                Self::collect_items(item_type, Self::indexed(array), name, pointer, options, errors)
+                    .map(|items| {
+                        let items = items.into_iter().map(|(_, value)| value).collect();
+                        TokenValue::Array(item_type.as_ref().clone(), items)
+                    })
+            }
+            (ParamType::FixedArray(item_type, size), Value::Array(array)) => {
+                if array.len() != *size {
+                    errors.push(FieldError {
+                        pointer: pointer.to_owned(),
+                        error: error!(AbiError::InvalidParameterLength {
+                            val: value.clone(),
+                            name: name.to_string(),
+                            path: pointer.to_owned(),
+                            expected: format!("array of {} elements", size),
+                        }),
+                    });
+                }
+                Self::collect_items(item_type, Self::indexed(array), name, pointer, options, errors)
+                    .filter(|items| items.len() == *size)
+                    .map(|items| {
+                        let items = items.into_iter().map(|(_, value)| value).collect();
+                        TokenValue::FixedArray(item_type.as_ref().clone(), items)
+                    })
+            }
+            (ParamType::Map(key_type, value_type), Value::Object(map)) => {
+                let items = map.iter().map(|(key, value)| (key.clone(), value));
+                Self::collect_items(value_type, items, name, pointer, options, errors).map(
+                    |items| {
+                        TokenValue::Map(
+                            key_type.as_ref().clone(),
+                            value_type.as_ref().clone(),
+                            items.into_iter().collect(),
+                        )
+                    },
+                )
+            }
+            (ParamType::Optional(inner_type), value) if !value.is_null() => {
+                let empty_cell = options.empty_cell_as_none
+                    && inner_type.as_ref() == &ParamType::Cell
+                    && value.as_str() == Some("");
+                if empty_cell {
+                    return Some(TokenValue::Optional(inner_type.as_ref().clone(), None));
+                }
+                Self::collect_parameter(inner_type, value, name, pointer, options, errors).map(
+                    |value| {
+                        TokenValue::Optional(inner_type.as_ref().clone(), Some(Box::new(value)))
+                    },
+                )
+            }
+            (ParamType::Ref(inner_type), value) => {
+                Self::collect_parameter(inner_type, value, name, pointer, options, errors)
+                    .map(TokenValue::into_ref)
+            }
+            (param_type, value) => {
+                match Self::tokenize_parameter_with_options(param_type, value, name, options) {
+                    Ok(value) => Some(value),
+                    Err(error) => {
+                        errors.push(FieldError {
+                            pointer: pointer.to_owned(),
+                            error: AbiError::prepend_path(error, pointer),
+                        });
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    fn indexed(array: &[Value]) -> impl Iterator<Item = (String, &Value)> {
+        array.iter().enumerate().map(|(i, value)| (i.to_string(), value))
+    }
+
+    fn pointer_push(pointer: &str, key: &str) -> String {
+        format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+    }
+
     /// Tries to parse parameters from JSON values to tokens.
     pub fn tokenize_optional_params(
         params: &[Param],
         values: &Value,
+    ) -> Result<HashMap<String, TokenValue>> {
+        Self::tokenize_optional_params_with_options(params, values, &TokenizeOptions::default())
+    }
+
+    /// Tries to parse parameters from JSON values to tokens using provided options.
+    pub fn tokenize_optional_params_with_options(
+        params: &[Param],
+        values: &Value,
+        options: &TokenizeOptions,
     ) -> Result<HashMap<String, TokenValue>> {
         if let Value::Object(map) = values {
             let mut map = map.clone();
             let mut tokens = HashMap::new();
             for param in params {
                 if let Some(value) = map.remove(&param.name) {
-                    let token_value = Self::tokenize_parameter(&param.kind, &value, &param.name)?;
+                    let name = &param.name;
+                    let token_value =
+                        Self::tokenize_nested(&param.kind, &value, name, name, options)?;
                     tokens.insert(param.name.clone(), token_value);
                 }
             }
@@ -122,11 +368,17 @@ impl Tokenizer {
     }
 
     /// Tries to read tokens array from `Value`
-    fn read_array(item_type: &ParamType, value: &Value, name: &str) -> Result<Vec<TokenValue>> {
+    fn read_array(
+        item_type: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<Vec<TokenValue>> {
         if let Value::Array(array) = value {
             let mut tokens = Vec::new();
-            for value in array {
-                tokens.push(Self::tokenize_parameter(item_type, value, name)?);
+            for (i, value) in array.iter().enumerate() {
+                let key = i.to_string();
+                tokens.push(Self::tokenize_nested(item_type, value, name, &key, options)?);
             }
 
             Ok(tokens)
@@ -134,6 +386,7 @@ impl Tokenizer {
             fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "array".to_string()
             })
         }
@@ -145,21 +398,28 @@ impl Tokenizer {
         size: usize,
         value: &Value,
         name: &str,
+        options: &TokenizeOptions,
     ) -> Result<TokenValue> {
-        let vec = Self::read_array(item_type, value, name)?;
+        let vec = Self::read_array(item_type, value, name, options)?;
         match vec.len() == size {
             true => Ok(TokenValue::FixedArray(item_type.clone(), vec)),
             false => fail!(AbiError::InvalidParameterLength {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: format!("array of {} elements", size),
             }),
         }
     }
 
     /// Tries to parse a value as a vector of tokens.
-    fn tokenize_array(item_type: &ParamType, value: &Value, name: &str) -> Result<TokenValue> {
-        let vec = Self::read_array(item_type, value, name)?;
+    fn tokenize_array(
+        item_type: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        let vec = Self::read_array(item_type, value, name, options)?;
 
         Ok(TokenValue::Array(item_type.clone(), vec))
     }
@@ -174,12 +434,14 @@ impl Tokenizer {
                 _ => fail!(AbiError::InvalidParameterValue {
                     val: value.clone(),
                     name: name.to_string(),
+                    path: String::new(),
                     err: "string should contain `true` or `false`".to_string()
                 }),
             },
             _ => fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "bool or string `true`/`false`".to_string()
             }),
         }
@@ -202,6 +464,7 @@ impl Tokenizer {
                 None => fail!(AbiError::InvalidParameterValue {
                     val: value.clone(),
                     name: name.to_string(),
+                    path: String::new(),
                     err: "can not parse number from string".to_string()
                 }),
             }
@@ -209,6 +472,7 @@ impl Tokenizer {
             fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "number or string with encoded number".to_string()
             })
         }
@@ -229,6 +493,7 @@ impl Tokenizer {
                 None => fail!(AbiError::InvalidParameterValue {
                     val: value.clone(),
                     name: name.to_string(),
+                    path: String::new(),
                     err: "can not parse number from string".to_string()
                 }),
             }
@@ -236,11 +501,41 @@ impl Tokenizer {
             fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "number or string with encoded number".to_string()
             })
         }
     }
 
+    /// Tries to read decimal coin amount from `Value` if amount format is set for the parameter.
+    /// Hex-encoded numbers are not treated as amounts.
+    fn read_amount(
+        param_type: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<Option<BigInt>> {
+        let format = match &options.amount {
+            Some(format) if format.applies_to(param_type, name) => format,
+            _ => return Ok(None),
+        };
+        let string = match value {
+            Value::String(string) if !string.starts_with("0x") && !string.starts_with("-0x") => {
+                string.clone()
+            }
+            Value::Number(number) => number.to_string(),
+            _ => return Ok(None),
+        };
+        format.parse(&string).map(Some).ok_or_else(|| {
+            error!(AbiError::InvalidParameterValue {
+                val: value.clone(),
+                name: name.to_string(),
+                path: String::new(),
+                err: format!("can not parse amount with {} decimals", format.decimals)
+            })
+        })
+    }
+
     /// Tries to read grams from `Value`
     fn read_grams(value: &Value, name: &str) -> Result<Grams> {
         if let Some(number) = value.as_u64() {
@@ -250,6 +545,7 @@ impl Tokenizer {
                 error!(AbiError::InvalidParameterValue {
                     val: value.clone(),
                     name: name.to_string(),
+                    path: String::new(),
                     err: "can not parse number from string".to_string()
                 })
             })
@@ -257,30 +553,53 @@ impl Tokenizer {
             fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "number or string with encoded number".to_string()
             })
         }
     }
 
-    /// Checks if given number can be fit into given bits count
-    fn check_int_size(number: &BigInt, size: usize) -> bool {
-        // `BigInt::bits` returns fewest bits necessary to express the number, not including
-        // the sign and it works well for all values except `-2^n`. Such values can be encoded
-        // using `n` bits, but `bits` function returns `n` (and plus one bit for sign) so we
-        // have to explicitly check such situation by comparing bits sizes of given number
-        // and increased number
-        if number.sign() == Sign::Minus && number.bits() != (number + BigInt::from(1)).bits() {
-            (number.bits() as usize) <= size
+    /// Checks string representation of the number of `bits` size in strict mode
+    fn check_strict_int(
+        value: &Value,
+        bits: usize,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<()> {
+        let string = match value.as_str() {
+            Some(string) if options.strict_int => string,
+            _ => return Ok(()),
+        };
+        let (negative, digits) = match string.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, string),
+        };
+        let err = if let Some(hex) = digits.strip_prefix("0x") {
+            if hex.len() > (bits + 3) / 4 {
+                Some(format!("hex number has more than {} digits", (bits + 3) / 4))
+            } else if negative && hex.bytes().all(|digit| digit == b'0') {
+                Some("negative zero".to_owned())
+            } else {
+                None
+            }
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            Some("decimal number has leading zeros".to_owned())
+        } else if negative && digits == "0" {
+            Some("negative zero".to_owned())
         } else {
-            (number.bits() as usize) < size
+            None
+        };
+        match err {
+            Some(err) => fail!(AbiError::InvalidParameterValue {
+                val: value.clone(),
+                name: name.to_string(),
+                path: String::new(),
+                err,
+            }),
+            None => Ok(()),
         }
     }
 
-    /// Checks if given number can be fit into given bits count
-    fn check_uint_size(number: &BigUint, size: usize) -> bool {
-        (number.bits() as usize) <= size
-    }
-
     /// Tries to parse a value as grams.
     fn tokenize_gram(value: &Value, name: &str) -> Result<TokenValue> {
         let number = Self::read_grams(value, name)?;
@@ -288,13 +607,20 @@ impl Tokenizer {
     }
 
     /// Tries to parse a value as unsigned integer.
-    fn tokenize_uint(size: usize, value: &Value, name: &str) -> Result<TokenValue> {
+    fn tokenize_uint(
+        size: usize,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        Self::check_strict_int(value, size, name, options)?;
         let number = Self::read_uint(value, name)?;
 
-        if !Self::check_uint_size(&number, size) {
+        if !IntBounds::uint(size).contains_uint(&number) {
             fail!(AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: "provided number is out of type range".to_string()
             })
         } else {
@@ -303,13 +629,20 @@ impl Tokenizer {
     }
 
     /// Tries to parse a value as signed integer.
-    fn tokenize_int(size: usize, value: &Value, name: &str) -> Result<TokenValue> {
+    fn tokenize_int(
+        size: usize,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        Self::check_strict_int(value, size, name, options)?;
         let number = Self::read_int(value, name)?;
 
-        if !Self::check_int_size(&number, size) {
+        if !IntBounds::int(size).contains(&number) {
             fail!(AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: "provided number is out of type range".to_string()
             })
         } else {
@@ -317,13 +650,33 @@ impl Tokenizer {
         }
     }
 
-    fn tokenize_varuint(size: usize, value: &Value, name: &str) -> Result<TokenValue> {
-        let number = Self::read_uint(value, name)?;
+    fn tokenize_varuint(
+        size: usize,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        let amount = Self::read_amount(&ParamType::VarUint(size), value, name, options)?;
+        let number = match amount {
+            Some(amount) => amount.to_biguint().ok_or_else(|| {
+                error!(AbiError::InvalidParameterValue {
+                    val: value.clone(),
+                    name: name.to_string(),
+                    path: String::new(),
+                    err: "amount should not be negative".to_string()
+                })
+            })?,
+            None => {
+                Self::check_strict_int(value, (size - 1) * 8, name, options)?;
+                Self::read_uint(value, name)?
+            }
+        };
 
-        if !Self::check_uint_size(&number, (size - 1) * 8) {
+        if !IntBounds::varuint(size).contains_uint(&number) {
             fail!(AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: "provided number is out of type range".to_string()
             })
         } else {
@@ -331,13 +684,26 @@ impl Tokenizer {
         }
     }
 
-    fn tokenize_varint(size: usize, value: &Value, name: &str) -> Result<TokenValue> {
-        let number = Self::read_int(value, name)?;
+    fn tokenize_varint(
+        size: usize,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        let amount = Self::read_amount(&ParamType::VarInt(size), value, name, options)?;
+        let number = match amount {
+            Some(amount) => amount,
+            None => {
+                Self::check_strict_int(value, (size - 1) * 8, name, options)?;
+                Self::read_int(value, name)?
+            }
+        };
 
-        if !Self::check_int_size(&number, (size - 1) * 8) {
+        if !IntBounds::varint(size).contains(&number) {
             fail!(AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: "provided number is out of type range".to_string()
             })
         } else {
@@ -345,40 +711,87 @@ impl Tokenizer {
         }
     }
 
-    fn tokenize_cell(value: &Value, name: &str) -> Result<TokenValue> {
+    fn tokenize_cell(value: &Value, name: &str, options: &TokenizeOptions) -> Result<TokenValue> {
         let string = value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             expected: "base64-encoded cell BOC".to_string(),
         })?;
 
         if string.is_empty() {
+            if options.strict_cell {
+                fail!(AbiError::InvalidParameterValue {
+                    val: value.clone(),
+                    name: name.to_string(),
+                    path: String::new(),
+                    err: "empty string is not a valid cell BOC".to_string()
+                })
+            }
             return Ok(TokenValue::Cell(Cell::default()));
         }
 
         let data = base64_decode(string).map_err(|err| AbiError::InvalidParameterValue {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             err: format!("can not decode base64: {}", err),
         })?;
         let cell = read_single_root_boc(&data).map_err(|err| AbiError::InvalidParameterValue {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             err: format!("can not deserialize cell: {}", err),
         })?;
         Ok(TokenValue::Cell(cell))
     }
 
+    fn tokenize_slice(value: &Value, name: &str) -> Result<TokenValue> {
+        let wrong_format = || AbiError::WrongDataFormat {
+            val: value.clone(),
+            name: name.to_string(),
+            path: String::new(),
+            expected: "object with `bits` number and `base64` string".to_string(),
+        };
+        let bits = value["bits"].as_u64().ok_or_else(wrong_format)? as usize;
+        let string = value["base64"].as_str().ok_or_else(wrong_format)?;
+
+        if bits > MAX_SLICE_BITS {
+            fail!(AbiError::InvalidParameterLength {
+                val: value.clone(),
+                name: name.to_string(),
+                path: String::new(),
+                expected: format!("up to {} bits", MAX_SLICE_BITS),
+            })
+        }
+        let data = base64_decode(string).map_err(|err| AbiError::InvalidParameterValue {
+            val: value.clone(),
+            name: name.to_string(),
+            path: String::new(),
+            err: format!("can not decode base64: {}", err),
+        })?;
+        if data.len() != (bits + 7) / 8 {
+            fail!(AbiError::InvalidParameterLength {
+                val: value.clone(),
+                name: name.to_string(),
+                path: String::new(),
+                expected: format!("{} bytes for {} bits", (bits + 7) / 8, bits),
+            })
+        }
+        Ok(TokenValue::Slice(SliceData::from_raw(data, bits)))
+    }
+
     fn tokenize_hashmap(
         key_type: &ParamType,
         value_type: &ParamType,
         map_value: &Value,
         name: &str,
+        options: &TokenizeOptions,
     ) -> Result<TokenValue> {
         if let Value::Object(map) = map_value {
             let mut new_map = BTreeMap::<String, TokenValue>::new();
             for (key, value) in map.iter() {
-                let value = Self::tokenize_parameter(value_type, value, name)?;
+                let value = Self::tokenize_nested(value_type, value, name, key, options)?;
                 new_map.insert(key.to_string(), value);
             }
             Ok(TokenValue::Map(
@@ -390,31 +803,40 @@ impl Tokenizer {
             fail!(AbiError::WrongDataFormat {
                 val: map_value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "JSON object".to_string()
             })
         }
     }
 
-    fn tokenize_bytes(value: &Value, size: Option<usize>, name: &str) -> Result<TokenValue> {
+    fn tokenize_bytes(
+        value: &Value,
+        size: Option<usize>,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
         let string = value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             expected: "hex-encoded string".to_string(),
         })?;
         let mut data = hex::decode(string).map_err(|err| AbiError::InvalidParameterValue {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             err: format!("can not decode hex: {}", err),
         })?;
         match size {
             Some(size) => {
-                if data.len() >= size {
+                if data.len() == size || (data.len() > size && options.allow_truncate) {
                     data.truncate(size);
                     Ok(TokenValue::FixedBytes(data))
                 } else {
                     fail!(AbiError::InvalidParameterLength {
                         val: value.clone(),
                         name: name.to_string(),
+                        path: String::new(),
                         expected: format!("{} bytes", size),
                     })
                 }
@@ -429,6 +851,7 @@ impl Tokenizer {
             .ok_or_else(|| AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "string".to_string(),
             })?
             .to_owned();
@@ -436,22 +859,43 @@ impl Tokenizer {
     }
 
     /// Tries to parse a value as tuple.
-    fn tokenize_tuple(params: &Vec<Param>, value: &Value, name: &str) -> Result<TokenValue> {
+    fn tokenize_tuple(
+        params: &Vec<Param>,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
         if !value.is_object() {
             fail!(AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "JSON object".to_string()
             })
         }
 
-        let tokens = Self::tokenize_all_params(params, value)?;
+        let tokens = Self::tokenize_all_params_with_options(params, value, options)?;
 
         Ok(TokenValue::Tuple(tokens))
     }
 
+    /// Tries to read RFC3339 datetime string as milliseconds since epoch
+    fn read_rfc3339_millis(value: &Value) -> Option<i64> {
+        let datetime = DateTime::parse_from_rfc3339(value.as_str()?).ok()?;
+        Some(datetime.timestamp_millis())
+    }
+
     /// Tries to parse a value as time.
     fn tokenize_time(value: &Value, name: &str) -> Result<TokenValue> {
+        if let Some(millis) = Self::read_rfc3339_millis(value) {
+            let time = u64::try_from(millis).map_err(|_| AbiError::InvalidParameterValue {
+                val: value.clone(),
+                name: name.to_string(),
+                path: String::new(),
+                err: "time should not be before epoch".to_string(),
+            })?;
+            return Ok(TokenValue::Time(time));
+        }
         let number = Self::read_uint(value, name)?;
 
         let time = number.to_u64().ok_or_else(|| {
@@ -465,6 +909,16 @@ impl Tokenizer {
 
     /// Tries to parse a value as expire.
     fn tokenize_expire(value: &Value, name: &str) -> Result<TokenValue> {
+        if let Some(millis) = Self::read_rfc3339_millis(value) {
+            let expire =
+                u32::try_from(millis / 1000).map_err(|_| AbiError::InvalidParameterValue {
+                    val: value.clone(),
+                    name: name.to_string(),
+                    path: String::new(),
+                    err: "expire should fit into u32 seconds since epoch".to_string(),
+                })?;
+            return Ok(TokenValue::Expire(expire));
+        }
         let number = Self::read_uint(value, name)?;
 
         let expire = number.to_u32().ok_or_else(|| {
@@ -480,8 +934,10 @@ impl Tokenizer {
         let string = value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
             val: value.clone(),
             name: name.to_string(),
+            path: String::new(),
             expected: "hex-encoded string".to_string(),
         })?;
+        let string = string.strip_prefix("0x").unwrap_or(string);
 
         if string.len() == 0 {
             Ok(TokenValue::PublicKey(None))
@@ -489,6 +945,7 @@ impl Tokenizer {
             let data = hex::decode(string).map_err(|err| AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: format!("can not decode hex: {}", err),
             })?;
             let bytes = data
@@ -496,26 +953,42 @@ impl Tokenizer {
                 .map_err(|_| AbiError::InvalidParameterLength {
                     val: value.clone(),
                     name: name.to_string(),
+                    path: String::new(),
                     expected: format!("{} bytes", ED25519_PUBLIC_KEY_LENGTH),
                 })?;
             Ok(TokenValue::PublicKey(Some(bytes)))
         }
     }
 
-    fn tokenize_optional(inner_type: &ParamType, value: &Value, name: &str) -> Result<TokenValue> {
-        if value.is_null() {
+    fn tokenize_optional(
+        inner_type: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        let empty_cell = options.empty_cell_as_none
+            && inner_type == &ParamType::Cell
+            && value.as_str() == Some("");
+        if value.is_null() || empty_cell {
             Ok(TokenValue::Optional(inner_type.clone(), None))
         } else {
             Ok(TokenValue::Optional(
                 inner_type.clone(),
-                Some(Box::new(Self::tokenize_parameter(inner_type, value, name)?)),
+                Some(Box::new(Self::tokenize_parameter_with_options(
+                    inner_type, value, name, options,
+                )?)),
             ))
         }
     }
 
-    fn tokenize_ref(inner_type: &ParamType, value: &Value, name: &str) -> Result<TokenValue> {
-        Ok(TokenValue::Ref(Box::new(Self::tokenize_parameter(
-            inner_type, value, name,
+    fn tokenize_ref(
+        inner_type: &ParamType,
+        value: &Value,
+        name: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenValue> {
+        Ok(TokenValue::Ref(Box::new(Self::tokenize_parameter_with_options(
+            inner_type, value, name, options,
         )?)))
     }
 
@@ -524,11 +997,13 @@ impl Tokenizer {
             MsgAddress::from_str(&value.as_str().ok_or_else(|| AbiError::WrongDataFormat {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 expected: "address string".to_string(),
             })?)
             .map_err(|err| AbiError::InvalidParameterValue {
                 val: value.clone(),
                 name: name.to_string(),
+                path: String::new(),
                 err: format!("can not parse address: {}", err),
             })?;
         Ok(TokenValue::Address(address))
@@ -0,0 +1,400 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Typed access to decoded values.
+//!
+//! Decoding functions return parameters as `Vec<Token>` ordered as in ABI. `Tokens` wraps such
+//! vector to look values up by parameter name and convert them into Rust types. `TokenValue`
+//! accessors `as_*` return reference to the value of expected kind and `expect_*` fail with
+//! `AbiError::UnexpectedValueType` for other kinds.
+
+use crate::{
+    error::AbiError,
+    int::{Int, Uint},
+    token::{Token, TokenValue},
+};
+
+use ever_block::{fail, Cell, Grams, MsgAddress, Result, SliceData};
+use num_bigint::{BigInt, BigUint};
+use num_traits::ToPrimitive;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Deref, Index};
+
+/// Parameter values ordered as in ABI with lookup by parameter name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tokens(pub Vec<Token>);
+
+impl Tokens {
+    /// Returns value of parameter `name`
+    pub fn get(&self, name: &str) -> Option<&TokenValue> {
+        self.0
+            .iter()
+            .find(|token| token.name == name)
+            .map(|token| &token.value)
+    }
+
+    /// Returns value of parameter `name` converted into `T`. Fails with
+    /// `AbiError::MissingToken` if there is no such parameter and with
+    /// `AbiError::UnexpectedValueType` if value can not be represented as `T`.
+    pub fn get_as<T: FromTokenValue>(&self, name: &str) -> Result<T> {
+        match self.get(name) {
+            Some(value) => T::from_token_value(value),
+            None => fail!(AbiError::MissingToken { name: name.to_owned() }),
+        }
+    }
+
+    /// Returns parameter values by parameter names
+    pub fn into_map(self) -> HashMap<String, TokenValue> {
+        self.0
+            .into_iter()
+            .map(|token| (token.name, token.value))
+            .collect()
+    }
+
+    pub fn into_inner(self) -> Vec<Token> {
+        self.0
+    }
+}
+
+impl From<Vec<Token>> for Tokens {
+    fn from(tokens: Vec<Token>) -> Self {
+        Self(tokens)
+    }
+}
+
+impl From<Tokens> for Vec<Token> {
+    fn from(tokens: Tokens) -> Self {
+        tokens.0
+    }
+}
+
+impl FromIterator<Token> for Tokens {
+    fn from_iter<I: IntoIterator<Item = Token>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Tokens {
+    type Item = Token;
+    type IntoIter = std::vec::IntoIter<Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Tokens {
+    type Item = &'a Token;
+    type IntoIter = std::slice::Iter<'a, Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Deref for Tokens {
+    type Target = [Token];
+
+    fn deref(&self) -> &[Token] {
+        &self.0
+    }
+}
+
+/// Panics if there is no parameter `name`
+impl Index<&str> for Tokens {
+    type Output = TokenValue;
+
+    fn index(&self, name: &str) -> &TokenValue {
+        self.get(name)
+            .unwrap_or_else(|| panic!("no token with name `{}`", name))
+    }
+}
+
+impl TokenValue {
+    pub fn as_uint(&self) -> Option<&Uint> {
+        match self {
+            TokenValue::Uint(uint) => Some(uint),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<&Int> {
+        match self {
+            TokenValue::Int(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    pub fn as_varuint(&self) -> Option<&BigUint> {
+        match self {
+            TokenValue::VarUint(_, number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn as_varint(&self) -> Option<&BigInt> {
+        match self {
+            TokenValue::VarInt(_, number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<&bool> {
+        match self {
+            TokenValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_tuple(&self) -> Option<&[Token]> {
+        match self {
+            TokenValue::Tuple(tokens) => Some(tokens),
+            _ => None,
+        }
+    }
+
+    /// Returns items of dynamic or fixed size array
+    pub fn as_array(&self) -> Option<&[TokenValue]> {
+        match self {
+            TokenValue::Array(_, items) | TokenValue::FixedArray(_, items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_cell(&self) -> Option<&Cell> {
+        match self {
+            TokenValue::Cell(cell) => Some(cell),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&BTreeMap<String, TokenValue>> {
+        match self {
+            TokenValue::Map(_, _, map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_address(&self) -> Option<&MsgAddress> {
+        match self {
+            TokenValue::Address(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    /// Returns value of `bytes` or `fixedbytes` parameter
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TokenValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_grams(&self) -> Option<&Grams> {
+        match self {
+            TokenValue::Token(grams) => Some(grams),
+            _ => None,
+        }
+    }
+
+    pub fn as_slice(&self) -> Option<&SliceData> {
+        match self {
+            TokenValue::Slice(slice) => Some(slice),
+            _ => None,
+        }
+    }
+
+    /// Returns `Some` with inner value of `optional` parameter which is `None` if value is absent
+    pub fn as_optional(&self) -> Option<Option<&TokenValue>> {
+        match self {
+            TokenValue::Optional(_, value) => Some(value.as_deref()),
+            _ => None,
+        }
+    }
+
+    /// Returns value of `ref` parameter
+    pub fn as_ref_value(&self) -> Option<&TokenValue> {
+        match self {
+            TokenValue::Ref(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn expect_uint(&self) -> Result<&Uint> {
+        self.as_uint().ok_or_else(|| self.unexpected_type("uint"))
+    }
+
+    pub fn expect_int(&self) -> Result<&Int> {
+        self.as_int().ok_or_else(|| self.unexpected_type("int"))
+    }
+
+    pub fn expect_varuint(&self) -> Result<&BigUint> {
+        self.as_varuint().ok_or_else(|| self.unexpected_type("varuint"))
+    }
+
+    pub fn expect_varint(&self) -> Result<&BigInt> {
+        self.as_varint().ok_or_else(|| self.unexpected_type("varint"))
+    }
+
+    pub fn expect_bool(&self) -> Result<&bool> {
+        self.as_bool().ok_or_else(|| self.unexpected_type("bool"))
+    }
+
+    pub fn expect_tuple(&self) -> Result<&[Token]> {
+        self.as_tuple().ok_or_else(|| self.unexpected_type("tuple"))
+    }
+
+    pub fn expect_array(&self) -> Result<&[TokenValue]> {
+        self.as_array().ok_or_else(|| self.unexpected_type("array"))
+    }
+
+    pub fn expect_cell(&self) -> Result<&Cell> {
+        self.as_cell().ok_or_else(|| self.unexpected_type("cell"))
+    }
+
+    pub fn expect_map(&self) -> Result<&BTreeMap<String, TokenValue>> {
+        self.as_map().ok_or_else(|| self.unexpected_type("map"))
+    }
+
+    pub fn expect_address(&self) -> Result<&MsgAddress> {
+        self.as_address().ok_or_else(|| self.unexpected_type("address"))
+    }
+
+    pub fn expect_bytes(&self) -> Result<&[u8]> {
+        self.as_bytes().ok_or_else(|| self.unexpected_type("bytes"))
+    }
+
+    pub fn expect_str(&self) -> Result<&str> {
+        self.as_str().ok_or_else(|| self.unexpected_type("string"))
+    }
+
+    pub fn expect_grams(&self) -> Result<&Grams> {
+        self.as_grams().ok_or_else(|| self.unexpected_type("gram"))
+    }
+
+    pub fn expect_slice(&self) -> Result<&SliceData> {
+        self.as_slice().ok_or_else(|| self.unexpected_type("slice"))
+    }
+
+    pub fn expect_optional(&self) -> Result<Option<&TokenValue>> {
+        self.as_optional().ok_or_else(|| self.unexpected_type("optional"))
+    }
+
+    pub fn expect_ref_value(&self) -> Result<&TokenValue> {
+        self.as_ref_value().ok_or_else(|| self.unexpected_type("ref"))
+    }
+
+    fn unexpected_type(&self, expected: &'static str) -> ever_block::Error {
+        AbiError::UnexpectedValueType {
+            expected,
+            value: self.to_string(),
+        }
+        .into()
+    }
+}
+
+/// Conversion of token value into Rust type used by `Tokens::get_as`
+pub trait FromTokenValue: Sized {
+    fn from_token_value(value: &TokenValue) -> Result<Self>;
+}
+
+impl FromTokenValue for TokenValue {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl FromTokenValue for bool {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_bool().copied()
+    }
+}
+
+impl FromTokenValue for BigUint {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        match value {
+            TokenValue::Uint(uint) => Ok(uint.number.clone()),
+            TokenValue::VarUint(_, number) => Ok(number.clone()),
+            TokenValue::Token(grams) => Ok(grams.as_u128().into()),
+            _ => Err(value.unexpected_type("unsigned integer")),
+        }
+    }
+}
+
+impl FromTokenValue for BigInt {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        match value {
+            TokenValue::Int(int) => Ok(int.number.clone()),
+            TokenValue::VarInt(_, number) => Ok(number.clone()),
+            _ => BigUint::from_token_value(value)
+                .map(BigInt::from)
+                .map_err(|_| value.unexpected_type("integer")),
+        }
+    }
+}
+
+macro_rules! from_token_value_for_primitive {
+    ($($type:ty => $convert:ident),*) => {
+        $(
+            impl FromTokenValue for $type {
+                fn from_token_value(value: &TokenValue) -> Result<Self> {
+                    BigInt::from_token_value(value)?
+                        .$convert()
+                        .ok_or_else(|| value.unexpected_type(stringify!($type)))
+                }
+            }
+        )*
+    };
+}
+
+from_token_value_for_primitive!(
+    u8 => to_u8, u16 => to_u16, u32 => to_u32, u64 => to_u64, u128 => to_u128,
+    i8 => to_i8, i16 => to_i16, i32 => to_i32, i64 => to_i64, i128 => to_i128
+);
+
+impl FromTokenValue for String {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_str().map(str::to_owned)
+    }
+}
+
+impl FromTokenValue for Vec<u8> {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_bytes().map(<[u8]>::to_vec)
+    }
+}
+
+impl FromTokenValue for MsgAddress {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_address().cloned()
+    }
+}
+
+impl FromTokenValue for Cell {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_cell().cloned()
+    }
+}
+
+impl FromTokenValue for Tokens {
+    fn from_token_value(value: &TokenValue) -> Result<Self> {
+        value.expect_tuple().map(|tokens| Self(tokens.to_vec()))
+    }
+}
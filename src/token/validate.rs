@@ -0,0 +1,272 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Value-level validation of tokens.
+//!
+//! Validation checks token values against parameter types without serializing them, so callers
+//! can report all input errors at once instead of failing at the first encoding error. Limits
+//! depending on ABI version (e.g. `fixedbytes` size in ABI 2.4) are checked by the encoder only.
+
+use crate::{
+    int::IntBounds,
+    param::Param,
+    param_type::ParamType,
+    token::{Token, TokenValue, Tokenizer},
+};
+
+use ever_block::MsgAddress;
+use num_bigint::BigInt;
+use std::fmt;
+
+/// Reason of token value rejection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssueKind {
+    /// Parameter has no corresponding token
+    Missing,
+    /// Token has no corresponding parameter
+    Unexpected,
+    /// Token name differs from parameter name
+    NameMismatch { expected: String },
+    /// Value kind does not match parameter type
+    TypeMismatch { expected: ParamType },
+    /// Integer value does not fit into parameter type
+    OutOfRange { value: String },
+    /// Number of fixed array items differs from array size
+    WrongLength { expected: usize, provided: usize },
+    /// Map key can not be represented as a value of key type
+    InvalidMapKey { key: String },
+    /// Address kind is not allowed at this position
+    AddressKind { address: String },
+}
+
+/// Token value which can not be encoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Value path: parameter name with tuple components and collection items separated by `.`
+    pub path: String,
+    pub kind: ValidationIssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: ", self.path)?;
+        match &self.kind {
+            ValidationIssueKind::Missing => write!(f, "value is missing"),
+            ValidationIssueKind::Unexpected => write!(f, "unexpected value"),
+            ValidationIssueKind::NameMismatch { expected } => {
+                write!(f, "name does not match parameter `{}`", expected)
+            }
+            ValidationIssueKind::TypeMismatch { expected } => {
+                write!(f, "value is not of type {}", expected)
+            }
+            ValidationIssueKind::OutOfRange { value } => {
+                write!(f, "{} is out of type range", value)
+            }
+            ValidationIssueKind::WrongLength { expected, provided } => {
+                write!(f, "expected {} items, provided {}", expected, provided)
+            }
+            ValidationIssueKind::InvalidMapKey { key } => write!(f, "invalid map key {}", key),
+            ValidationIssueKind::AddressKind { address } => {
+                write!(f, "only std non-anycast address is allowed, provided {}", address)
+            }
+        }
+    }
+}
+
+/// Checks tokens against parameters and returns all found issues. Empty result means values
+/// have proper types and fit into them.
+pub fn validate_tokens(params: &[Param], tokens: &[Token]) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    validate_params(params, tokens, "", &mut issues);
+    issues
+}
+
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+fn issue(issues: &mut Vec<ValidationIssue>, path: &str, kind: ValidationIssueKind) {
+    issues.push(ValidationIssue {
+        path: path.to_owned(),
+        kind,
+    });
+}
+
+fn validate_params(
+    params: &[Param],
+    tokens: &[Token],
+    prefix: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for (i, param) in params.iter().enumerate() {
+        let path = join_path(prefix, &param.name);
+        match tokens.get(i) {
+            Some(token) => {
+                if token.name != param.name {
+                    let expected = param.name.clone();
+                    let path = join_path(prefix, &token.name);
+                    issue(issues, &path, ValidationIssueKind::NameMismatch { expected });
+                }
+                validate_value(&param.kind, &token.value, &path, issues);
+            }
+            None => issue(issues, &path, ValidationIssueKind::Missing),
+        }
+    }
+    for token in tokens.iter().skip(params.len()) {
+        let path = join_path(prefix, &token.name);
+        issue(issues, &path, ValidationIssueKind::Unexpected);
+    }
+}
+
+fn check_range(
+    param_type: &ParamType,
+    number: &BigInt,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let in_range = IntBounds::for_type(param_type)
+        .map(|bounds| bounds.contains(number))
+        .unwrap_or(true);
+    if !in_range {
+        let value = number.to_string();
+        issue(issues, path, ValidationIssueKind::OutOfRange { value });
+    }
+}
+
+fn validate_items(
+    item_type: &ParamType,
+    items: &[TokenValue],
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for (i, item) in items.iter().enumerate() {
+        validate_value(item_type, item, &join_path(path, &i.to_string()), issues);
+    }
+}
+
+fn validate_map_key(
+    key_type: &ParamType,
+    key: &str,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let key_value = match key_type {
+        ParamType::Int(_) | ParamType::Uint(_) | ParamType::Address => {
+            Tokenizer::tokenize_parameter(key_type, &key.into(), "map key").ok()
+        }
+        _ => None,
+    };
+    match key_value {
+        Some(TokenValue::Address(MsgAddress::AddrStd(std))) if std.anycast.is_none() => {}
+        Some(TokenValue::Address(address)) => {
+            let address = address.to_string();
+            issue(issues, path, ValidationIssueKind::AddressKind { address })
+        }
+        Some(_) => {}
+        None => {
+            let key = key.to_owned();
+            issue(issues, path, ValidationIssueKind::InvalidMapKey { key })
+        }
+    }
+}
+
+fn validate_value(
+    param_type: &ParamType,
+    value: &TokenValue,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match (param_type, value) {
+        (ParamType::Uint(size), TokenValue::Uint(uint)) if *size == uint.size => {
+            check_range(param_type, &BigInt::from(uint.number.clone()), path, issues)
+        }
+        (ParamType::Int(size), TokenValue::Int(int)) if *size == int.size => {
+            check_range(param_type, &int.number, path, issues)
+        }
+        (ParamType::VarUint(size), TokenValue::VarUint(value_size, number))
+            if size == value_size =>
+        {
+            check_range(param_type, &BigInt::from(number.clone()), path, issues)
+        }
+        (ParamType::VarInt(size), TokenValue::VarInt(value_size, number))
+            if size == value_size =>
+        {
+            check_range(param_type, number, path, issues)
+        }
+        (ParamType::Tuple(params), TokenValue::Tuple(tokens)) => {
+            validate_params(params, tokens, path, issues)
+        }
+        (ParamType::Array(item_type), TokenValue::Array(value_type, items))
+            if item_type.as_ref() == value_type =>
+        {
+            validate_items(item_type, items, path, issues)
+        }
+        (ParamType::FixedArray(item_type, size), TokenValue::FixedArray(value_type, items))
+            if item_type.as_ref() == value_type =>
+        {
+            if *size != items.len() {
+                let kind = ValidationIssueKind::WrongLength {
+                    expected: *size,
+                    provided: items.len(),
+                };
+                issue(issues, path, kind);
+            }
+            validate_items(item_type, items, path, issues)
+        }
+        (ParamType::Map(key_type, value_type), TokenValue::Map(map_key, map_value, map))
+            if key_type.as_ref() == map_key && value_type.as_ref() == map_value =>
+        {
+            for (key, value) in map {
+                let path = join_path(path, key);
+                validate_map_key(key_type, key, &path, issues);
+                validate_value(value_type, value, &path, issues);
+            }
+        }
+        (ParamType::Optional(inner_type), TokenValue::Optional(value_type, value))
+            if inner_type.as_ref() == value_type =>
+        {
+            if let Some(value) = value {
+                validate_value(inner_type, value, path, issues)
+            }
+        }
+        (ParamType::Ref(inner_type), TokenValue::Ref(value)) => {
+            validate_value(inner_type, value, path, issues)
+        }
+        (ParamType::FixedBytes(size), TokenValue::FixedBytes(data)) if *size != data.len() => {
+            let kind = ValidationIssueKind::WrongLength {
+                expected: *size,
+                provided: data.len(),
+            };
+            issue(issues, path, kind);
+        }
+        (ParamType::Bool, TokenValue::Bool(_))
+        | (ParamType::Cell, TokenValue::Cell(_))
+        | (ParamType::Slice, TokenValue::Slice(_))
+        | (ParamType::Address, TokenValue::Address(_))
+        | (ParamType::Bytes, TokenValue::Bytes(_))
+        | (ParamType::FixedBytes(_), TokenValue::FixedBytes(_))
+        | (ParamType::String, TokenValue::String(_))
+        | (ParamType::Token, TokenValue::Token(_))
+        | (ParamType::Time, TokenValue::Time(_))
+        | (ParamType::Expire, TokenValue::Expire(_))
+        | (ParamType::PublicKey, TokenValue::PublicKey(_)) => {}
+        _ => {
+            let expected = param_type.clone();
+            issue(issues, path, ValidationIssueKind::TypeMismatch { expected });
+        }
+    }
+}
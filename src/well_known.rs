@@ -0,0 +1,154 @@
+/*
+* Copyright (C) 2019-2023 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABIs of standard network contracts.
+//!
+//! Embedded ABIs are compiled in with `well_known` feature, so downstream tools share the same
+//! definitions instead of vendoring their own copies. Elector and config contracts are not ABI
+//! contracts, their internal messages are described as functions with operation codes as IDs.
+//! DeNS root ABI covers domain resolution only.
+
+use crate::contract::Contract;
+
+use std::sync::OnceLock;
+
+/// Elector messages: stake submission and recovery requests and elector answers to them
+pub const ELECTOR_ABI: &str = r#"{
+    "version": "2.4",
+    "header": [],
+    "functions": [
+        {
+            "name": "process_new_stake",
+            "id": "0x4E73744B",
+            "inputs": [
+                {"name": "query_id", "type": "uint64"},
+                {"name": "validator_pubkey", "type": "uint256"},
+                {"name": "stake_at", "type": "uint32"},
+                {"name": "max_factor", "type": "uint32"},
+                {"name": "adnl_addr", "type": "uint256"},
+                {"name": "signature", "type": "ref(fixedbytes64)"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "new_stake_confirmation",
+            "id": "0xF374484C",
+            "inputs": [
+                {"name": "query_id", "type": "uint64"},
+                {"name": "comment", "type": "uint32"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "new_stake_rejection",
+            "id": "0xEE6F454C",
+            "inputs": [
+                {"name": "query_id", "type": "uint64"},
+                {"name": "reason", "type": "uint32"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "recover_stake",
+            "id": "0x47657424",
+            "inputs": [{"name": "query_id", "type": "uint64"}],
+            "outputs": []
+        },
+        {
+            "name": "recover_stake_ok",
+            "id": "0xF96F7324",
+            "inputs": [{"name": "query_id", "type": "uint64"}],
+            "outputs": []
+        },
+        {
+            "name": "recover_stake_error",
+            "id": "0xFFFFFFFE",
+            "inputs": [
+                {"name": "query_id", "type": "uint64"},
+                {"name": "op", "type": "uint32"}
+            ],
+            "outputs": []
+        }
+    ]
+}"#;
+
+/// Config contract messages: configuration change proposals and answers to them
+pub const CONFIG_ABI: &str = r#"{
+    "version": "2.4",
+    "header": [],
+    "functions": [
+        {
+            "name": "new_proposal",
+            "id": "0x6E565052",
+            "inputs": [
+                {"name": "query_id", "type": "uint64"},
+                {"name": "expire_at", "type": "uint32"},
+                {"name": "proposal", "type": "cell"},
+                {"name": "critical", "type": "bool"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "new_proposal_accepted",
+            "id": "0xEE764F4B",
+            "inputs": [{"name": "query_id", "type": "uint64"}],
+            "outputs": []
+        }
+    ]
+}"#;
+
+/// DeNS root contract resolving domain names into certificate addresses
+pub const DENS_ROOT_ABI: &str = r#"{
+    "version": "2.2",
+    "header": ["pubkey", "time", "expire"],
+    "functions": [
+        {
+            "name": "resolve",
+            "inputs": [
+                {"name": "answerId", "type": "uint32"},
+                {"name": "path", "type": "string"}
+            ],
+            "outputs": [{"name": "certificate", "type": "address"}]
+        }
+    ]
+}"#;
+
+/// Returns config contract ABI
+pub fn config_contract() -> &'static Contract {
+    static CONTRACT: OnceLock<Contract> = OnceLock::new();
+    load(&CONTRACT, CONFIG_ABI, "config")
+}
+
+/// Returns elector contract ABI
+pub fn elector_contract() -> &'static Contract {
+    static CONTRACT: OnceLock<Contract> = OnceLock::new();
+    load(&CONTRACT, ELECTOR_ABI, "elector")
+}
+
+/// Returns DeNS root contract ABI
+pub fn dens_root_contract() -> &'static Contract {
+    static CONTRACT: OnceLock<Contract> = OnceLock::new();
+    load(&CONTRACT, DENS_ROOT_ABI, "dens_root")
+}
+
+fn load(contract: &'static OnceLock<Contract>, abi: &str, name: &str) -> &'static Contract {
+    contract.get_or_init(|| {
+        Contract::load(abi.as_bytes())
+            .expect("embedded ABI is valid")
+            .with_name(name)
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/test_well_known.rs"]
+mod tests;